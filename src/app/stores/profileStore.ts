@@ -696,8 +696,16 @@ function createProfileStore() {
 
           let numericStats = null;
           let categoricalStats = null;
+          let booleanStats = null;
           let histogram = null;
-          let inferredType: 'Integer' | 'Numeric' | 'String' | 'Boolean' | 'Date' = 'String';
+          let inferredType:
+            | 'Integer'
+            | 'Numeric'
+            | 'String'
+            | 'Boolean'
+            | 'Date'
+            | 'Timestamp'
+            | 'Time' = 'String';
 
           if (isNumeric) {
             inferredType = colType.includes('INT') ? 'Integer' : 'Numeric';
@@ -801,8 +809,12 @@ function createProfileStore() {
             // Categorical stats - get top values
             if (colType.includes('BOOL')) {
               inferredType = 'Boolean';
-            } else if (colType.includes('DATE') || colType.includes('TIME')) {
+            } else if (colType.includes('TIMESTAMP') || colType.includes('DATETIME')) {
+              inferredType = 'Timestamp';
+            } else if (colType.includes('DATE')) {
               inferredType = 'Date';
+            } else if (colType.includes('TIME')) {
+              inferredType = 'Time';
             }
 
             const topValuesQuery = `
@@ -819,15 +831,31 @@ function createProfileStore() {
             const topValues = await executeQuery<{ value: string; count: string }>(topValuesQuery);
             const totalNonNull = totalCount - missingCount;
 
+            const topValueEntries = topValues.rows.map((row) => ({
+              value: row.value || '',
+              count: parseInt(row.count || '0', 10),
+              percentage:
+                totalNonNull > 0 ? (parseInt(row.count || '0', 10) / totalNonNull) * 100 : 0,
+            }));
             categoricalStats = {
-              top_values: topValues.rows.map((row) => ({
-                value: row.value || '',
-                count: parseInt(row.count || '0', 10),
-                percentage:
-                  totalNonNull > 0 ? (parseInt(row.count || '0', 10) / totalNonNull) * 100 : 0,
-              })),
+              top_values: topValueEntries,
               unique_count: distinctCount,
             };
+
+            if (inferredType === 'Boolean') {
+              // DuckDB casts booleans to 'true' and 'false'
+              const countOf = (value: string) =>
+                topValueEntries.find((entry) => entry.value === value)?.count ?? 0;
+              const trueCount = countOf('true');
+              const falseCount = countOf('false');
+              booleanStats = {
+                true_count: trueCount,
+                false_count: falseCount,
+                true_ratio: totalNonNull > 0 ? trueCount / totalNonNull : 0,
+                spellings: topValueEntries,
+                candidate_flag: false,
+              };
+            }
           }
 
           // Build column profile
@@ -841,15 +869,15 @@ function createProfileStore() {
             },
             numeric_stats: numericStats,
             categorical_stats: categoricalStats,
+            boolean_stats: booleanStats,
             histogram: histogram,
             min_length: null,
             max_length: null,
             notes: ['Profiled via DuckDB (large file mode)'],
             quality_metrics: null,
+            datetime_format: null,
             integer_count: inferredType === 'Integer' ? totalCount - missingCount : 0,
             numeric_count: inferredType === 'Numeric' ? totalCount - missingCount : 0,
-            boolean_count: inferredType === 'Boolean' ? totalCount - missingCount : 0,
-            date_count: inferredType === 'Date' ? totalCount - missingCount : 0,
             total_valid: totalCount - missingCount,
             sample_values: [],
             missing_rows: [],
@@ -16,11 +16,13 @@ export type { BaseStats as RawBaseStats } from '../../wasm/bindings/BaseStats';
 export type { CategoricalStats as RawCategoricalStats } from '../../wasm/bindings/CategoricalStats';
 export type { ColumnProfile as RawColumnProfile } from '../../wasm/bindings/ColumnProfile';
 export type { FreqEntry as RawFreqEntry } from '../../wasm/bindings/FreqEntry';
+export type { BooleanStats as RawBooleanStats } from '../../wasm/bindings/BooleanStats';
 export type { Histogram as RawHistogram } from '../../wasm/bindings/Histogram';
 export type { HistogramBin as RawHistogramBin } from '../../wasm/bindings/HistogramBin';
 export type { NumericStats as RawNumericStats } from '../../wasm/bindings/NumericStats';
 export type { ProfilerResult as RawProfilerResult } from '../../wasm/bindings/ProfilerResult';
 export type { DataType as RawDataType } from '../../wasm/bindings/DataType';
+export type { DatetimeFormat as RawDatetimeFormat } from '../../wasm/bindings/DatetimeFormat';
 
 // Re-export types that don't have bigint fields and don't need adaptation
 export type { ColumnQualityMetrics } from '../../wasm/bindings/ColumnQualityMetrics';
@@ -42,6 +44,8 @@ export type DataType =
   | 'String'
   | 'Boolean'
   | 'Date'
+  | 'Timestamp'
+  | 'Time'
   | 'Null'
   // Additional variants used in the existing codebase
   | 'Mixed' // Used when column has multiple data types
@@ -51,7 +55,9 @@ export type DataType =
   | 'integer'
   | 'numeric'
   | 'boolean'
-  | 'date';
+  | 'date'
+  | 'timestamp'
+  | 'time';
 
 /**
  * Adapted types with `number` instead of `bigint` for backwards compatibility.
@@ -82,6 +88,14 @@ export interface CategoricalStats {
   unique_count: number;
 }
 
+export interface BooleanStats {
+  true_count: number;
+  false_count: number;
+  true_ratio: number;
+  spellings: Array<FreqEntry>;
+  candidate_flag: boolean;
+}
+
 export interface NumericStats {
   min: number;
   max: number;
@@ -100,6 +114,13 @@ export interface NumericStats {
   p99: number;
 }
 
+export interface DatetimeFormat {
+  format: string;
+  matches: number;
+  ambiguous_with: string | null;
+  with_timezone: number;
+}
+
 export interface BaseStats {
   count: number;
   missing: number;
@@ -112,15 +133,15 @@ export interface ColumnProfile {
   base_stats: BaseStats;
   numeric_stats: NumericStats | null;
   categorical_stats: CategoricalStats | null;
+  boolean_stats: BooleanStats | null;
   histogram: Histogram | null;
   min_length: number | null;
   max_length: number | null;
   notes: Array<string>;
   quality_metrics: ColumnQualityMetrics | null;
+  datetime_format: DatetimeFormat | null;
   integer_count: number;
   numeric_count: number;
-  boolean_count: number;
-  date_count: number;
   total_valid: number;
   sample_values: Array<string>;
   missing_rows: Array<number>;
@@ -19,15 +19,15 @@ function createColumnProfile(overrides: Partial<ColumnProfile> = {}): ColumnProf
     },
     numeric_stats: null,
     categorical_stats: null,
+    boolean_stats: null,
     histogram: null,
     min_length: null,
     max_length: null,
     notes: [],
     quality_metrics: null,
+    datetime_format: null,
     integer_count: 0,
     numeric_count: 0,
-    boolean_count: 0,
-    date_count: 0,
     total_valid: 100,
     sample_values: [],
     missing_rows: [],
@@ -112,6 +112,40 @@ describe('exportGreatExpectations', () => {
       ).toBeDefined();
     });
 
+    it('should map date, timestamp and time columns to GX types', () => {
+      const results = createProfileResult([
+        {
+          name: 'day',
+          base_stats: { count: 100, missing: 0, distinct_estimate: 30, inferred_type: 'Date' },
+        },
+        {
+          name: 'created_at',
+          base_stats: {
+            count: 100,
+            missing: 0,
+            distinct_estimate: 100,
+            inferred_type: 'Timestamp',
+          },
+        },
+        {
+          name: 'opens_at',
+          base_stats: { count: 100, missing: 0, distinct_estimate: 12, inferred_type: 'Time' },
+        },
+      ]);
+      const suite = generateGXSuite(results, 'test.csv');
+
+      const typeOf = (column: string) =>
+        suite.expectations.find(
+          (e) =>
+            e.expectation_type === 'expect_column_values_to_be_of_type' &&
+            e.kwargs.column === column,
+        )?.kwargs.type_;
+
+      expect(typeOf('day')).toBe('DATE');
+      expect(typeOf('created_at')).toBe('DATETIME');
+      expect(typeOf('opens_at')).toBe('TIME');
+    });
+
     it('should skip type expectations for Mixed type', () => {
       const results = createProfileResult([
         {
@@ -63,6 +63,8 @@ function mapTypeToGX(dataCertType: string): string | null {
     Boolean: 'BOOLEAN',
     Date: 'DATE',
     DateTime: 'DATETIME',
+    Timestamp: 'DATETIME',
+    Time: 'TIME',
   };
   return typeMap[dataCertType] ?? null;
 }
@@ -12,7 +12,8 @@ import { ProfileResult } from '../stores/profileStore';
  * - String -> "string" with minLength/maxLength
  * - Boolean -> "boolean"
  * - Date -> "string" with format: "date"
- * - DateTime -> "string" with format: "date-time"
+ * - DateTime/Timestamp -> "string" with format: "date-time"
+ * - Time -> "string" with format: "time"
  * - Mixed -> ["string", "number", "boolean", "null"]
  * - Null/Empty -> "null"
  */
@@ -51,6 +52,8 @@ function mapDataCertTypeToJsonSchema(inferredType: string): {
     Boolean: { type: 'boolean' },
     Date: { type: 'string', format: 'date' },
     DateTime: { type: 'string', format: 'date-time' },
+    Timestamp: { type: 'string', format: 'date-time' },
+    Time: { type: 'string', format: 'time' },
     Mixed: { type: ['string', 'number', 'boolean', 'null'] },
     Null: { type: 'null' },
     Empty: { type: 'null' },
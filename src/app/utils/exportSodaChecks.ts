@@ -105,6 +105,8 @@ function mapToSodaType(type: string): string | null {
   if (lower.includes('numeric') || lower.includes('float')) return 'decimal';
   if (lower.includes('string') || lower.includes('text')) return 'text';
   if (lower.includes('boolean')) return 'boolean';
+  if (lower.includes('timestamp')) return 'timestamp';
   if (lower.includes('date')) return 'date';
+  if (lower.includes('time')) return 'time';
   return null;
 }
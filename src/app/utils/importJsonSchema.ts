@@ -182,14 +182,20 @@ function validateType(
 
   const allowedTypes = Array.isArray(schemaType) ? schemaType : [schemaType];
 
-  // DataCert Types: String, Integer, Float, Boolean, Date
+  // DataCert Types: String, Integer, Float, Boolean, Date, Timestamp, Time
   // JSON Schema Types: string, number, integer, boolean, object, array, null
 
   let isMatch = false;
 
   for (const type of allowedTypes) {
     if (type === 'string') {
-      if (inferredType === 'string' || inferredType === 'date' || inferredType === 'datetime')
+      if (
+        inferredType === 'string' ||
+        inferredType === 'date' ||
+        inferredType === 'datetime' ||
+        inferredType === 'timestamp' ||
+        inferredType === 'time'
+      )
         isMatch = true;
     } else if (type === 'integer') {
       if (inferredType === 'integer') isMatch = true;
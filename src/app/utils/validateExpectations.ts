@@ -298,7 +298,8 @@ function evaluateGXExpectation(
       STRING: ['String'],
       BOOLEAN: ['Boolean'],
       DATE: ['Date'],
-      DATETIME: ['DateTime'],
+      DATETIME: ['DateTime', 'Timestamp'],
+      TIME: ['Time'],
     };
 
     const acceptableTypes = typeMap[expectedType] || [];
@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Whether a statistic is exact and, if not, how far off it may be.
+ */
+export type Accuracy = { exact: boolean, 
+/**
+ * For approximate statistics: the relative standard error of a count,
+ * or the 95% bound on the rank error of a quantile or histogram
+ */
+error_bound: number | null, };
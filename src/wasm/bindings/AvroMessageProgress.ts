@@ -0,0 +1,12 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { AvroSchemaIdCount } from "./AvroSchemaIdCount";
+import type { AvroSkippedMessage } from "./AvroSkippedMessage";
+
+/**
+ * Progress of a message dump read, reported per chunk.
+ */
+export type AvroMessageProgress = { bytes_consumed: bigint, messages_read: bigint, messages_skipped: bigint, schema_ids: Array<AvroSchemaIdCount>, 
+/**
+ * First skipped messages, capped at 1000
+ */
+skipped_messages: Array<AvroSkippedMessage>, };
@@ -0,0 +1,18 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * How often a schema id (or single-object fingerprint) occurred in a message dump.
+ */
+export type AvroSchemaIdCount = { 
+/**
+ * Confluent schema id; for single-object messages, the id the fingerprint is registered under
+ */
+schema_id: number | null, 
+/**
+ * Rabin fingerprint (hex) for single-object encoded messages
+ */
+fingerprint: string | null, count: bigint, 
+/**
+ * False if no registered schema matched; those messages are skipped
+ */
+registered: boolean, };
@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A data block that was dropped because it could not be framed or decoded.
+ */
+export type AvroSkippedBlock = { block_index: bigint, 
+/**
+ * File offset where the block (or the unreadable region) starts
+ */
+byte_offset: bigint, reason: string, };
@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A message that could not be decoded.
+ */
+export type AvroSkippedMessage = { message_index: bigint, 
+/**
+ * File offset of the message's length prefix
+ */
+byte_offset: bigint, reason: string, };
@@ -0,0 +1,15 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { AvroSkippedBlock } from "./AvroSkippedBlock";
+
+/**
+ * Progress of an incremental object container read, reported per chunk.
+ */
+export type AvroStreamProgress = { header_parsed: boolean, 
+/**
+ * Bytes of the file handed to the profiler so far
+ */
+bytes_received: bigint, 
+/**
+ * Bytes fully consumed as header or data blocks
+ */
+bytes_consumed: bigint, blocks_read: bigint, records_read: bigint, skipped_blocks: Array<AvroSkippedBlock>, };
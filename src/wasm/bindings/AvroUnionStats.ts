@@ -0,0 +1,19 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { AvroBranchCount } from "./AvroBranchCount";
+
+/**
+ * Null-vs-value counts for a union field in the writer schema.
+ */
+export type AvroUnionStats = { 
+/**
+ * Flattened column the union resolves into (the record prefix for nullable records)
+ */
+column: string, 
+/**
+ * Declared branch types, in schema order
+ */
+branches: Array<string>, null_count: bigint, value_count: bigint, 
+/**
+ * Per-branch counts, only populated for unions with more than one non-null branch
+ */
+branch_counts: Array<AvroBranchCount>, };
@@ -0,0 +1,13 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { FreqEntry } from "./FreqEntry";
+
+export type BooleanStats = { true_count: bigint, false_count: bigint, true_ratio: number, 
+/**
+ * Raw values seen for each side, e.g. "Y", "yes", "1"
+ */
+spellings: Array<FreqEntry>, 
+/**
+ * The column was inferred as Integer but only holds values from the
+ * boolean vocabularies (`0` and `1` by default)
+ */
+candidate_flag: boolean, };
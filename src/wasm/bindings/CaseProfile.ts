@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Values by letter case; values without cased letters are `uncased`.
+ */
+export type CaseProfile = { upper: bigint, lower: bigint, 
+/**
+ * Every word capitalized, e.g. "New York"
+ */
+title: bigint, mixed: bigint, uncased: bigint, };
@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Share of all characters (of trimmed values) in each class. A character
+ * can be in several classes, e.g. "é" is both alphabetic and non-ASCII.
+ */
+export type CharClassRatios = { alpha: number, digit: number, 
+/**
+ * Punctuation and symbols
+ */
+punctuation: number, whitespace: number, non_ascii: number, };
@@ -0,0 +1,19 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Accuracy } from "./Accuracy";
+
+/**
+ * Accuracy of each estimated statistic of a column.
+ */
+export type ColumnAccuracy = { distinct_estimate: Accuracy, 
+/**
+ * `categorical_stats.top_values`; approximate once `max_categorical_keys` overflowed
+ */
+top_values: Accuracy, 
+/**
+ * `numeric_stats` median and percentiles
+ */
+quantiles: Accuracy | null, histogram: Accuracy | null, 
+/**
+ * `outlier_stats` counts and `outlier_rows`, given the fences
+ */
+outliers: Accuracy | null, };
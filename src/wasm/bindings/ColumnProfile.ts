@@ -1,8 +1,26 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
 import type { BaseStats } from "./BaseStats";
+import type { BooleanStats } from "./BooleanStats";
 import type { CategoricalStats } from "./CategoricalStats";
+import type { ColumnAccuracy } from "./ColumnAccuracy";
 import type { ColumnQualityMetrics } from "./ColumnQualityMetrics";
+import type { DateRange } from "./DateRange";
+import type { DatetimeFormat } from "./DatetimeFormat";
+import type { DecimalStats } from "./DecimalStats";
 import type { Histogram } from "./Histogram";
+import type { LogicalType } from "./LogicalType";
 import type { NumericStats } from "./NumericStats";
+import type { OutlierStats } from "./OutlierStats";
+import type { ShapeStats } from "./ShapeStats";
+import type { StringStats } from "./StringStats";
+import type { TemporalStats } from "./TemporalStats";
 
-export type ColumnProfile = { name: string, base_stats: BaseStats, numeric_stats: NumericStats | null, categorical_stats: CategoricalStats | null, histogram: Histogram | null, min_length: number | null, max_length: number | null, notes: Array<string>, quality_metrics: ColumnQualityMetrics | null, integer_count: bigint, numeric_count: bigint, boolean_count: bigint, date_count: bigint, total_valid: bigint, sample_values: Array<string>, missing_rows: Array<number>, pii_rows: Array<number>, outlier_rows: Array<number>, };
+export type ColumnProfile = { name: string, base_stats: BaseStats, numeric_stats: NumericStats | null, categorical_stats: CategoricalStats | null, shape_stats: ShapeStats | null, boolean_stats: BooleanStats | null, histogram: Histogram | null, outlier_stats: OutlierStats | null, min_length: number | null, max_length: number | null, notes: Array<string>, quality_metrics: ColumnQualityMetrics | null, logical_type: LogicalType | null, date_range: DateRange | null, decimal_stats: DecimalStats | null, 
+/**
+ * Detected format of a date, time or timestamp column
+ */
+datetime_format: DatetimeFormat | null, temporal_stats: TemporalStats | null, 
+/**
+ * Which of the statistics above are exact
+ */
+accuracy: ColumnAccuracy | null, string_stats: StringStats | null, integer_count: bigint, numeric_count: bigint, total_valid: bigint, sample_values: Array<string>, missing_rows: Array<number>, pii_rows: Array<number>, outlier_rows: Array<number>, };
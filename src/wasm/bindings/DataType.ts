@@ -1,3 +1,3 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
 
-export type DataType = "Integer" | "Numeric" | "String" | "Boolean" | "Date" | "Null";
+export type DataType = "Integer" | "Numeric" | "String" | "Boolean" | "Date" | "Timestamp" | "Time" | "Null";
@@ -0,0 +1,14 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A column of the reconciled dataset schema.
+ */
+export type DatasetColumnInfo = { name: string, 
+/**
+ * True for virtual columns built from Hive partition keys
+ */
+partition_key: boolean, 
+/**
+ * Number of files containing the column (or its partition key)
+ */
+files_present: number, declared_type: string | null, };
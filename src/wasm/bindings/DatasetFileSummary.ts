@@ -0,0 +1,28 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { DatasetTypeChange } from "./DatasetTypeChange";
+
+/**
+ * What one file contributed to the dataset.
+ */
+export type DatasetFileSummary = { path: string, rows: bigint, 
+/**
+ * Hive partition keys parsed from the path; None for `__HIVE_DEFAULT_PARTITION__`
+ */
+partitions: { [key in string]?: string | null }, 
+/**
+ * Columns this file was the first to contain
+ */
+new_columns: Array<string>, 
+/**
+ * Dataset columns absent from this file, profiled as missing for its rows
+ */
+missing_columns: Array<string>, 
+/**
+ * Declared types that differ from the first file declaring the column
+ * (Parquet and Arrow files only; other formats carry no declared types)
+ */
+type_changes: Array<DatasetTypeChange>, 
+/**
+ * Read error; rows read before it are still profiled
+ */
+error: string | null, };
@@ -0,0 +1,8 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { DatasetColumnInfo } from "./DatasetColumnInfo";
+import type { DatasetFileSummary } from "./DatasetFileSummary";
+
+/**
+ * Dataset-level metadata attached to the `ProfilerResult`.
+ */
+export type DatasetMetadata = { format: string, columns: Array<DatasetColumnInfo>, files: Array<DatasetFileSummary>, };
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A column whose declared type differs from the one in an earlier file.
+ */
+export type DatasetTypeChange = { column: string, expected: string, found: string, };
@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Earliest and latest value of a date or timestamp column.
+ * Values are ISO 8601 strings in a single fixed format, so they order lexically.
+ */
+export type DateRange = { min: string, max: string, };
@@ -0,0 +1,24 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * The format detected for a date, time or timestamp column.
+ */
+export type DatetimeFormat = { 
+/**
+ * chrono (strftime) pattern matching the most values, or
+ * "epoch_seconds" / "epoch_millis"
+ */
+format: string, 
+/**
+ * Values matching `format`
+ */
+matches: bigint, 
+/**
+ * Another format matching just as many values, e.g. "%d/%m/%Y" next to
+ * "%m/%d/%Y" when no day exceeds 12
+ */
+ambiguous_with: string | null, 
+/**
+ * Values carrying a UTC offset or zone designator
+ */
+with_timezone: bigint, };
@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Exact statistics for a fixed-scale decimal column. `min` and `max` keep the
+ * full declared precision instead of going through f64.
+ */
+export type DecimalStats = { precision: number, scale: number, min: string | null, max: string | null, 
+/**
+ * Widest integer part seen, to compare against `precision - scale` headroom
+ */
+max_integer_digits: number, };
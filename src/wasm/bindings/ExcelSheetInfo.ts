@@ -0,0 +1,22 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Layout of a single worksheet as detected before profiling.
+ */
+export type ExcelSheetInfo = { name: string, hidden: boolean, 
+/**
+ * Bounding box of non-empty cells in A1 notation, e.g. "B3:F120"
+ */
+used_range: string | null, 
+/**
+ * 1-based sheet row holding the column names; None if the sheet has no header row
+ */
+header_row: number | null, data_rows: bigint, columns: number, 
+/**
+ * Fully blank rows inside the used range, which are not profiled
+ */
+blank_rows: bigint, 
+/**
+ * Cells holding Excel errors such as #N/A or #DIV/0!, profiled as missing
+ */
+error_cells: bigint, };
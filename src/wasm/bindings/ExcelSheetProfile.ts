@@ -0,0 +1,8 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ExcelSheetInfo } from "./ExcelSheetInfo";
+import type { ProfilerResult } from "./ProfilerResult";
+
+/**
+ * Profile of one worksheet.
+ */
+export type ExcelSheetProfile = { sheet: ExcelSheetInfo, profile: ProfilerResult, };
@@ -0,0 +1,27 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Result of format sniffing.
+ */
+export type FormatDetection = { 
+/**
+ * "csv", "tsv", "json", "jsonl", "xml", "parquet", "avro", "arrow",
+ * "orc", "xlsx", "xls", "ods", "sqlite", "zip" or "unknown"
+ */
+format: string, 
+/**
+ * 1.0 for magic bytes; lower for content heuristics
+ */
+confidence: number, 
+/**
+ * "gzip" when the format was detected inside a gzip stream
+ */
+compression: string | null, 
+/**
+ * Field delimiter for "csv" and "tsv"
+ */
+delimiter: string | null, 
+/**
+ * What the decision was based on, e.g. "PAR1 magic bytes"
+ */
+reason: string, };
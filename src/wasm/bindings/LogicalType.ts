@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Semantic type declared by a source schema (e.g. Avro logical types), as
+ * opposed to the type inferred from the values themselves.
+ */
+export type LogicalType = { "kind": "decimal", precision: number, scale: number, } | { "kind": "date" } | { "kind": "time-millis" } | { "kind": "time-micros" } | { "kind": "timestamp-millis" } | { "kind": "timestamp-micros" } | { "kind": "local-timestamp-millis" } | { "kind": "local-timestamp-micros" } | { "kind": "uuid" } | { "kind": "duration" };
@@ -0,0 +1,15 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Statistics an ORC writer recorded for one column, from the file footer or
+ * the per-stripe metadata section. Values are rendered like profiled values.
+ */
+export type OrcColumnStatistics = { 
+/**
+ * Flattened column name, or the dotted path of a nested struct field
+ */
+column: string, orc_type: string, number_of_values: bigint | null, has_null: boolean | null, minimum: string | null, maximum: string | null, sum: string | null, 
+/**
+ * Boolean columns: number of true values
+ */
+true_count: bigint | null, };
@@ -0,0 +1,8 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { OrcColumnStatistics } from "./OrcColumnStatistics";
+import type { OrcStripeSummary } from "./OrcStripeSummary";
+
+/**
+ * File-level ORC metadata attached to the `ProfilerResult`.
+ */
+export type OrcMetadata = { compression: string, file_version: string, writer_version: number, row_index_stride: number, number_of_rows: bigint, statistics: Array<OrcColumnStatistics>, stripes: Array<OrcStripeSummary>, };
@@ -0,0 +1,4 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { OrcColumnStatistics } from "./OrcColumnStatistics";
+
+export type OrcStripeSummary = { offset: bigint, rows: bigint, data_length: bigint, statistics: Array<OrcColumnStatistics>, };
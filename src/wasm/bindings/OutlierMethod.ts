@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * How the fences beyond which values are outliers are computed.
+ */
+export type OutlierMethod = { "method": "z_score", threshold: number, } | { "method": "modified_z_score", threshold: number, } | { "method": "iqr", multiplier: number, } | { "method": "percentile", lower: number, upper: number, };
@@ -0,0 +1,13 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { OutlierMethod } from "./OutlierMethod";
+
+export type OutlierStats = { method: OutlierMethod, lower_fence: number, upper_fence: number, 
+/**
+ * Values below `lower_fence` or above `upper_fence`; estimated from the
+ * quantile sketch when a side had more outliers than `max_anomaly_rows`
+ */
+count: bigint, low_count: bigint, high_count: bigint, 
+/**
+ * `count` over the column's numeric values
+ */
+ratio: number, };
@@ -0,0 +1,95 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { OutlierMethod } from "./OutlierMethod";
+import type { SamplingMode } from "./SamplingMode";
+
+/**
+ * Tunables for column profiling. Every profiler constructor accepts one
+ * (any field may be omitted from JS to keep its default), and the
+ * `ProfilerResult` echoes the configuration that produced it.
+ */
+export type ProfilerConfig = { 
+/**
+ * Values counted as missing, compared after trimming and ignoring ASCII case
+ */
+null_tokens: Array<string>, 
+/**
+ * Distinct non-null values kept as `sample_values` for display
+ */
+max_sample_values: number, 
+/**
+ * Non-null values kept per column for PII pattern detection
+ */
+max_pii_samples: number, 
+/**
+ * Reservoir size for the numeric sample behind histograms and the MAD
+ */
+histogram_reservoir_size: number, 
+/**
+ * Accuracy of the KLL sketch behind the median and percentiles; the
+ * rank error is about 1.3% at the default 200 and shrinks roughly as 1/k
+ */
+quantile_sketch_k: number, 
+/**
+ * Distinct values counted exactly by the categorical accumulator
+ */
+max_categorical_keys: number, 
+/**
+ * Entries reported in `categorical_stats.top_values`
+ */
+top_values: number, 
+/**
+ * HyperLogLog++ precision for distinct estimates (4 to 18)
+ */
+hll_precision: number, 
+/**
+ * Cap on each of `missing_rows` and `outlier_rows`, and the extreme
+ * values kept on each side of a column to find its outliers
+ */
+max_anomaly_rows: number, 
+/**
+ * How outlier fences are computed; `outlier_stats` reports the result
+ */
+outlier_method: OutlierMethod, 
+/**
+ * Values read as `true`, compared after trimming and ignoring ASCII case
+ */
+true_tokens: Array<string>, 
+/**
+ * Values read as `false`, compared like `true_tokens`
+ */
+false_tokens: Array<string>, 
+/**
+ * Which rows are profiled; `sampling` in the result reports the coverage
+ */
+sampling: SamplingMode, 
+/**
+ * Stop reading after this many rows
+ */
+max_rows: number | null, 
+/**
+ * Stop reading after this many bytes. Input is cut at the limit and the
+ * record it splits is dropped, so the read ends on a record boundary.
+ * Applies to streaming formats; whole-file formats need their complete
+ * input.
+ */
+max_bytes: bigint | null, 
+/**
+ * Seeds all randomness (sampling, reservoirs, sketch hashing), so equal
+ * input and configuration give identical results
+ */
+seed: bigint, 
+/**
+ * Instant that `temporal_stats.future_count` is measured against, as an
+ * ISO 8601 date or timestamp. Without it `future_count` is not computed,
+ * as the clock would make results differ between runs.
+ */
+reference_time: string | null, 
+/**
+ * Count distinct values and compute quantiles from every value rather
+ * than sketches, while a column stays within `exact_memory_limit`
+ */
+exact_mode: boolean, 
+/**
+ * Bytes per column kept for `exact_mode` before falling back to sketches
+ */
+exact_memory_limit: number, };
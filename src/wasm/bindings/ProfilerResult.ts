@@ -1,5 +1,19 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { AvroSchemaIdCount } from "./AvroSchemaIdCount";
+import type { AvroUnionStats } from "./AvroUnionStats";
 import type { ColumnProfile } from "./ColumnProfile";
+import type { DatasetMetadata } from "./DatasetMetadata";
+import type { OrcMetadata } from "./OrcMetadata";
+import type { ProfilerConfig } from "./ProfilerConfig";
 import type { QualityIssue } from "./QualityIssue";
+import type { SamplingSummary } from "./SamplingSummary";
 
-export type ProfilerResult = { column_profiles: Array<ColumnProfile>, total_rows: bigint, duplicate_issues: Array<QualityIssue>, avro_schema: string | null, };
+export type ProfilerResult = { column_profiles: Array<ColumnProfile>, total_rows: bigint, duplicate_issues: Array<QualityIssue>, avro_schema: string | null, avro_union_stats: Array<AvroUnionStats> | null, avro_schema_ids: Array<AvroSchemaIdCount> | null, orc_metadata: OrcMetadata | null, dataset: DatasetMetadata | null, 
+/**
+ * The configuration this profile was produced with
+ */
+config: ProfilerConfig, 
+/**
+ * Coverage of a sampled or limited profile; None for a full read
+ */
+sampling: SamplingSummary | null, };
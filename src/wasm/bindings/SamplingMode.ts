@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Which of the rows read are profiled.
+ */
+export type SamplingMode = { "mode": "full" } | { "mode": "head", rows: number, } | { "mode": "bernoulli", rate: number, seed: bigint | null, } | { "mode": "systematic", every: number, };
@@ -0,0 +1,30 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { SamplingMode } from "./SamplingMode";
+import type { StopReason } from "./StopReason";
+
+/**
+ * How much of the input a sampled or limited profile covers.
+ */
+export type SamplingSummary = { mode: SamplingMode, 
+/**
+ * Rows the source produced before reading stopped
+ */
+rows_read: bigint, rows_profiled: bigint, bytes_read: bigint | null, 
+/**
+ * None when the whole input was read
+ */
+stop_reason: StopReason | null, 
+/**
+ * Share of the input read: 1 when complete, else bytes read over the
+ * input size when JS provided it
+ */
+fraction_read: number | null, 
+/**
+ * Rows in the whole input: exact when complete, else extrapolated from
+ * the rows parsed per byte read
+ */
+estimated_total_rows: bigint | null, 
+/**
+ * Multiply profiled counts by this to estimate counts for the whole input
+ */
+scale_factor: number | null, };
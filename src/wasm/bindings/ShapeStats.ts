@@ -0,0 +1,21 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ShapeEntry } from "./ShapeEntry";
+
+export type ShapeStats = { top_shapes: Array<ShapeEntry>, 
+/**
+ * Distinct shapes seen, up to `max_categorical_keys`
+ */
+distinct_shapes: bigint, 
+/**
+ * Shape of at least half of the values, if any
+ */
+dominant_format: string | null, dominant_ratio: number, 
+/**
+ * Values not matching the dominant format
+ */
+nonconforming_count: bigint, 
+/**
+ * Rows (1-based) not matching the dominant format, up to `max_anomaly_rows`.
+ * Rows of shapes past the `max_categorical_keys` limit are not listed.
+ */
+nonconforming_rows: Array<number>, };
@@ -0,0 +1,14 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Progress counters shared by all sources.
+ */
+export type SourceProgress = { 
+/**
+ * Bytes handed to the source so far
+ */
+bytes_received: bigint, records_read: bigint, batches_read: bigint, 
+/**
+ * Set once `finish` was called
+ */
+finished: boolean, };
@@ -0,0 +1,17 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { DataType } from "./DataType";
+import type { SqliteStorageClassCounts } from "./SqliteStorageClassCounts";
+
+/**
+ * Declared type of a column compared with what it actually holds.
+ */
+export type SqliteColumnAffinity = { column: string, declared_type: string, affinity: string, inferred_type: DataType, storage_classes: SqliteStorageClassCounts, 
+/**
+ * Values stored with a class the affinity could not coerce them to,
+ * e.g. non-numeric text in an INTEGER column
+ */
+affinity_violations: bigint, 
+/**
+ * The inferred type contradicts the declared affinity
+ */
+type_mismatch: boolean, };
@@ -0,0 +1,14 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A column as declared in the schema SQL.
+ */
+export type SqliteColumnInfo = { name: string, 
+/**
+ * Declared type exactly as written, e.g. "VARCHAR(32)"; empty if none
+ */
+declared_type: string, 
+/**
+ * Type affinity SQLite derives from the declared type
+ */
+affinity: string, primary_key: boolean, not_null: boolean, };
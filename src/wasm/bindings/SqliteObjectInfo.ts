@@ -0,0 +1,15 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { SqliteColumnInfo } from "./SqliteColumnInfo";
+
+/**
+ * A table or view from `sqlite_schema`.
+ */
+export type SqliteObjectInfo = { name: string, 
+/**
+ * "table", "view" or "virtual" (virtual tables have no stored rows)
+ */
+kind: string, sql: string | null, columns: Array<SqliteColumnInfo>, without_rowid: boolean, strict: boolean, 
+/**
+ * Stored rows; None for views and virtual tables
+ */
+row_count: bigint | null, };
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Values per storage class as actually found on disk.
+ */
+export type SqliteStorageClassCounts = { null: bigint, integer: bigint, real: bigint, text: bigint, blob: bigint, };
@@ -0,0 +1,9 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ProfilerResult } from "./ProfilerResult";
+import type { SqliteColumnAffinity } from "./SqliteColumnAffinity";
+import type { SqliteObjectInfo } from "./SqliteObjectInfo";
+
+/**
+ * Profile of one table.
+ */
+export type SqliteTableProfile = { table: SqliteObjectInfo, affinity: Array<SqliteColumnAffinity>, profile: ProfilerResult, };
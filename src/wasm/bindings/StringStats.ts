@@ -0,0 +1,28 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { CaseProfile } from "./CaseProfile";
+import type { CharClassRatios } from "./CharClassRatios";
+import type { HistogramBin } from "./HistogramBin";
+import type { ScriptCount } from "./ScriptCount";
+
+export type StringStats = { 
+/**
+ * Lengths are in characters, after trimming
+ */
+mean_length: number, median_length: number, length_histogram: Array<HistogramBin>, char_classes: CharClassRatios, 
+/**
+ * Values starting or ending with whitespace
+ */
+leading_whitespace: bigint, trailing_whitespace: bigint, case_profile: CaseProfile, 
+/**
+ * C0 and C1 control characters other than tab, newline and carriage return
+ */
+control_chars: bigint, 
+/**
+ * Invisible formatting characters: zero-width spaces and joiners,
+ * direction marks, soft hyphens and byte order marks
+ */
+non_printable_chars: bigint, 
+/**
+ * Scripts of the letters seen, most frequent first
+ */
+scripts: Array<ScriptCount>, };
@@ -0,0 +1,14 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A stretch without values between two occupied periods.
+ */
+export type TemporalGap = { 
+/**
+ * Last period with values before the gap, ISO 8601
+ */
+from: string, 
+/**
+ * First period with values after the gap, ISO 8601
+ */
+to: string, seconds: bigint, };
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * The finest unit in which the values of a column vary.
+ */
+export type TemporalGranularity = "yearly" | "monthly" | "daily" | "hourly" | "minutely" | "secondly";
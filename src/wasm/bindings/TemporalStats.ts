@@ -0,0 +1,41 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { TemporalBin } from "./TemporalBin";
+import type { TemporalGap } from "./TemporalGap";
+import type { TemporalGranularity } from "./TemporalGranularity";
+
+export type TemporalStats = { 
+/**
+ * Earliest and latest value, ISO 8601 (UTC for values with an offset)
+ */
+min: string, max: string, span_seconds: bigint, granularity: TemporalGranularity, 
+/**
+ * Counts by weekday, Monday first
+ */
+by_day_of_week: Array<bigint>, 
+/**
+ * Counts by hour of day, 0 to 23
+ */
+by_hour: Array<bigint>, 
+/**
+ * Counts by month, January first
+ */
+by_month: Array<bigint>, 
+/**
+ * "hour", "day", "month" or "year", chosen from the span
+ */
+histogram_period: string, histogram: Array<TemporalBin>, 
+/**
+ * Largest gaps longer than one `granularity` step, largest first.
+ * Measured between hour buckets, which are widened to days, months and
+ * years when the values fill too many of them
+ */
+largest_gaps: Array<TemporalGap>, 
+/**
+ * Values after `ProfilerConfig::reference_time`; None when it is not set
+ */
+future_count: bigint | null, 
+/**
+ * Values before 1900, after 2100, or a placeholder such as 1900-01-01
+ * or 1970-01-01T00:00:00
+ */
+implausible_count: bigint, };
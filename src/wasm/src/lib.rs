@@ -1,10 +1,85 @@
+/// Adds the chunked-reading methods shared by the profilers that wrap a
+/// checkpointable `ProfileDriver` in a `driver` field. Defined before the
+/// modules so `parser::avro` can use it too; Avro reports its own chunk
+/// progress, so it takes the `without_chunk_results` form.
+macro_rules! driver_methods {
+    ($profiler:ident) => {
+        #[wasm_bindgen]
+        impl $profiler {
+            /// Returns the chunk's parse result, or null once the profiler has
+            /// stopped and the chunk was ignored.
+            pub fn parse_and_profile_chunk(&mut self, chunk: &[u8]) -> Result<JsValue, JsValue> {
+                self.driver.push(chunk).map_err(|e| JsValue::from_str(&e))?;
+                serde_wasm_bindgen::to_value(&self.driver.source_mut().take_last_result()).map_err(|e| JsValue::from_str(&e.to_string()))
+            }
+
+            /// Returns a `SourceProgress`.
+            pub fn get_progress(&self) -> Result<JsValue, JsValue> {
+                serde_wasm_bindgen::to_value(&self.driver.progress()).map_err(|e| JsValue::from_str(&e.to_string()))
+            }
+        }
+
+        driver_methods!($profiler, without_chunk_results);
+    };
+    ($profiler:ident, without_chunk_results) => {
+        #[wasm_bindgen]
+        impl $profiler {
+            /// Restores a profiler from `checkpoint`. Continue by passing the input
+            /// from byte offset `get_progress().bytes_received` on.
+            pub fn from_checkpoint(checkpoint: &[u8]) -> Result<$profiler, JsValue> {
+                let driver = ProfileDriver::resume(checkpoint).map_err(|e| JsValue::from_str(&e))?;
+                Ok(Self { driver })
+            }
+
+            /// Size of the whole file in bytes, so a sampled or limited profile can
+            /// extrapolate its row count.
+            pub fn set_input_size(&mut self, bytes: f64) {
+                self.driver.set_input_size(bytes as u64);
+            }
+
+            /// True once `max_rows`, `max_bytes` or `head` sampling was satisfied;
+            /// stop reading and call `finalize`.
+            pub fn is_stopped(&self) -> bool {
+                self.driver.is_stopped()
+            }
+
+            /// Value at quantile `q` (0 to 1) of a numeric column, for percentiles
+            /// beyond those in `numeric_stats`. Also works after `finalize`.
+            pub fn quantile(&self, column: &str, q: f64) -> Option<f64> {
+                self.driver.quantile(column, q)
+            }
+
+            pub fn finalize(&mut self) -> Result<JsValue, JsValue> {
+                let stats_result = self.driver.finish().map_err(|e| JsValue::from_str(&e))?;
+                serde_wasm_bindgen::to_value(&stats_result).map_err(|e| JsValue::from_str(&e.to_string()))
+            }
+
+            /// Serializes the profiler between chunks so an interrupted run can be
+            /// resumed with `from_checkpoint`.
+            pub fn checkpoint(&self) -> Result<Vec<u8>, JsValue> {
+                self.driver.checkpoint().map_err(|e| JsValue::from_str(&e))
+            }
+
+            /// Ends the input like `finalize` but returns the partial profile, to be
+            /// combined with other workers' partials in a `ProfileMerger`.
+            pub fn finalize_partial(&mut self) -> Result<JsValue, JsValue> {
+                let partial = self.driver.finish_partial().map_err(|e| JsValue::from_str(&e))?;
+                serde_wasm_bindgen::to_value(&partial).map_err(|e| JsValue::from_str(&e.to_string()))
+            }
+        }
+    };
+}
+
 mod parser;
 mod stats;
 mod export;
 mod quality;
 
 use wasm_bindgen::prelude::*;
-use parser::{CsvParser, JsonParser, JsonFormat, JsonParserConfig, AvroProfiler};
+use parser::{CsvParser, JsonParser, JsonFormat, JsonParserConfig, AvroProfiler, XmlParser};
+use parser::{CsvSource, JsonSource, XmlSource, ProfileDriver};
+use stats::config::ProfilerConfig;
+use stats::partial::PartialProfile;
 use stats::profiler::Profiler;
 use stats::correlation::compute_correlation_matrix;
 
@@ -18,51 +93,85 @@ pub fn init() {
 
 #[wasm_bindgen]
 pub struct DataCertProfiler {
-    parser: CsvParser,
-    profiler: Option<Profiler>,
+    driver: ProfileDriver<CsvSource>,
 }
 
+driver_methods!(DataCertProfiler);
+
 #[wasm_bindgen]
 impl DataCertProfiler {
+    /// `config` is a `ProfilerConfig`; omitted fields keep their defaults.
     #[wasm_bindgen(constructor)]
-    pub fn new(delimiter: Option<u8>, has_headers: bool) -> Self {
-        Self {
-            parser: CsvParser::new(delimiter, has_headers),
-            profiler: None,
-        }
+    pub fn new(delimiter: Option<u8>, has_headers: bool, config: Option<JsValue>) -> Result<DataCertProfiler, JsValue> {
+        let config = ProfilerConfig::from_js(config).map_err(|e| JsValue::from_str(&e))?;
+        let source = CsvSource::new(CsvParser::new(delimiter, has_headers));
+        Ok(Self {
+            driver: ProfileDriver::new(source, config),
+        })
     }
 
     pub fn auto_detect_delimiter(&mut self, chunk: &[u8]) -> u8 {
         let delimiter = CsvParser::auto_detect_delimiter(chunk);
-        self.parser.set_delimiter(delimiter);
+        self.driver.source_mut().parser_mut().set_delimiter(delimiter);
         delimiter
     }
+}
 
-    pub fn parse_and_profile_chunk(&mut self, chunk: &[u8]) -> Result<JsValue, JsValue> {
-        let parse_result = self.parser.parse_chunk(chunk);
-        
-        if self.profiler.is_none() && !parse_result.headers.is_empty() {
-            self.profiler = Some(Profiler::new(parse_result.headers.clone()));
-        }
+/// Combines partial profiles from workers that each profiled one range of a
+/// file. Partials must be added in file order, so that row indices of later
+/// ranges follow those of earlier ones. For CSV, every range after the first
+/// should be profiled with the header line prepended.
+#[wasm_bindgen]
+pub struct ProfileMerger {
+    profiler: Option<Profiler>,
+}
 
-        if let Some(ref mut profiler) = self.profiler {
-            profiler.update_batch(&parse_result.rows);
-        }
+#[wasm_bindgen]
+impl ProfileMerger {
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> Self {
+        Self { profiler: None }
+    }
 
-        serde_wasm_bindgen::to_value(&parse_result).map_err(|e| JsValue::from_str(&e.to_string()))
+    pub fn add_partial(&mut self, partial: JsValue) -> Result<(), JsValue> {
+        let partial: PartialProfile = serde_wasm_bindgen::from_value(partial)
+            .map_err(|e| JsValue::from_str(&format!("Invalid partial profile: {}", e)))?;
+        self.add_partial_internal(partial).map_err(|e| JsValue::from_str(&e))
+    }
+
+    /// Returns the merged partial profile, for merging in several stages.
+    pub fn get_partial(&self) -> Result<JsValue, JsValue> {
+        let profiler = self.profiler.as_ref().ok_or_else(|| JsValue::from_str("No partial profiles were added"))?;
+        serde_wasm_bindgen::to_value(&profiler.to_partial()).map_err(|e| JsValue::from_str(&e.to_string()))
     }
 
     pub fn finalize(&mut self) -> Result<JsValue, JsValue> {
-        // Flush remaining rows from parser
-        let flush_result = self.parser.flush();
-        
-        if let Some(ref mut profiler) = self.profiler {
-            profiler.update_batch(&flush_result.rows);
-            let stats_result = profiler.finalize();
-            return serde_wasm_bindgen::to_value(&stats_result).map_err(|e| JsValue::from_str(&e.to_string()));
-        }
+        let profiler = self.profiler.as_mut().ok_or_else(|| JsValue::from_str("No partial profiles were added"))?;
+        serde_wasm_bindgen::to_value(&profiler.finalize()).map_err(|e| JsValue::from_str(&e.to_string()))
+    }
 
-        Err(JsValue::from_str("No data was processed"))
+    /// Value at quantile `q` (0 to 1) of a numeric column across all partials.
+    pub fn quantile(&self, column: &str, q: f64) -> Option<f64> {
+        self.profiler.as_ref()?.quantile(column, q)
+    }
+}
+
+impl Default for ProfileMerger {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ProfileMerger {
+    fn add_partial_internal(&mut self, partial: PartialProfile) -> Result<(), String> {
+        let incoming = Profiler::from_partial(partial)?;
+        match self.profiler {
+            Some(ref mut profiler) => profiler.merge(&incoming),
+            None => {
+                self.profiler = Some(incoming);
+                Ok(())
+            }
+        }
     }
 }
 
@@ -101,22 +210,25 @@ impl CsvStreamingParser {
 // JSON/JSONL Profiler - combines parsing and profiling
 #[wasm_bindgen]
 pub struct JsonProfiler {
-    parser: JsonParser,
-    profiler: Option<Profiler>,
+    driver: ProfileDriver<JsonSource>,
 }
 
+driver_methods!(JsonProfiler);
+
 #[wasm_bindgen]
 impl JsonProfiler {
+    /// `config` is a `ProfilerConfig`; omitted fields keep their defaults.
     #[wasm_bindgen(constructor)]
-    pub fn new(max_depth: Option<usize>, max_keys: Option<usize>) -> Self {
-        let config = JsonParserConfig {
+    pub fn new(max_depth: Option<usize>, max_keys: Option<usize>, config: Option<JsValue>) -> Result<JsonProfiler, JsValue> {
+        let parser_config = JsonParserConfig {
             max_nested_depth: max_depth.unwrap_or(3),
             max_keys_per_object: max_keys.unwrap_or(500),
         };
-        Self {
-            parser: JsonParser::new(Some(config)),
-            profiler: None,
-        }
+        let config = ProfilerConfig::from_js(config).map_err(|e| JsValue::from_str(&e))?;
+        let source = JsonSource::new(JsonParser::new(Some(parser_config)));
+        Ok(Self {
+            driver: ProfileDriver::new(source, config),
+        })
     }
 
     pub fn auto_detect_format(&self, chunk: &[u8]) -> String {
@@ -128,35 +240,8 @@ impl JsonProfiler {
         }
     }
 
-    pub fn parse_and_profile_chunk(&mut self, chunk: &[u8]) -> Result<JsValue, JsValue> {
-        let parse_result = self.parser.parse_chunk(chunk);
-
-        if self.profiler.is_none() && !parse_result.headers.is_empty() {
-            self.profiler = Some(Profiler::new(parse_result.headers.clone()));
-        }
-
-        if let Some(ref mut profiler) = self.profiler {
-            profiler.update_batch(&parse_result.rows);
-        }
-
-        serde_wasm_bindgen::to_value(&parse_result).map_err(|e| JsValue::from_str(&e.to_string()))
-    }
-
-    pub fn finalize(&mut self) -> Result<JsValue, JsValue> {
-        // Flush remaining data from parser
-        let flush_result = self.parser.flush();
-
-        if let Some(ref mut profiler) = self.profiler {
-            profiler.update_batch(&flush_result.rows);
-            let stats_result = profiler.finalize();
-            return serde_wasm_bindgen::to_value(&stats_result).map_err(|e| JsValue::from_str(&e.to_string()));
-        }
-
-        Err(JsValue::from_str("No data was processed"))
-    }
-
     pub fn get_format(&self) -> String {
-        match self.parser.get_format() {
+        match self.driver.source().parser().get_format() {
             JsonFormat::JsonArray => "json_array".to_string(),
             JsonFormat::JsonLines => "jsonl".to_string(),
             JsonFormat::Unknown => "unknown".to_string(),
@@ -164,7 +249,7 @@ impl JsonProfiler {
     }
 
     pub fn get_array_stats(&self) -> Result<JsValue, JsValue> {
-        serde_wasm_bindgen::to_value(self.parser.get_array_stats())
+        serde_wasm_bindgen::to_value(self.driver.source().parser().get_array_stats())
             .map_err(|e| JsValue::from_str(&e.to_string()))
     }
 }
@@ -230,6 +315,48 @@ impl JsonStreamingParser {
     }
 }
 
+// XML Profiler - repeated record elements flattened like JSON objects
+#[wasm_bindgen]
+pub struct XmlProfiler {
+    driver: ProfileDriver<XmlSource>,
+}
+
+driver_methods!(XmlProfiler);
+
+#[wasm_bindgen]
+impl XmlProfiler {
+    /// `record_path` selects the record element ("/catalog/book", "//book");
+    /// when omitted, the shallowest repeating element is detected.
+    /// `config` is a `ProfilerConfig`; omitted fields keep their defaults.
+    #[wasm_bindgen(constructor)]
+    pub fn new(
+        record_path: Option<String>,
+        max_depth: Option<usize>,
+        max_keys: Option<usize>,
+        config: Option<JsValue>,
+    ) -> Result<XmlProfiler, JsValue> {
+        let parser_config = JsonParserConfig {
+            max_nested_depth: max_depth.unwrap_or(3),
+            max_keys_per_object: max_keys.unwrap_or(500),
+        };
+        let parser = XmlParser::new(record_path.as_deref(), Some(parser_config))
+            .map_err(|e| JsValue::from_str(&e))?;
+        let config = ProfilerConfig::from_js(config).map_err(|e| JsValue::from_str(&e))?;
+        Ok(Self {
+            driver: ProfileDriver::new(XmlSource::new(parser), config),
+        })
+    }
+
+    pub fn get_record_path(&self) -> Option<String> {
+        self.driver.source().parser().get_record_path()
+    }
+
+    pub fn get_array_stats(&self) -> Result<JsValue, JsValue> {
+        serde_wasm_bindgen::to_value(self.driver.source().parser().get_array_stats())
+            .map_err(|e| JsValue::from_str(&e.to_string()))
+    }
+}
+
 /// Correlation Matrix Calculator for computing Pearson correlation coefficients
 /// between numeric columns
 #[wasm_bindgen]
@@ -320,19 +447,62 @@ pub fn analyze_json_structure_wasm(
         .map_err(|e| JsValue::from_str(&e.to_string()))
 }
 
+use parser::xml::analyze_xml_structure;
+
+/// Analyze XML structure without full profiling (for Tree Mode)
+/// Records are located as in `XmlProfiler`; attributes appear as "@name" paths
+#[wasm_bindgen]
+pub fn analyze_xml_structure_wasm(
+    data: &[u8],
+    record_path: Option<String>,
+    max_sample_rows: Option<usize>,
+    collect_examples: Option<bool>,
+) -> Result<JsValue, JsValue> {
+    let config = StructureConfig {
+        max_sample_rows: max_sample_rows.unwrap_or(1000),
+        collect_examples: collect_examples.unwrap_or(true),
+    };
+
+    let analysis = analyze_xml_structure(data, record_path.as_deref(), Some(config))
+        .map_err(|e| JsValue::from_str(&e))?;
+
+    serde_wasm_bindgen::to_value(&analysis)
+        .map_err(|e| JsValue::from_str(&e.to_string()))
+}
+
 #[cfg(test)]
 mod ts_export_tests {
     //! Tests that trigger ts-rs TypeScript type generation.
     //! Run `cargo test` to generate types into the `bindings/` directory.
 
-    use crate::stats::types::{DataType, BaseStats};
+    use crate::stats::types::{DataType, BaseStats, LogicalType};
+    use crate::stats::logical::{DateRange, DecimalStats};
+    use crate::stats::datetime::DatetimeFormat;
+    use crate::stats::temporal::{TemporalBin, TemporalGap, TemporalGranularity, TemporalStats};
+    use crate::stats::strings::{CaseProfile, CharClassRatios, ScriptCount, StringStats};
+    use crate::stats::shapes::{ShapeEntry, ShapeStats};
+    use crate::stats::boolean::BooleanStats;
+    use crate::stats::sampling::{SamplingMode, SamplingSummary, StopReason};
+    use crate::stats::exact::{Accuracy, ColumnAccuracy};
+    use crate::stats::outliers::{OutlierMethod, OutlierStats};
     use crate::stats::numeric::NumericStats;
     use crate::stats::categorical::{FreqEntry, CategoricalStats};
     use crate::stats::histogram::{Histogram, HistogramBin};
     use crate::stats::ColumnProfile;
     use crate::stats::profiler::ProfilerResult;
+    use crate::stats::config::ProfilerConfig;
     use crate::stats::correlation::CorrelationMatrix;
     use crate::quality::{Severity, QualityIssue, ColumnQualityMetrics};
+    use crate::parser::avro::{AvroUnionStats, AvroBranchCount, AvroStreamProgress, AvroSkippedBlock};
+    use crate::parser::avro_messages::{AvroSchemaIdCount, AvroMessageProgress, AvroSkippedMessage};
+    use crate::parser::excel::{ExcelSheetInfo, ExcelSheetProfile};
+    use crate::parser::orc::{OrcColumnStatistics, OrcStripeSummary, OrcMetadata};
+    use crate::parser::dataset::{DatasetTypeChange, DatasetFileSummary, DatasetColumnInfo, DatasetMetadata};
+    use crate::parser::detect::FormatDetection;
+    use crate::parser::source::SourceProgress;
+    use crate::parser::sqlite::{
+        SqliteColumnInfo, SqliteObjectInfo, SqliteStorageClassCounts, SqliteColumnAffinity, SqliteTableProfile,
+    };
 
     #[test]
     fn export_typescript_types() {
@@ -346,7 +516,30 @@ mod ts_export_tests {
         // Verify all types implement TS (compilation will fail if they don't)
         let _ = DataType::decl();
         let _ = BaseStats::decl();
+        let _ = LogicalType::decl();
+        let _ = DateRange::decl();
+        let _ = DatetimeFormat::decl();
+        let _ = TemporalGranularity::decl();
+        let _ = TemporalBin::decl();
+        let _ = TemporalGap::decl();
+        let _ = TemporalStats::decl();
+        let _ = CharClassRatios::decl();
+        let _ = CaseProfile::decl();
+        let _ = ScriptCount::decl();
+        let _ = StringStats::decl();
+        let _ = ShapeEntry::decl();
+        let _ = ShapeStats::decl();
+        let _ = BooleanStats::decl();
+        let _ = SamplingMode::decl();
+        let _ = StopReason::decl();
+        let _ = SamplingSummary::decl();
+        let _ = Accuracy::decl();
+        let _ = ColumnAccuracy::decl();
+        let _ = OutlierMethod::decl();
+        let _ = OutlierStats::decl();
+        let _ = DecimalStats::decl();
         let _ = NumericStats::decl();
+        let _ = ProfilerConfig::decl();
         let _ = FreqEntry::decl();
         let _ = CategoricalStats::decl();
         let _ = Histogram::decl();
@@ -357,5 +550,28 @@ mod ts_export_tests {
         let _ = Severity::decl();
         let _ = QualityIssue::decl();
         let _ = ColumnQualityMetrics::decl();
+        let _ = AvroUnionStats::decl();
+        let _ = AvroBranchCount::decl();
+        let _ = AvroStreamProgress::decl();
+        let _ = AvroSkippedBlock::decl();
+        let _ = AvroSchemaIdCount::decl();
+        let _ = AvroMessageProgress::decl();
+        let _ = AvroSkippedMessage::decl();
+        let _ = ExcelSheetInfo::decl();
+        let _ = ExcelSheetProfile::decl();
+        let _ = OrcColumnStatistics::decl();
+        let _ = OrcStripeSummary::decl();
+        let _ = OrcMetadata::decl();
+        let _ = SqliteColumnInfo::decl();
+        let _ = SqliteObjectInfo::decl();
+        let _ = SqliteStorageClassCounts::decl();
+        let _ = SqliteColumnAffinity::decl();
+        let _ = SqliteTableProfile::decl();
+        let _ = DatasetTypeChange::decl();
+        let _ = DatasetFileSummary::decl();
+        let _ = DatasetColumnInfo::decl();
+        let _ = DatasetMetadata::decl();
+        let _ = FormatDetection::decl();
+        let _ = SourceProgress::decl();
     }
 }
\ No newline at end of file
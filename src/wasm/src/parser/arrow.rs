@@ -0,0 +1,502 @@
+use wasm_bindgen::prelude::*;
+use arrow_array::RecordBatch;
+use arrow_cast::display::{ArrayFormatter, FormatOptions};
+use arrow_ipc::reader::{FileReader, StreamReader};
+use arrow_ipc::{root_as_message, CompressionType, Message};
+use arrow_schema::ArrowError;
+use std::io::{Cursor, Read};
+use crate::parser::source::{ProfileDriver, RecordSource, SourceProgress, SourceSchema};
+use crate::stats::config::ProfilerConfig;
+use crate::stats::profiler::ProfilerResult;
+
+/// Magic bytes at the start (and end) of an Arrow IPC file / Feather v2 file.
+/// Streams have no magic and start directly with an encapsulated message.
+const ARROW_FILE_MAGIC: &[u8; 6] = b"ARROW1";
+/// Marker before each message's metadata length; older writers omit it.
+const CONTINUATION: u32 = 0xFFFF_FFFF;
+/// Buffer length prefix meaning the buffer data is stored uncompressed.
+const UNCOMPRESSED: i64 = -1;
+
+type BatchIter = Box<dyn Iterator<Item = Result<RecordBatch, ArrowError>>>;
+
+/// `RecordSource` for Arrow IPC files and streams. Input is buffered until
+/// `finish`; each record batch then becomes one batch of rows.
+pub struct ArrowSource {
+    buffer: Vec<u8>,
+    headers: Vec<String>,
+    declared_types: Vec<Option<String>>,
+    format: String,
+    batches: Option<BatchIter>,
+    rows: Vec<Vec<String>>,
+    progress: SourceProgress,
+}
+
+impl ArrowSource {
+    pub fn new() -> Self {
+        Self {
+            buffer: Vec::new(),
+            headers: Vec::new(),
+            declared_types: Vec::new(),
+            format: String::new(),
+            batches: None,
+            rows: Vec::new(),
+            progress: SourceProgress::default(),
+        }
+    }
+
+    /// "file" or "stream" once the input was opened.
+    pub fn format(&self) -> &str {
+        &self.format
+    }
+}
+
+impl RecordSource for ArrowSource {
+    fn push(&mut self, chunk: &[u8]) -> Result<(), String> {
+        self.progress.bytes_received += chunk.len() as u64;
+        self.buffer.extend_from_slice(chunk);
+        Ok(())
+    }
+
+    fn finish(&mut self) -> Result<(), String> {
+        self.progress.finished = true;
+        let bytes = std::mem::take(&mut self.buffer);
+        let is_file = is_arrow_file(&bytes);
+        self.format = if is_file { "file" } else { "stream" }.to_string();
+        let (schema, batches): (_, BatchIter) = match decompress_zstd_buffers(&bytes)? {
+            Some(stream) => {
+                let reader = StreamReader::try_new(Cursor::new(stream), None).map_err(|e| e.to_string())?;
+                (reader.schema(), Box::new(reader))
+            }
+            None if is_file => {
+                let reader = FileReader::try_new(Cursor::new(bytes), None).map_err(|e| e.to_string())?;
+                (reader.schema(), Box::new(reader))
+            }
+            None => {
+                let reader = StreamReader::try_new(Cursor::new(bytes), None).map_err(|e| e.to_string())?;
+                (reader.schema(), Box::new(reader))
+            }
+        };
+        self.headers = schema.fields().iter().map(|f| f.name().to_string()).collect();
+        self.declared_types = schema.fields().iter().map(|f| Some(f.data_type().to_string())).collect();
+        self.batches = Some(batches);
+        Ok(())
+    }
+
+    fn schema(&self) -> Option<SourceSchema> {
+        self.batches.as_ref().map(|_| SourceSchema {
+            declared_types: self.declared_types.clone(),
+            ..SourceSchema::untyped(self.headers.clone())
+        })
+    }
+
+    fn next_batch(&mut self) -> Result<Option<&[Vec<String>]>, String> {
+        let batch = match self.batches.as_mut().and_then(|batches| batches.next()) {
+            Some(batch) => batch.map_err(|e| e.to_string())?,
+            None => return Ok(None),
+        };
+        self.rows = record_batch_to_rows(&batch).map_err(|e| e.to_string())?;
+        self.progress.record_batch(self.rows.len());
+        Ok(Some(&self.rows))
+    }
+
+    fn progress(&self) -> SourceProgress {
+        self.progress.clone()
+    }
+
+    fn streaming(&self) -> bool {
+        false
+    }
+}
+
+#[wasm_bindgen]
+pub struct ArrowProfiler {
+    driver: ProfileDriver<ArrowSource>,
+}
+
+#[wasm_bindgen]
+impl ArrowProfiler {
+    /// `config` is a `ProfilerConfig`; omitted fields keep their defaults.
+    #[wasm_bindgen(constructor)]
+    pub fn new(config: Option<JsValue>) -> Result<ArrowProfiler, JsValue> {
+        let config = ProfilerConfig::from_js(config).map_err(|e| JsValue::from_str(&e))?;
+        Ok(Self::with_config(config))
+    }
+
+    /// Returns "file" or "stream" once a buffer has been parsed.
+    pub fn get_format(&self) -> String {
+        self.driver.source().format().to_string()
+    }
+
+    /// Parses an entire Arrow IPC buffer (file/Feather v2 or stream format)
+    /// and updates the profiler.
+    pub fn parse_and_profile(&mut self, file_bytes: &[u8]) -> Result<JsValue, JsValue> {
+        match self.parse_and_profile_internal(file_bytes) {
+            Ok(stats) => serde_wasm_bindgen::to_value(&stats).map_err(|e| JsValue::from_str(&e.to_string())),
+            Err(e) => Err(JsValue::from_str(&e)),
+        }
+    }
+}
+
+impl ArrowProfiler {
+    pub(crate) fn with_config(config: ProfilerConfig) -> Self {
+        Self {
+            driver: ProfileDriver::new(ArrowSource::new(), config),
+        }
+    }
+
+    pub(crate) fn parse_and_profile_internal(&mut self, file_bytes: &[u8]) -> Result<ProfilerResult, String> {
+        self.driver.push(file_bytes)?;
+        self.driver.finish()
+    }
+}
+
+pub(crate) fn is_arrow_file(bytes: &[u8]) -> bool {
+    bytes.len() >= ARROW_FILE_MAGIC.len() && &bytes[..ARROW_FILE_MAGIC.len()] == ARROW_FILE_MAGIC
+}
+
+/// An encapsulated IPC message: flatbuffer metadata followed by its body.
+struct IpcMessage<'a> {
+    metadata: &'a [u8],
+    body: &'a [u8],
+}
+
+impl<'a> IpcMessage<'a> {
+    fn message(&self) -> Result<Message<'a>, String> {
+        root_as_message(self.metadata).map_err(|e| format!("Invalid Arrow IPC message: {}", e))
+    }
+}
+
+/// Splits the messages of a stream, or of the message section of a file, up
+/// to the end-of-stream marker.
+fn ipc_messages(bytes: &[u8]) -> Result<Vec<IpcMessage<'_>>, String> {
+    let (mut pos, end) = if is_arrow_file(bytes) {
+        // Magic padded to 8 bytes at the start; footer, its length and the
+        // magic at the end
+        let footer_len = bytes.len().checked_sub(ARROW_FILE_MAGIC.len() + 4)
+            .and_then(|at| bytes.get(at..at + 4))
+            .map(|len| i32::from_le_bytes([len[0], len[1], len[2], len[3]]).max(0) as usize)
+            .ok_or_else(|| "Truncated Arrow IPC file".to_string())?;
+        (8, bytes.len().saturating_sub(ARROW_FILE_MAGIC.len() + 4 + footer_len))
+    } else {
+        (0, bytes.len())
+    };
+
+    let read_u32 = |at: usize| bytes.get(at..at + 4).map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]));
+    let mut messages = Vec::new();
+    while pos + 4 <= end {
+        let mut len = read_u32(pos).unwrap_or(0);
+        pos += 4;
+        if len == CONTINUATION {
+            len = read_u32(pos).ok_or_else(|| "Truncated Arrow IPC message".to_string())?;
+            pos += 4;
+        }
+        if len == 0 {
+            break;
+        }
+        let metadata = pos.checked_add(len as usize)
+            .and_then(|meta_end| bytes.get(pos..meta_end))
+            .ok_or_else(|| "Truncated Arrow IPC message".to_string())?;
+        pos += metadata.len();
+        let body_len = usize::try_from(IpcMessage { metadata, body: &[] }.message()?.bodyLength())
+            .map_err(|_| "Invalid Arrow IPC body length".to_string())?;
+        let body = pos.checked_add(body_len)
+            .and_then(|body_end| bytes.get(pos..body_end))
+            .ok_or_else(|| "Truncated Arrow IPC message body".to_string())?;
+        pos += body.len();
+        messages.push(IpcMessage { metadata, body });
+    }
+    Ok(messages)
+}
+
+/// arrow-ipc can only decompress ZSTD buffers through the C zstd library,
+/// which is not built for wasm. Input with ZSTD-compressed batches is
+/// rewritten here as a stream whose buffers are decompressed with ruzstd and
+/// marked as stored uncompressed. Returns None if no batch uses ZSTD, without
+/// copying the input: the message headers are scanned first.
+fn decompress_zstd_buffers(bytes: &[u8]) -> Result<Option<Vec<u8>>, String> {
+    let messages = ipc_messages(bytes)?;
+    let mut uses_zstd = false;
+    for ipc in &messages {
+        uses_zstd |= zstd_batch(&ipc.message()?).is_some();
+    }
+    if !uses_zstd {
+        return Ok(None);
+    }
+
+    let mut stream = Vec::with_capacity(bytes.len());
+    for ipc in &messages {
+        let message = ipc.message()?;
+        let mut metadata = ipc.metadata.to_vec();
+        let body = match zstd_batch(&message) {
+            Some(batch) => {
+                let mut body = Vec::new();
+                if let Some(buffers) = batch.buffers() {
+                    // Buffer structs are 16 bytes, offset then length; they
+                    // are patched where the vector sits in the metadata
+                    let start = buffers.bytes().as_ptr() as usize - ipc.metadata.as_ptr() as usize;
+                    for (idx, buffer) in buffers.iter().enumerate() {
+                        let data = usize::try_from(buffer.offset()).ok()
+                            .zip(usize::try_from(buffer.length()).ok())
+                            .and_then(|(offset, len)| ipc.body.get(offset..offset.checked_add(len)?))
+                            .ok_or_else(|| "Arrow IPC buffer out of bounds".to_string())?;
+                        let offset = body.len();
+                        if !data.is_empty() {
+                            body.extend_from_slice(&UNCOMPRESSED.to_le_bytes());
+                            body.extend_from_slice(&decompress_zstd_buffer(data)?);
+                        }
+                        let len = body.len() - offset;
+                        body.resize(body.len().next_multiple_of(8), 0);
+                        let at = start + idx * 16;
+                        metadata[at..at + 8].copy_from_slice(&(offset as i64).to_le_bytes());
+                        metadata[at + 8..at + 16].copy_from_slice(&(len as i64).to_le_bytes());
+                    }
+                }
+                let slot = message._tab.vtable().get(Message::VT_BODYLENGTH) as usize;
+                if slot == 0 && !body.is_empty() {
+                    return Err("Arrow IPC message without a body length".to_string());
+                }
+                if slot != 0 {
+                    let at = message._tab.loc() + slot;
+                    metadata[at..at + 8].copy_from_slice(&(body.len() as i64).to_le_bytes());
+                }
+                body
+            }
+            None => ipc.body.to_vec(),
+        };
+
+        stream.extend_from_slice(&CONTINUATION.to_le_bytes());
+        stream.extend_from_slice(&(metadata.len() as u32).to_le_bytes());
+        stream.extend_from_slice(&metadata);
+        stream.extend_from_slice(&body);
+    }
+    stream.extend_from_slice(&CONTINUATION.to_le_bytes());
+    stream.extend_from_slice(&0u32.to_le_bytes());
+    Ok(Some(stream))
+}
+
+/// The record batch (or dictionary batch data) of a message, if its buffers
+/// are ZSTD-compressed.
+fn zstd_batch<'a>(message: &Message<'a>) -> Option<arrow_ipc::RecordBatch<'a>> {
+    message.header_as_record_batch()
+        .or_else(|| message.header_as_dictionary_batch().and_then(|d| d.data()))
+        .filter(|b| b.compression().is_some_and(|c| c.codec() == CompressionType::ZSTD))
+}
+
+/// Decodes one compressed buffer: its uncompressed length as a
+/// little-endian i64, then a ZSTD frame (or the raw data for length -1).
+fn decompress_zstd_buffer(data: &[u8]) -> Result<Vec<u8>, String> {
+    let (prefix, payload) = data.split_at_checked(8).ok_or_else(|| "Truncated Arrow IPC buffer".to_string())?;
+    let expected = i64::from_le_bytes(prefix.try_into().unwrap_or_default());
+    match expected {
+        0 => Ok(Vec::new()),
+        UNCOMPRESSED => Ok(payload.to_vec()),
+        len => {
+            let mut out = Vec::with_capacity(usize::try_from(len).unwrap_or(0).min(payload.len().saturating_mul(16)));
+            ruzstd::decoding::StreamingDecoder::new(payload)
+                .map_err(|e| format!("zstd: {}", e))?
+                .read_to_end(&mut out)
+                .map_err(|e| format!("zstd: {}", e))?;
+            if out.len() as i64 != len {
+                return Err(format!("zstd: expected {} bytes, got {}", len, out.len()));
+            }
+            Ok(out)
+        }
+    }
+}
+
+/// Converts a record batch into string rows for the Profiler.
+/// Dictionary-encoded columns are rendered as their decoded values and nulls
+/// become empty strings so they are counted as missing.
+pub(crate) fn record_batch_to_rows(batch: &RecordBatch) -> Result<Vec<Vec<String>>, ArrowError> {
+    let options = FormatOptions::default();
+    let formatters = batch.columns().iter()
+        .map(|col| ArrayFormatter::try_new(col.as_ref(), &options))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut rows = Vec::with_capacity(batch.num_rows());
+    for row_idx in 0..batch.num_rows() {
+        let row = formatters.iter()
+            .map(|f| f.value(row_idx).to_string())
+            .collect();
+        rows.push(row);
+    }
+    Ok(rows)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow_array::{ArrayRef, DictionaryArray, Float64Array, Int64Array, StringArray};
+    use arrow_array::types::Int32Type;
+    use arrow_ipc::writer::{FileWriter, IpcWriteOptions, StreamWriter};
+    use arrow_ipc::{
+        Block, BodyCompressionBuilder, BodyCompressionMethod, FieldNode, FooterBuilder, MessageBuilder,
+        MessageHeader, RecordBatchBuilder,
+    };
+    use flatbuffers::FlatBufferBuilder;
+    use std::sync::Arc;
+
+    fn sample_batch() -> RecordBatch {
+        let ids: ArrayRef = Arc::new(Int64Array::from(vec![1, 2, 3, 4]));
+        let scores: ArrayRef = Arc::new(Float64Array::from(vec![Some(1.5), None, Some(3.0), Some(4.5)]));
+        let colors: DictionaryArray<Int32Type> = vec!["red", "blue", "red", "green"].into_iter().collect();
+        let names: ArrayRef = Arc::new(StringArray::from(vec!["a", "b", "c", "d"]));
+        RecordBatch::try_from_iter(vec![
+            ("id", ids),
+            ("score", scores),
+            ("color", Arc::new(colors) as ArrayRef),
+            ("name", names),
+        ]).unwrap()
+    }
+
+    /// An IPC file holding `batch` twice, with buffers compressed by `compression`.
+    fn ipc_file(batch: &RecordBatch, compression: CompressionType) -> Vec<u8> {
+        let options = IpcWriteOptions::default()
+            .try_with_compression(Some(compression))
+            .unwrap();
+        let mut writer = FileWriter::try_new_with_options(Vec::new(), &batch.schema(), options).unwrap();
+        writer.write(batch).unwrap();
+        writer.write(batch).unwrap();
+        writer.into_inner().unwrap()
+    }
+
+    /// An IPC file holding `batch` twice with ZSTD-compressed buffers, built
+    /// by hand as arrow-ipc only writes ZSTD with its C zstd feature.
+    fn zstd_ipc_file(batch: &RecordBatch) -> Vec<u8> {
+        let mut writer = StreamWriter::try_new(Vec::new(), &batch.schema()).unwrap();
+        writer.write(batch).unwrap();
+        writer.write(batch).unwrap();
+        let stream = writer.into_inner().unwrap();
+
+        let mut file = b"ARROW1\0\0".to_vec();
+        let mut blocks = Vec::new();
+        for ipc in ipc_messages(&stream).unwrap() {
+            let message = ipc.message().unwrap();
+            let (metadata, body) = match message.header_as_record_batch() {
+                Some(record_batch) => {
+                    let mut fbb = FlatBufferBuilder::new();
+                    let mut body = Vec::new();
+                    let buffers: Vec<arrow_ipc::Buffer> = record_batch.buffers().unwrap().iter().map(|buffer| {
+                        let data = &ipc.body[buffer.offset() as usize..][..buffer.length() as usize];
+                        let offset = body.len();
+                        if !data.is_empty() {
+                            body.extend_from_slice(&(data.len() as i64).to_le_bytes());
+                            body.extend(ruzstd::encoding::compress_to_vec(data, ruzstd::encoding::CompressionLevel::Fastest));
+                        }
+                        let len = body.len() - offset;
+                        body.resize(body.len().next_multiple_of(8), 0);
+                        arrow_ipc::Buffer::new(offset as i64, len as i64)
+                    }).collect();
+                    let nodes: Vec<FieldNode> = record_batch.nodes().unwrap().iter().copied().collect();
+                    let nodes = fbb.create_vector(&nodes);
+                    let buffers = fbb.create_vector(&buffers);
+                    let mut compression = BodyCompressionBuilder::new(&mut fbb);
+                    compression.add_codec(CompressionType::ZSTD);
+                    compression.add_method(BodyCompressionMethod::BUFFER);
+                    let compression = compression.finish();
+                    let mut header = RecordBatchBuilder::new(&mut fbb);
+                    header.add_length(record_batch.length());
+                    header.add_nodes(nodes);
+                    header.add_buffers(buffers);
+                    header.add_compression(compression);
+                    let header = header.finish();
+                    let mut rewritten = MessageBuilder::new(&mut fbb);
+                    rewritten.add_version(message.version());
+                    rewritten.add_header_type(MessageHeader::RecordBatch);
+                    rewritten.add_header(header.as_union_value());
+                    rewritten.add_bodyLength(body.len() as i64);
+                    let rewritten = rewritten.finish();
+                    fbb.finish(rewritten, None);
+                    let mut metadata = fbb.finished_data().to_vec();
+                    metadata.resize(metadata.len().next_multiple_of(8), 0);
+                    (metadata, body)
+                }
+                None => (ipc.metadata.to_vec(), ipc.body.to_vec()),
+            };
+            if message.header_type() == MessageHeader::RecordBatch {
+                blocks.push(Block::new(file.len() as i64, metadata.len() as i32 + 8, body.len() as i64));
+            }
+            file.extend_from_slice(&CONTINUATION.to_le_bytes());
+            file.extend_from_slice(&(metadata.len() as u32).to_le_bytes());
+            file.extend_from_slice(&metadata);
+            file.extend_from_slice(&body);
+        }
+        file.extend_from_slice(&CONTINUATION.to_le_bytes());
+        file.extend_from_slice(&0u32.to_le_bytes());
+
+        let mut fbb = FlatBufferBuilder::new();
+        let schema = arrow_ipc::convert::schema_to_fb_offset(&mut fbb, &batch.schema());
+        let blocks = fbb.create_vector(&blocks);
+        let mut footer = FooterBuilder::new(&mut fbb);
+        footer.add_version(arrow_ipc::MetadataVersion::V5);
+        footer.add_schema(schema);
+        footer.add_recordBatches(blocks);
+        let footer = footer.finish();
+        fbb.finish(footer, None);
+        file.extend_from_slice(fbb.finished_data());
+        file.extend_from_slice(&(fbb.finished_data().len() as i32).to_le_bytes());
+        file.extend_from_slice(ARROW_FILE_MAGIC);
+        file
+    }
+
+    #[test]
+    fn test_record_batch_to_rows() {
+        let rows = record_batch_to_rows(&sample_batch()).unwrap();
+        assert_eq!(rows.len(), 4);
+        assert_eq!(rows[0], vec!["1", "1.5", "red", "a"]);
+        assert_eq!(rows[1][1], "");
+        assert_eq!(rows[3][2], "green");
+    }
+
+    #[test]
+    fn test_profile_ipc_file_compressed() {
+        let batch = sample_batch();
+        let bytes = ipc_file(&batch, CompressionType::LZ4_FRAME);
+
+        // Only ZSTD input is rewritten
+        assert!(decompress_zstd_buffers(&bytes).unwrap().is_none());
+        assert!(decompress_zstd_buffers(&zstd_ipc_file(&batch)).unwrap().is_some());
+
+        let mut profiler = ArrowProfiler::with_config(ProfilerConfig::default());
+        let result = profiler.parse_and_profile_internal(&bytes).unwrap();
+
+        assert_eq!(profiler.get_format(), "file");
+        let names: Vec<&str> = result.column_profiles.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, vec!["id", "score", "color", "name"]);
+        assert_eq!(result.total_rows, 8);
+        assert_eq!(result.column_profiles[1].base_stats.missing, 2);
+    }
+
+    #[test]
+    fn test_profile_ipc_file_zstd() {
+        let batch = sample_batch();
+        let bytes = zstd_ipc_file(&batch);
+        assert!(FileReader::try_new(Cursor::new(bytes.clone()), None).unwrap().next().unwrap().is_err());
+
+        let mut profiler = ArrowProfiler::with_config(ProfilerConfig::default());
+        let result = profiler.parse_and_profile_internal(&bytes).unwrap();
+
+        assert_eq!(result.total_rows, 8);
+        assert_eq!(result.column_profiles[0].numeric_stats.as_ref().unwrap().max, 4.0);
+        let color = &result.column_profiles[2];
+        assert_eq!(color.categorical_stats.as_ref().unwrap().top_values[0].count, 4);
+    }
+
+    #[test]
+    fn test_profile_ipc_stream() {
+        let batch = sample_batch();
+        let mut writer = StreamWriter::try_new(Vec::new(), &batch.schema()).unwrap();
+        writer.write(&batch).unwrap();
+        let bytes = writer.into_inner().unwrap();
+
+        let mut profiler = ArrowProfiler::with_config(ProfilerConfig::default());
+        let result = profiler.parse_and_profile_internal(&bytes).unwrap();
+
+        assert_eq!(profiler.get_format(), "stream");
+        assert_eq!(result.total_rows, 4);
+        let color = &result.column_profiles[2];
+        let top = &color.categorical_stats.as_ref().unwrap().top_values[0];
+        assert_eq!(top.value, "red");
+        assert_eq!(top.count, 2);
+    }
+}
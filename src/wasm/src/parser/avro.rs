@@ -1,32 +1,437 @@
 use wasm_bindgen::prelude::*;
-use apache_avro::Reader;
-use std::io::Cursor;
+use apache_avro::schema::{Name, ResolvedSchema, UnionSchema};
+use apache_avro::types::Value as AvroValue;
+use apache_avro::{Codec, Schema};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::str::FromStr;
+use ts_rs::TS;
+use crate::stats::logical::format_scaled_decimal;
+use crate::parser::source::{ProfileDriver, RecordSource, SourceProgress, SourceSchema};
+use crate::stats::config::ProfilerConfig;
 use crate::stats::profiler::{Profiler, ProfilerResult};
+use crate::stats::types::LogicalType;
+use chrono::{DateTime, NaiveTime};
+use num_bigint::BigInt;
 use serde_json::Value;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::io::Read;
 
-#[wasm_bindgen]
-pub struct AvroProfiler {
-    profiler: Option<Profiler>,
+/// Null-vs-value counts for a union field in the writer schema.
+#[derive(Serialize, Deserialize, Debug, Clone, TS)]
+#[ts(export)]
+pub struct AvroUnionStats {
+    /// Flattened column the union resolves into (the record prefix for nullable records)
+    pub column: String,
+    /// Declared branch types, in schema order
+    pub branches: Vec<String>,
+    pub null_count: u64,
+    pub value_count: u64,
+    /// Per-branch counts, only populated for unions with more than one non-null branch
+    pub branch_counts: Vec<AvroBranchCount>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, TS)]
+#[ts(export)]
+pub struct AvroBranchCount {
+    pub branch: String,
+    pub count: u64,
+}
+
+impl AvroUnionStats {
+    fn new(column: String, union: &UnionSchema) -> Self {
+        let branches: Vec<String> = union.variants().iter().map(branch_name).collect();
+        let non_null = union.variants().iter().filter(|v| !matches!(v, Schema::Null)).count();
+        let branch_counts = if non_null > 1 {
+            branches.iter().map(|b| AvroBranchCount { branch: b.clone(), count: 0 }).collect()
+        } else {
+            Vec::new()
+        };
+
+        Self {
+            column,
+            branches,
+            null_count: 0,
+            value_count: 0,
+            branch_counts,
+        }
+    }
+
+    /// Adds the counts of the same union declared by another schema version.
+    /// Returns false if the declared branches differ.
+    pub(crate) fn merge(&mut self, other: &AvroUnionStats) -> bool {
+        if self.column != other.column || self.branches != other.branches {
+            return false;
+        }
+        self.null_count += other.null_count;
+        self.value_count += other.value_count;
+        for (entry, other_entry) in self.branch_counts.iter_mut().zip(&other.branch_counts) {
+            entry.count += other_entry.count;
+        }
+        true
+    }
+
+    fn update(&mut self, branch_idx: usize, is_null: bool) {
+        if is_null {
+            self.null_count += 1;
+        } else {
+            self.value_count += 1;
+        }
+        if let Some(entry) = self.branch_counts.get_mut(branch_idx) {
+            entry.count += 1;
+        }
+    }
+}
+
+/// Progress of an incremental object container read, reported per chunk.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, TS)]
+#[ts(export)]
+pub struct AvroStreamProgress {
+    pub header_parsed: bool,
+    /// Bytes of the file handed to the profiler so far
+    pub bytes_received: u64,
+    /// Bytes fully consumed as header or data blocks
+    pub bytes_consumed: u64,
+    pub blocks_read: u64,
+    pub records_read: u64,
+    pub skipped_blocks: Vec<AvroSkippedBlock>,
+}
+
+/// A data block that was dropped because it could not be framed or decoded.
+#[derive(Serialize, Deserialize, Debug, Clone, TS)]
+#[ts(export)]
+pub struct AvroSkippedBlock {
+    pub block_index: u64,
+    /// File offset where the block (or the unreadable region) starts
+    pub byte_offset: u64,
+    pub reason: String,
+}
+
+/// Avro object container magic: "Obj" followed by format version 1.
+const AVRO_MAGIC: &[u8; 4] = b"Obj\x01";
+const SYNC_SIZE: usize = 16;
+/// Blocks claiming to be larger than this are treated as corrupt instead of
+/// buffered; it also caps a block's decompressed size.
+const MAX_BLOCK_BYTES: i64 = 64 * 1024 * 1024;
+/// Record count cap for schemas whose records encode to zero bytes, where the
+/// block size cannot bound the count.
+const MAX_BLOCK_RECORDS: usize = 1 << 20;
+
+type SchemaNames = HashMap<Name, Schema>;
+
+struct ContainerHeader {
+    schema: Schema,
+    codec: Codec,
+    sync: [u8; SYNC_SIZE],
+}
+
+enum BlockStep {
+    NeedMore,
+    Block { count: usize, data: Vec<u8>, len: usize },
+    Corrupt(String),
+}
+
+/// Column layout derived from the writer schema.
+pub(crate) struct AvroColumns {
+    pub(crate) schema: Schema,
+    names: SchemaNames,
+    pub(crate) headers: Vec<String>,
+    pub(crate) logical_types: Vec<Option<LogicalType>>,
+    header_set: HashSet<String>,
+    union_index: HashMap<String, usize>,
+    // Records encode to zero bytes (e.g. a record of `null` fields)
+    zero_width: bool,
+}
+
+impl AvroColumns {
+    pub(crate) fn new(schema: Schema) -> Result<(Self, Vec<AvroUnionStats>), String> {
+        let names: SchemaNames = ResolvedSchema::try_from(&schema)
+            .map_err(|e| e.to_string())?
+            .get_names()
+            .iter()
+            .map(|(name, s)| (name.clone(), (*s).clone()))
+            .collect();
+
+        // Headers come from the writer schema in declaration order, so fields
+        // that are null in the first records are still profiled.
+        let mut leaves = Vec::new();
+        let mut unions = Vec::new();
+        collect_columns(&schema, "", &names, &mut Vec::new(), &mut leaves, &mut unions);
+        let (headers, logical_types): (Vec<String>, Vec<Option<LogicalType>>) = leaves.into_iter().unzip();
+
+        let header_set = headers.iter().cloned().collect();
+        let union_index = unions.iter().enumerate()
+            .map(|(idx, stats)| (stats.column.clone(), idx))
+            .collect();
+
+        let zero_width = is_zero_width(&schema, &names, &mut Vec::new());
+        Ok((Self { schema, names, headers, logical_types, header_set, union_index, zero_width }, unions))
+    }
+
+    pub(crate) fn flatten_record(&self, record: &AvroValue, unions: &mut [AvroUnionStats]) -> Vec<String> {
+        let mut flat_map = HashMap::new();
+        self.flatten_value(&self.schema, record, "", unions, &mut flat_map);
+        self.headers.iter().map(|h| {
+            flat_map.remove(h).unwrap_or_default()
+        }).collect()
+    }
+
+    /// Walks a record alongside its writer schema, writing leaf values into
+    /// `output` keyed by column and counting union branches on the way.
+    fn flatten_value(
+        &self,
+        schema: &Schema,
+        value: &AvroValue,
+        prefix: &str,
+        unions: &mut [AvroUnionStats],
+        output: &mut HashMap<String, String>,
+    ) {
+        match (schema, value) {
+            (Schema::Ref { name }, _) => {
+                if let Some(resolved) = self.names.get(name) {
+                    self.flatten_value(resolved, value, prefix, unions, output);
+                }
+            }
+            (Schema::Union(union), AvroValue::Union(branch_idx, inner)) => {
+                let branch_idx = *branch_idx as usize;
+                if let Some(&idx) = self.union_index.get(&column_key(prefix)) {
+                    let is_null = matches!(**inner, AvroValue::Null);
+                    unions[idx].update(branch_idx, is_null);
+                }
+                if let Some(branch) = union.variants().get(branch_idx) {
+                    self.flatten_value(branch, inner, prefix, unions, output);
+                }
+            }
+            // Records are expanded unless the schema walk made them a leaf column
+            // (recursive types, records inside multi-type unions).
+            (Schema::Record(record), AvroValue::Record(fields)) if !self.header_set.contains(prefix) => {
+                for (field, (_, field_value)) in record.fields.iter().zip(fields) {
+                    let key = join_key(prefix, &field.name);
+                    self.flatten_value(&field.schema, field_value, &key, unions, output);
+                }
+            }
+            _ => {
+                let key = column_key(prefix);
+                if self.header_set.contains(&key) {
+                    output.insert(key, avro_leaf_to_string(schema, value));
+                }
+            }
+        }
+    }
+}
+
+/// `RecordSource` for Avro object container files, read incrementally:
+/// the header is parsed as soon as it is complete, after that each
+/// `next_batch` call decodes one complete data block, so only the block being
+/// profiled is held as rows and only a partial block stays buffered.
+pub struct AvroSource {
     headers: Vec<String>,
+    columns: Option<AvroColumns>,
+    union_stats: Vec<AvroUnionStats>,
     schema_json: String,
+    // Incremental container state: unconsumed bytes are `buffer[read_pos..]`
+    // and start at file offset `buffer_offset`
+    container: Option<ContainerHeader>,
+    // Raw header bytes, kept so a checkpoint can rebuild the decoders
+    header_bytes: Vec<u8>,
+    buffer: Vec<u8>,
+    // Consumed bytes before it are dropped once per `push`, not once per block
+    read_pos: usize,
+    buffer_offset: u64,
+    resyncing: bool,
+    // Unconsumed bytes of a pending block already scanned for sync markers
+    sync_scan_pos: usize,
+    block_index: u64,
+    progress: AvroStreamProgress,
+    // Rows of the block last returned by `next_batch`
+    current: Vec<Vec<String>>,
+    finished: bool,
 }
 
-#[wasm_bindgen]
-impl AvroProfiler {
-    #[wasm_bindgen(constructor)]
+impl AvroSource {
     pub fn new() -> Self {
         Self {
-            profiler: None,
             headers: Vec::new(),
+            columns: None,
+            union_stats: Vec::new(),
             schema_json: String::new(),
+            container: None,
+            header_bytes: Vec::new(),
+            buffer: Vec::new(),
+            read_pos: 0,
+            buffer_offset: 0,
+            resyncing: false,
+            sync_scan_pos: 0,
+            block_index: 0,
+            progress: AvroStreamProgress::default(),
+            current: Vec::new(),
+            finished: false,
         }
     }
 
+    pub fn schema_json(&self) -> &str {
+        &self.schema_json
+    }
+
+    pub fn stream_progress(&self) -> &AvroStreamProgress {
+        &self.progress
+    }
+}
+
+impl RecordSource for AvroSource {
+    fn push(&mut self, chunk: &[u8]) -> Result<(), String> {
+        self.progress.bytes_received += chunk.len() as u64;
+        self.buffer.drain(..self.read_pos);
+        self.read_pos = 0;
+        self.buffer.extend_from_slice(chunk);
+
+        if self.container.is_none() {
+            match parse_container_header(&self.buffer)? {
+                Some((header, header_len)) => {
+                    self.init_columns(header.schema.clone())?;
+                    self.container = Some(header);
+                    self.header_bytes = self.buffer[..header_len].to_vec();
+                    self.consume(header_len);
+                    self.progress.header_parsed = true;
+                }
+                None => return Ok(()),
+            }
+        }
+        Ok(())
+    }
+
+    fn finish(&mut self) -> Result<(), String> {
+        self.finished = true;
+        if self.container.is_none() {
+            return Err("Invalid or incomplete Avro header".to_string());
+        }
+        Ok(())
+    }
+
+    fn schema(&self) -> Option<SourceSchema> {
+        self.columns.as_ref().map(|columns| SourceSchema {
+            headers: columns.headers.clone(),
+            logical_types: columns.logical_types.clone(),
+            declared_types: Vec::new(),
+        })
+    }
+
+    /// Decodes the next complete block; blocks that fail to frame or decode
+    /// are reported and skipped.
+    fn next_batch(&mut self) -> Result<Option<&[Vec<String>]>, String> {
+        if self.container.is_none() {
+            return Ok(None);
+        }
+        loop {
+            if self.resyncing && !self.resync() {
+                self.discard_tail();
+                return Ok(None);
+            }
+
+            match self.next_block() {
+                BlockStep::NeedMore => {
+                    self.discard_tail();
+                    return Ok(None);
+                }
+                BlockStep::Block { count, data, len } => {
+                    let block_offset = self.buffer_offset;
+                    self.consume(len);
+                    match self.decode_block(count, data) {
+                        Ok(rows) => {
+                            self.block_index += 1;
+                            self.progress.blocks_read += 1;
+                            self.progress.records_read += rows.len() as u64;
+                            self.current = rows;
+                            return Ok(Some(&self.current));
+                        }
+                        Err(reason) => {
+                            self.skip_block(block_offset, reason);
+                            self.block_index += 1;
+                        }
+                    }
+                }
+                BlockStep::Corrupt(reason) => {
+                    self.skip_block(self.buffer_offset, reason);
+                    self.block_index += 1;
+                    // Drop the first byte so the sync search starts past the bad block header
+                    self.consume(1);
+                    self.resyncing = true;
+                }
+            }
+        }
+    }
+
+    fn progress(&self) -> SourceProgress {
+        SourceProgress {
+            bytes_received: self.progress.bytes_received,
+            records_read: self.progress.records_read,
+            batches_read: self.progress.blocks_read,
+            finished: self.finished,
+        }
+    }
+
+    fn annotate(&self, profiler: &mut Profiler) {
+        profiler.avro_schema = Some(self.schema_json.clone());
+        profiler.avro_union_stats = Some(self.union_stats.clone());
+    }
+}
+
+/// Serialized form of an `AvroSource`. Decoders built from the writer schema
+/// are not serializable, so the raw container header is kept and parsed
+/// again on restore. Only unconsumed bytes are kept: checkpoints are taken
+/// between chunks, after the driver decoded and profiled every complete block.
+#[derive(Serialize, Deserialize)]
+struct AvroCheckpoint {
+    header_bytes: Vec<u8>,
+    union_stats: Vec<AvroUnionStats>,
+    buffer: Vec<u8>,
+    buffer_offset: u64,
+    resyncing: bool,
+    block_index: u64,
+    progress: AvroStreamProgress,
+    finished: bool,
+}
+
+impl Serialize for AvroSource {
+    fn serialize<Ser: Serializer>(&self, serializer: Ser) -> Result<Ser::Ok, Ser::Error> {
+        AvroCheckpoint {
+            header_bytes: self.header_bytes.clone(),
+            union_stats: self.union_stats.clone(),
+            buffer: self.buffer[self.read_pos..].to_vec(),
+            buffer_offset: self.buffer_offset,
+            resyncing: self.resyncing,
+            block_index: self.block_index,
+            progress: self.progress.clone(),
+            finished: self.finished,
+        }.serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for AvroSource {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let checkpoint = AvroCheckpoint::deserialize(deserializer)?;
+        AvroSource::restore(checkpoint).map_err(serde::de::Error::custom)
+    }
+}
+
+#[wasm_bindgen]
+pub struct AvroProfiler {
+    driver: ProfileDriver<AvroSource>,
+}
+
+#[wasm_bindgen]
+impl AvroProfiler {
+    /// `config` is a `ProfilerConfig`; omitted fields keep their defaults.
+    #[wasm_bindgen(constructor)]
+    pub fn new(config: Option<JsValue>) -> Result<AvroProfiler, JsValue> {
+        let config = ProfilerConfig::from_js(config).map_err(|e| JsValue::from_str(&e))?;
+        Ok(Self::with_config(config))
+    }
+
     pub fn get_schema_json(&self) -> String {
-        self.schema_json.clone()
+        self.driver.source().schema_json().to_string()
     }
 
+    /// Parses an entire Avro object container file buffer and returns the profile.
     pub fn parse_and_profile(&mut self, file_bytes: &[u8]) -> Result<JsValue, JsValue> {
         match self.parse_and_profile_internal(file_bytes) {
             Ok(stats) => serde_wasm_bindgen::to_value(&stats).map_err(|e| JsValue::from_str(&e.to_string())),
@@ -34,153 +439,631 @@ impl AvroProfiler {
         }
     }
 
+    /// Feeds the next chunk of an object container file. The header is parsed
+    /// as soon as it is complete; after that every complete data block is
+    /// decoded and profiled, so only a partial block is ever buffered.
+    /// Returns an `AvroStreamProgress`.
+    pub fn parse_and_profile_chunk(&mut self, chunk: &[u8]) -> Result<JsValue, JsValue> {
+        self.process_chunk(chunk).map_err(|e| JsValue::from_str(&e))?;
+        self.get_progress()
+    }
+
+    /// Returns an `AvroStreamProgress`, which adds the header state, bytes
+    /// consumed and skipped blocks to the usual counters.
+    pub fn get_progress(&self) -> Result<JsValue, JsValue> {
+        serde_wasm_bindgen::to_value(self.driver.source().stream_progress()).map_err(|e| JsValue::from_str(&e.to_string()))
+    }
+}
+
+driver_methods!(AvroProfiler, without_chunk_results);
+
+impl AvroProfiler {
+    pub(crate) fn with_config(config: ProfilerConfig) -> Self {
+        Self {
+            driver: ProfileDriver::new(AvroSource::new(), config),
+        }
+    }
+
     fn parse_and_profile_internal(&mut self, file_bytes: &[u8]) -> Result<ProfilerResult, String> {
-        let cursor = Cursor::new(file_bytes);
-        let reader = Reader::new(cursor).map_err(|e| e.to_string())?;
-        
-        // Extract schema
-        let schema = reader.writer_schema();
+        self.process_chunk(file_bytes)?;
+        self.finalize_internal()
+    }
+
+    pub(crate) fn process_chunk(&mut self, chunk: &[u8]) -> Result<(), String> {
+        self.driver.push(chunk)
+    }
+
+    pub(crate) fn finalize_internal(&mut self) -> Result<ProfilerResult, String> {
+        self.driver.finish()
+    }
+}
+
+impl AvroSource {
+    fn restore(checkpoint: AvroCheckpoint) -> Result<Self, String> {
+        let mut source = AvroSource::new();
+        if !checkpoint.header_bytes.is_empty() {
+            let (header, _) = parse_container_header(&checkpoint.header_bytes)?
+                .ok_or_else(|| "Incomplete Avro header in checkpoint".to_string())?;
+            source.init_columns(header.schema.clone())?;
+            source.container = Some(header);
+        }
+        source.header_bytes = checkpoint.header_bytes;
+        source.union_stats = checkpoint.union_stats;
+        source.buffer = checkpoint.buffer;
+        source.buffer_offset = checkpoint.buffer_offset;
+        source.resyncing = checkpoint.resyncing;
+        source.block_index = checkpoint.block_index;
+        source.progress = checkpoint.progress;
+        source.finished = checkpoint.finished;
+        Ok(source)
+    }
+
+    fn init_columns(&mut self, schema: Schema) -> Result<(), String> {
         self.schema_json = serde_json::to_string_pretty(&schema).unwrap_or_default();
-        
-        // Let's use the reader iterator.
-        let mut batch: Vec<Vec<String>> = Vec::with_capacity(1000);
-        let batch_size = 1000;
-        
-        for record_result in reader {
-            let record = record_result.map_err(|e| e.to_string())?;
-            
-            // First time initialization of headers if needed
-            let serde_value: Value =  apache_avro::from_value(&record).map_err(|e| e.to_string())?;
-            
-            if self.headers.is_empty() {
-                // Determine headers from the first record structure (flattened)
-                // This aligns with how we handle JSON
-                self.headers = extract_headers_from_value(&serde_value, "");
-                let mut profiler = Profiler::new(self.headers.clone());
-                profiler.avro_schema = Some(self.schema_json.clone());
-                self.profiler = Some(profiler);
-            }
-            
-            let row = flatten_avro_value(&serde_value, &self.headers);
-            batch.push(row);
-
-            if batch.len() >= batch_size {
-                if let Some(ref mut p) = self.profiler {
-                    p.update_batch(&batch);
-                }
-                batch.clear();
+        let (columns, unions) = AvroColumns::new(schema)?;
+        self.headers = columns.headers.clone();
+        self.union_stats = unions;
+        self.columns = Some(columns);
+        Ok(())
+    }
+
+    /// Frames the next data block: record count, byte size, payload and the
+    /// trailing sync marker, which must match the one from the header.
+    fn next_block(&mut self) -> BlockStep {
+        let sync = match self.container {
+            Some(ref header) => header.sync,
+            None => return BlockStep::NeedMore,
+        };
+
+        let buf = &self.buffer[self.read_pos..];
+        let (count, count_len) = match read_long(buf) {
+            Ok(Some(v)) => v,
+            Ok(None) => return BlockStep::NeedMore,
+            Err(e) => return BlockStep::Corrupt(e),
+        };
+        let (size, size_len) = match read_long(&buf[count_len..]) {
+            Ok(Some(v)) => v,
+            Ok(None) => return BlockStep::NeedMore,
+            Err(e) => return BlockStep::Corrupt(e),
+        };
+        if count < 0 || !(0..=MAX_BLOCK_BYTES).contains(&size) {
+            return BlockStep::Corrupt(format!("invalid block header (count {}, size {})", count, size));
+        }
+
+        let data_start = count_len + size_len;
+        let data_end = data_start + size as usize;
+        if buf.len() < data_end + SYNC_SIZE {
+            // A sync marker inside the claimed payload means the size field is
+            // corrupt; without this check we would buffer until end of file.
+            let scan_from = self.sync_scan_pos.saturating_sub(SYNC_SIZE - 1).max(data_start);
+            self.sync_scan_pos = buf.len();
+            if buf[scan_from..].windows(SYNC_SIZE).any(|w| w == sync) {
+                return BlockStep::Corrupt("block size overruns the next sync marker".to_string());
             }
+            return BlockStep::NeedMore;
+        }
+        if buf[data_end..data_end + SYNC_SIZE] != sync {
+            return BlockStep::Corrupt("sync marker mismatch".to_string());
         }
 
-        // Final batch
-        if !batch.is_empty() {
-            if let Some(ref mut p) = self.profiler {
-                p.update_batch(&batch);
+        BlockStep::Block {
+            count: count as usize,
+            data: buf[data_start..data_end].to_vec(),
+            len: data_end + SYNC_SIZE,
+        }
+    }
+
+    /// Decodes every record of a block. A block is profiled all-or-nothing so
+    /// a decoding error never leaves half a block in the statistics.
+    fn decode_block(&mut self, count: usize, mut data: Vec<u8>) -> Result<Vec<Vec<String>>, String> {
+        let (codec, columns) = match (&self.container, &self.columns) {
+            (Some(header), Some(columns)) => (header.codec, columns),
+            _ => return Err("Profiler not initialized".to_string()),
+        };
+        decompress_block(codec, &mut data, MAX_BLOCK_BYTES as usize)?;
+        // Every record takes at least a byte unless the schema encodes to
+        // nothing, so a larger count is corrupt; checked before it sizes an
+        // allocation
+        let max_count = if columns.zero_width { MAX_BLOCK_RECORDS } else { data.len() };
+        if count > max_count {
+            return Err(format!("invalid block header (count {} in {} bytes)", count, data.len()));
+        }
+
+        let mut records = Vec::with_capacity(count);
+        let mut reader = &data[..];
+        for _ in 0..count {
+            let record = apache_avro::from_avro_datum(&columns.schema, &mut reader, None)
+                .map_err(|e| e.to_string())?;
+            records.push(record);
+        }
+
+        Ok(records.iter()
+            .map(|record| columns.flatten_record(record, &mut self.union_stats))
+            .collect())
+    }
+
+    fn skip_block(&mut self, byte_offset: u64, reason: String) {
+        self.progress.skipped_blocks.push(AvroSkippedBlock {
+            block_index: self.block_index,
+            byte_offset,
+            reason,
+        });
+    }
+
+    /// Discards bytes up to and including the next sync marker. Returns false
+    /// if none is buffered yet; the tail that could hold a partial marker is kept.
+    fn resync(&mut self) -> bool {
+        let sync = match self.container {
+            Some(ref header) => header.sync,
+            None => return false,
+        };
+        let buf = &self.buffer[self.read_pos..];
+        match buf.windows(SYNC_SIZE).position(|w| w == sync) {
+            Some(pos) => {
+                self.consume(pos + SYNC_SIZE);
+                self.resyncing = false;
+                true
+            }
+            None => {
+                let skip = buf.len() - buf.len().min(SYNC_SIZE - 1);
+                self.consume(skip);
+                false
             }
         }
+    }
 
-        // Finalize
-        if let Some(ref mut p) = self.profiler {
-            Ok(p.finalize())
-        } else {
-            Err("Profiler not initialized".to_string())
+    /// Once input has ended, reports bytes that never completed a block.
+    /// Bytes left while resyncing belong to a block that was already reported.
+    fn discard_tail(&mut self) {
+        let remaining = self.buffer.len() - self.read_pos;
+        if !self.finished || remaining == 0 {
+            return;
         }
+        if !self.resyncing {
+            self.skip_block(self.buffer_offset, "truncated block at end of file".to_string());
+        }
+        self.consume(remaining);
+    }
+
+    fn consume(&mut self, len: usize) {
+        self.read_pos += len;
+        self.sync_scan_pos = 0;
+        self.buffer_offset += len as u64;
+        self.progress.bytes_consumed = self.buffer_offset;
     }
 }
 
-// Helper to extract headers from a flattened view of the value
-fn extract_headers_from_value(val: &Value, prefix: &str) -> Vec<String> {
-    let mut headers = Vec::new();
-    match val {
-        Value::Object(map) => {
-            for (k, v) in map {
-                let full_key = if prefix.is_empty() { k.clone() } else { format!("{}.{}", prefix, k) };
-                match v {
-                    Value::Object(_) => {
-                        headers.extend(extract_headers_from_value(v, &full_key));
-                    },
-                    _ => headers.push(full_key),
-                }
+/// Decompresses a block payload in place. Output beyond `limit` bytes makes
+/// the block corrupt rather than growing without bound.
+fn decompress_block(codec: Codec, data: &mut Vec<u8>, limit: usize) -> Result<(), String> {
+    let too_large = || format!("block decompresses to more than {} bytes", limit);
+    match codec {
+        Codec::Null => {}
+        Codec::Deflate => {
+            let mut out = Vec::new();
+            flate2::read::DeflateDecoder::new(&data[..])
+                .take(limit as u64 + 1)
+                .read_to_end(&mut out)
+                .map_err(|e| format!("deflate: {}", e))?;
+            if out.len() > limit {
+                return Err(too_large());
             }
-        },
-        _ => headers.push(if prefix.is_empty() { "value".to_string() } else { prefix.to_string() }),
-    }
-    // Sort headers for deterministic order? Or keep insertion order?
-    // Since we rely on index, we must respect the order we decided on.
-    // Maps are unordered. We should probably sort them to be consistent across rows if schema changes (rare in Avro).
-    // Or better: Use the Schema to determine order if possible.
-    // For now, let's sort to ensure stability.
-    headers.sort(); 
-    headers
-}
-
-// Helper to flatten value into row based on known headers
-fn flatten_avro_value(val: &Value, headers: &[String]) -> Vec<String> {
-    let mut flat_map = HashMap::new();
-    flatten_recursive(val, "", &mut flat_map);
-    
-    headers.iter().map(|h| {
-        flat_map.get(h).cloned().unwrap_or_default()
-    }).collect()
-}
-
-fn flatten_recursive(val: &Value, prefix: &str, output: &mut HashMap<String, String>) {
-    match val {
-        Value::Object(map) => {
-            for (k, v) in map {
-                let full_key = if prefix.is_empty() { k.clone() } else { format!("{}.{}", prefix, k) };
-                flatten_recursive(v, &full_key, output);
+            *data = out;
+        }
+        Codec::Snappy => {
+            // The decompressed length is declared up front, followed by the
+            // payload and a 4-byte CRC; checked before the codec allocates it
+            let payload = &data[..data.len().saturating_sub(4)];
+            let len = snap::raw::decompress_len(payload).map_err(|e| format!("snappy: {}", e))?;
+            if len > limit {
+                return Err(too_large());
             }
+            codec.decompress(data).map_err(|e| e.to_string())?;
+        }
+    }
+    Ok(())
+}
+
+/// True if every record of `schema` encodes to zero bytes.
+fn is_zero_width(schema: &Schema, names: &SchemaNames, visiting: &mut Vec<Name>) -> bool {
+    match schema {
+        Schema::Null => true,
+        Schema::Fixed(fixed) => fixed.size == 0,
+        Schema::Record(record) => {
+            if visiting.contains(&record.name) {
+                return false;
+            }
+            visiting.push(record.name.clone());
+            let zero = record.fields.iter().all(|field| is_zero_width(&field.schema, names, visiting));
+            visiting.pop();
+            zero
+        }
+        Schema::Ref { name } => names.get(name).is_some_and(|resolved| is_zero_width(resolved, names, visiting)),
+        _ => false,
+    }
+}
+
+/// Parses the object container header (magic, metadata map, sync marker).
+/// Returns `Ok(None)` while `buf` does not yet hold the complete header.
+fn parse_container_header(buf: &[u8]) -> Result<Option<(ContainerHeader, usize)>, String> {
+    if buf.len() < AVRO_MAGIC.len() {
+        return Ok(None);
+    }
+    if &buf[..AVRO_MAGIC.len()] != AVRO_MAGIC {
+        return Err("Not an Avro object container file".to_string());
+    }
+
+    let mut pos = AVRO_MAGIC.len();
+    let mut metadata: HashMap<String, Vec<u8>> = HashMap::new();
+    loop {
+        let Some((mut count, n)) = read_long(&buf[pos..])? else { return Ok(None) };
+        pos += n;
+        if count == 0 {
+            break;
+        }
+        if count < 0 {
+            // Negative counts are followed by the block size in bytes
+            let Some((_, n)) = read_long(&buf[pos..])? else { return Ok(None) };
+            pos += n;
+            count = count.checked_neg().ok_or("invalid block count in Avro header")?;
+        }
+        for _ in 0..count {
+            let Some((key, n)) = read_bytes(&buf[pos..])? else { return Ok(None) };
+            pos += n;
+            let Some((value, n)) = read_bytes(&buf[pos..])? else { return Ok(None) };
+            pos += n;
+            metadata.insert(String::from_utf8_lossy(key).to_string(), value.to_vec());
+        }
+    }
+
+    if buf.len() < pos + SYNC_SIZE {
+        return Ok(None);
+    }
+    let mut sync = [0u8; SYNC_SIZE];
+    sync.copy_from_slice(&buf[pos..pos + SYNC_SIZE]);
+    pos += SYNC_SIZE;
+
+    let schema_bytes = metadata.get("avro.schema").ok_or("Missing avro.schema in header")?;
+    let schema = Schema::parse_str(&String::from_utf8_lossy(schema_bytes)).map_err(|e| e.to_string())?;
+    let codec_name = metadata.get("avro.codec")
+        .map(|c| String::from_utf8_lossy(c).to_string())
+        .unwrap_or_else(|| "null".to_string());
+    let codec = Codec::from_str(&codec_name).map_err(|_| format!("Unsupported Avro codec: {}", codec_name))?;
+
+    Ok(Some((ContainerHeader { schema, codec, sync }, pos)))
+}
+
+/// Reads a zig-zag varint long. Returns the value and its encoded length,
+/// or `Ok(None)` if `buf` ends mid-varint.
+fn read_long(buf: &[u8]) -> Result<Option<(i64, usize)>, String> {
+    let mut raw: u64 = 0;
+    for (i, &byte) in buf.iter().enumerate() {
+        if i >= 10 {
+            return Err("invalid variable-length integer".to_string());
+        }
+        raw |= ((byte & 0x7f) as u64) << (7 * i);
+        if byte & 0x80 == 0 {
+            let value = ((raw >> 1) as i64) ^ -((raw & 1) as i64);
+            return Ok(Some((value, i + 1)));
+        }
+    }
+    Ok(None)
+}
+
+/// Reads a length-prefixed byte string.
+fn read_bytes(buf: &[u8]) -> Result<Option<(&[u8], usize)>, String> {
+    let Some((len, n)) = read_long(buf)? else { return Ok(None) };
+    if len < 0 {
+        return Err("negative length in Avro header".to_string());
+    }
+    let end = n + len as usize;
+    if buf.len() < end {
+        return Ok(None);
+    }
+    Ok(Some((&buf[n..end], end)))
+}
+
+fn join_key(prefix: &str, name: &str) -> String {
+    if prefix.is_empty() { name.to_string() } else { format!("{}.{}", prefix, name) }
+}
+
+fn column_key(prefix: &str) -> String {
+    if prefix.is_empty() { "value".to_string() } else { prefix.to_string() }
+}
+
+/// Derives flattened column names from the writer schema, in declaration order,
+/// along with the logical type of each leaf. Nested records become dotted
+/// columns; a union of `null` and a single record is expanded the same way.
+/// Any other union is a single column.
+fn collect_columns(
+    schema: &Schema,
+    prefix: &str,
+    names: &SchemaNames,
+    visiting: &mut Vec<String>,
+    leaves: &mut Vec<(String, Option<LogicalType>)>,
+    unions: &mut Vec<AvroUnionStats>,
+) {
+    match schema {
+        Schema::Record(record) => {
+            let fullname = record.name.fullname(None);
+            if visiting.contains(&fullname) {
+                // Recursive type: profile the nested value as a single JSON column
+                leaves.push((column_key(prefix), None));
+                return;
+            }
+            visiting.push(fullname);
+            for field in &record.fields {
+                let key = join_key(prefix, &field.name);
+                collect_columns(&field.schema, &key, names, visiting, leaves, unions);
+            }
+            visiting.pop();
+        }
+        Schema::Ref { name } => match names.get(name) {
+            Some(resolved) => collect_columns(resolved, prefix, names, visiting, leaves, unions),
+            None => leaves.push((column_key(prefix), None)),
         },
-        Value::Array(arr) => {
-            // Arrays in Avro are common. Serialize as string representation like "[item1, item2]"
-            // or count? The JSON parser did `[array:N]`.
-            // Let's serialize content for now to be useful.
-            output.insert(prefix.to_string(), val.to_string());
-        },
-        Value::Null => {
-            output.insert(prefix.to_string(), String::new());
-        },
-        Value::String(s) => {
-            output.insert(prefix.to_string(), s.clone());
-        },
-        _ => {
-            output.insert(prefix.to_string(), val.to_string());
+        Schema::Union(union) => {
+            unions.push(AvroUnionStats::new(column_key(prefix), union));
+            match expandable_branch(union, names) {
+                Some(branch) => collect_columns(branch, prefix, names, visiting, leaves, unions),
+                None => leaves.push((column_key(prefix), logical_type_of(schema, names))),
+            }
+        }
+        _ => leaves.push((column_key(prefix), logical_type_of(schema, names))),
+    }
+}
+
+/// Maps an Avro logical type to the profiler's `LogicalType`. A union of
+/// `null` and a single logical type (the usual optional field) counts too.
+fn logical_type_of(schema: &Schema, names: &SchemaNames) -> Option<LogicalType> {
+    match schema {
+        Schema::Decimal(d) => Some(LogicalType::Decimal { precision: d.precision, scale: d.scale }),
+        Schema::Date => Some(LogicalType::Date),
+        Schema::TimeMillis => Some(LogicalType::TimeMillis),
+        Schema::TimeMicros => Some(LogicalType::TimeMicros),
+        Schema::TimestampMillis => Some(LogicalType::TimestampMillis),
+        Schema::TimestampMicros => Some(LogicalType::TimestampMicros),
+        Schema::LocalTimestampMillis => Some(LogicalType::LocalTimestampMillis),
+        Schema::LocalTimestampMicros => Some(LogicalType::LocalTimestampMicros),
+        Schema::Uuid => Some(LogicalType::Uuid),
+        Schema::Duration => Some(LogicalType::Duration),
+        Schema::Ref { name } => names.get(name).and_then(|s| logical_type_of(s, names)),
+        Schema::Union(union) => {
+            let mut non_null = union.variants().iter().filter(|v| !matches!(v, Schema::Null));
+            match (non_null.next(), non_null.next()) {
+                (Some(branch), None) => logical_type_of(branch, names),
+                _ => None,
+            }
         }
+        _ => None,
     }
 }
 
+/// Returns the record branch of a `["null", record]` style union.
+fn expandable_branch<'a>(union: &'a UnionSchema, names: &'a SchemaNames) -> Option<&'a Schema> {
+    let mut non_null = union.variants().iter().filter(|v| !matches!(v, Schema::Null));
+    let branch = non_null.next()?;
+    if non_null.next().is_some() {
+        return None;
+    }
+    let is_record = match branch {
+        Schema::Record(_) => true,
+        Schema::Ref { name } => matches!(names.get(name), Some(Schema::Record(_))),
+        _ => false,
+    };
+    if is_record { Some(branch) } else { None }
+}
+
+fn branch_name(schema: &Schema) -> String {
+    match schema {
+        Schema::Null => "null".to_string(),
+        Schema::Boolean => "boolean".to_string(),
+        Schema::Int => "int".to_string(),
+        Schema::Long => "long".to_string(),
+        Schema::Float => "float".to_string(),
+        Schema::Double => "double".to_string(),
+        Schema::Bytes => "bytes".to_string(),
+        Schema::String => "string".to_string(),
+        Schema::Array(_) => "array".to_string(),
+        Schema::Map(_) => "map".to_string(),
+        Schema::Union(_) => "union".to_string(),
+        Schema::Record(r) => r.name.name.clone(),
+        Schema::Enum(e) => e.name.name.clone(),
+        Schema::Fixed(f) => f.name.name.clone(),
+        Schema::Ref { name } => name.name.clone(),
+        Schema::Decimal(_) => "decimal".to_string(),
+        Schema::Uuid => "uuid".to_string(),
+        Schema::Date => "date".to_string(),
+        Schema::TimeMillis => "time-millis".to_string(),
+        Schema::TimeMicros => "time-micros".to_string(),
+        Schema::TimestampMillis => "timestamp-millis".to_string(),
+        Schema::TimestampMicros => "timestamp-micros".to_string(),
+        Schema::LocalTimestampMillis => "local-timestamp-millis".to_string(),
+        Schema::LocalTimestampMicros => "local-timestamp-micros".to_string(),
+        Schema::Duration => "duration".to_string(),
+    }
+}
+
+// Converts a leaf value to the string handed to the Profiler. Logical types
+// get canonical text forms (ISO 8601 dates/times, scaled decimals); arrays,
+// maps and unexpanded records are serialized as JSON.
+fn avro_leaf_to_string(schema: &Schema, value: &AvroValue) -> String {
+    match value {
+        AvroValue::Date(days) => DateTime::from_timestamp(*days as i64 * 86_400, 0)
+            .map(|dt| dt.format("%Y-%m-%d").to_string())
+            .unwrap_or_default(),
+        AvroValue::TimeMillis(ms) => format_time(*ms as i64 * 1_000, "%H:%M:%S%.3f"),
+        AvroValue::TimeMicros(us) => format_time(*us, "%H:%M:%S%.6f"),
+        AvroValue::TimestampMillis(ms) => DateTime::from_timestamp_millis(*ms)
+            .map(|dt| dt.format("%Y-%m-%dT%H:%M:%S%.3fZ").to_string())
+            .unwrap_or_default(),
+        AvroValue::TimestampMicros(us) => DateTime::from_timestamp_micros(*us)
+            .map(|dt| dt.format("%Y-%m-%dT%H:%M:%S%.6fZ").to_string())
+            .unwrap_or_default(),
+        AvroValue::LocalTimestampMillis(ms) => DateTime::from_timestamp_millis(*ms)
+            .map(|dt| dt.naive_utc().format("%Y-%m-%dT%H:%M:%S%.3f").to_string())
+            .unwrap_or_default(),
+        AvroValue::LocalTimestampMicros(us) => DateTime::from_timestamp_micros(*us)
+            .map(|dt| dt.naive_utc().format("%Y-%m-%dT%H:%M:%S%.6f").to_string())
+            .unwrap_or_default(),
+        AvroValue::Decimal(decimal) => {
+            let scale = match schema {
+                Schema::Decimal(d) => d.scale,
+                _ => 0,
+            };
+            format_scaled_decimal(&BigInt::from(decimal.clone()).to_string(), scale)
+        }
+        AvroValue::Uuid(uuid) => uuid.to_string(),
+        AvroValue::Duration(duration) => {
+            // ISO 8601 duration; Avro durations carry months, days and millis separately
+            let millis = u32::from(duration.millis());
+            format!(
+                "P{}M{}DT{}.{:03}S",
+                u32::from(duration.months()),
+                u32::from(duration.days()),
+                millis / 1_000,
+                millis % 1_000
+            )
+        }
+        _ => match apache_avro::from_value::<Value>(value) {
+            Ok(Value::Null) => String::new(),
+            Ok(Value::String(s)) => s,
+            Ok(other) => other.to_string(),
+            Err(_) => String::new(),
+        },
+    }
+}
+
+fn format_time(micros_since_midnight: i64, format: &str) -> String {
+    let secs = micros_since_midnight.div_euclid(1_000_000) as u32;
+    let nanos = (micros_since_midnight.rem_euclid(1_000_000) * 1_000) as u32;
+    NaiveTime::from_num_seconds_from_midnight_opt(secs, nanos)
+        .map(|t| t.format(format).to_string())
+        .unwrap_or_default()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use serde_json::json;
+    use crate::stats::types::DataType;
+
+    fn resolve_columns(raw_schema: &str) -> (Vec<String>, Vec<AvroUnionStats>) {
+        let schema = Schema::parse_str(raw_schema).unwrap();
+        let (columns, unions) = AvroColumns::new(schema).unwrap();
+        (columns.headers, unions)
+    }
 
     #[test]
-    fn test_extract_headers() {
-        let val = json!({
-            "a": 1,
-            "b": {
-                "c": 2,
-                "d": "test"
-            },
-            "e": [1, 2, 3]
-        });
-        let headers = extract_headers_from_value(&val, "");
-        assert_eq!(headers, vec!["a", "b.c", "b.d", "e"]);
+    fn test_headers_follow_schema_order() {
+        let (headers, unions) = resolve_columns(r#"
+            {
+                "type": "record",
+                "name": "event",
+                "fields": [
+                    {"name": "zeta", "type": "long"},
+                    {"name": "address", "type": ["null", {
+                        "type": "record",
+                        "name": "address",
+                        "fields": [
+                            {"name": "street", "type": "string"},
+                            {"name": "city", "type": "string"}
+                        ]
+                    }]},
+                    {"name": "alpha", "type": ["null", "string", "long"]},
+                    {"name": "tags", "type": {"type": "array", "items": "string"}}
+                ]
+            }
+        "#);
+        assert_eq!(headers, vec!["zeta", "address.street", "address.city", "alpha", "tags"]);
+        assert_eq!(unions.len(), 2);
+        assert_eq!(unions[0].column, "address");
+        assert!(unions[0].branch_counts.is_empty());
+        assert_eq!(unions[1].branches, vec!["null", "string", "long"]);
+        assert_eq!(unions[1].branch_counts.len(), 3);
     }
 
     #[test]
-    fn test_flatten_avro_value() {
-        let val = json!({
-            "a": 1,
-            "b": {
-                "c": 2,
-                "d": "test"
+    fn test_recursive_schema_is_single_column() {
+        let (headers, _) = resolve_columns(r#"
+            {
+                "type": "record",
+                "name": "node",
+                "fields": [
+                    {"name": "value", "type": "int"},
+                    {"name": "next", "type": ["null", "node"]}
+                ]
             }
-        });
-        let headers = vec!["a".to_string(), "b.c".to_string(), "b.d".to_string()];
-        let row = flatten_avro_value(&val, &headers);
-        assert_eq!(row, vec!["1", "2", "test"]);
+        "#);
+        assert_eq!(headers, vec!["value", "next"]);
+    }
+
+    #[test]
+    fn test_union_fields_after_first_record() {
+        use apache_avro::types::Record;
+        use apache_avro::Writer;
+
+        let raw_schema = r#"
+            {
+                "type": "record",
+                "name": "test",
+                "fields": [
+                    {"name": "id", "type": "long"},
+                    {"name": "detail", "type": ["null", {
+                        "type": "record",
+                        "name": "detail",
+                        "fields": [{"name": "note", "type": "string"}]
+                    }], "default": null},
+                    {"name": "amount", "type": ["null", "long", "string"], "default": null}
+                ]
+            }
+        "#;
+        let schema = Schema::parse_str(raw_schema).unwrap();
+        let detail_schema = match &schema {
+            Schema::Record(rs) => match &rs.fields[1].schema {
+                Schema::Union(u) => u.variants()[1].clone(),
+                _ => unreachable!(),
+            },
+            _ => unreachable!(),
+        };
+        let mut writer = Writer::new(&schema, Vec::new());
+
+        // First record has every union set to null
+        let mut first = Record::new(writer.schema()).unwrap();
+        first.put("id", 1i64);
+        first.put("detail", AvroValue::Union(0, Box::new(AvroValue::Null)));
+        first.put("amount", AvroValue::Union(0, Box::new(AvroValue::Null)));
+        writer.append(first).unwrap();
+
+        let mut detail = Record::new(&detail_schema).unwrap();
+        detail.put("note", "late field");
+        let mut second = Record::new(writer.schema()).unwrap();
+        second.put("id", 2i64);
+        second.put("detail", AvroValue::Union(1, Box::new(detail.into())));
+        second.put("amount", AvroValue::Union(1, Box::new(AvroValue::Long(10))));
+        writer.append(second).unwrap();
+
+        let mut third = Record::new(writer.schema()).unwrap();
+        third.put("id", 3i64);
+        third.put("detail", AvroValue::Union(0, Box::new(AvroValue::Null)));
+        third.put("amount", AvroValue::Union(2, Box::new(AvroValue::String("n/a".to_string()))));
+        writer.append(third).unwrap();
+
+        let bytes = writer.into_inner().unwrap();
+        let mut profiler = AvroProfiler::with_config(ProfilerConfig::default());
+        let result = profiler.parse_and_profile_internal(&bytes).unwrap();
+
+        assert_eq!(profiler.driver.source().headers, vec!["id", "detail.note", "amount"]);
+        let note = &result.column_profiles[1];
+        assert_eq!(note.base_stats.missing, 2);
+        assert_eq!(note.sample_values, vec!["late field"]);
+
+        let unions = result.avro_union_stats.unwrap();
+        assert_eq!(unions[0].column, "detail");
+        assert_eq!(unions[0].null_count, 2);
+        assert_eq!(unions[0].value_count, 1);
+        assert_eq!(unions[1].column, "amount");
+        let counts: Vec<u64> = unions[1].branch_counts.iter().map(|b| b.count).collect();
+        assert_eq!(counts, vec![1, 1, 1]);
     }
 
     #[test]
@@ -227,12 +1110,334 @@ mod tests {
         writer.append(record).unwrap();
 
         let bytes = writer.into_inner().unwrap();
-        let mut profiler = AvroProfiler::new();
+        let mut profiler = AvroProfiler::with_config(ProfilerConfig::default());
         
         // This will call the actual logic
         let _ = profiler.parse_and_profile_internal(&bytes).unwrap();
         
-        assert_eq!(profiler.headers, vec!["id", "name", "nested.val"]);
-        assert!(profiler.schema_json.contains("nested"));
+        assert_eq!(profiler.driver.source().headers, vec!["id", "name", "nested.val"]);
+        assert!(profiler.driver.source().schema_json.contains("nested"));
+    }
+
+    fn write_blocks(codec: Codec, blocks: usize, per_block: i64) -> Vec<u8> {
+        use apache_avro::types::Record;
+        use apache_avro::Writer;
+
+        let schema = Schema::parse_str(r#"
+            {
+                "type": "record",
+                "name": "row",
+                "fields": [
+                    {"name": "id", "type": "long"},
+                    {"name": "label", "type": "string"}
+                ]
+            }
+        "#).unwrap();
+        let mut writer = Writer::with_codec(&schema, Vec::new(), codec);
+        for b in 0..blocks as i64 {
+            for i in 0..per_block {
+                let mut record = Record::new(writer.schema()).unwrap();
+                record.put("id", b * per_block + i);
+                record.put("label", format!("row-{}", i));
+                writer.append(record).unwrap();
+            }
+            writer.flush().unwrap();
+        }
+        writer.into_inner().unwrap()
+    }
+
+    #[test]
+    fn test_read_long() {
+        assert_eq!(read_long(&[0x00]).unwrap(), Some((0, 1)));
+        assert_eq!(read_long(&[0x01]).unwrap(), Some((-1, 1)));
+        assert_eq!(read_long(&[0x96, 0x01]).unwrap(), Some((75, 2)));
+        assert_eq!(read_long(&[0x96]).unwrap(), None);
+        assert!(read_long(&[0xff; 11]).is_err());
+    }
+
+    #[test]
+    fn test_decompressed_block_size_is_capped() {
+        let raw = vec![0u8; 4096];
+        for codec in [Codec::Deflate, Codec::Snappy] {
+            let mut compressed = raw.clone();
+            codec.compress(&mut compressed).unwrap();
+
+            let mut data = compressed.clone();
+            decompress_block(codec, &mut data, raw.len()).unwrap();
+            assert_eq!(data, raw);
+
+            let mut data = compressed;
+            let err = decompress_block(codec, &mut data, raw.len() - 1).unwrap_err();
+            assert!(err.contains("decompresses to more than"), "{:?}: {}", codec, err);
+        }
+    }
+
+    #[test]
+    fn test_chunked_container_read() {
+        let bytes = write_blocks(Codec::Deflate, 4, 25);
+
+        let mut profiler = AvroProfiler::with_config(ProfilerConfig::default());
+        for chunk in bytes.chunks(7) {
+            profiler.process_chunk(chunk).unwrap();
+            // Never more than one block plus a chunk buffered
+            assert!(profiler.driver.source().buffer.len() < 1024);
+        }
+        assert!(profiler.driver.source().progress.header_parsed);
+        assert_eq!(profiler.driver.source().progress.blocks_read, 4);
+        assert_eq!(profiler.driver.source().progress.records_read, 100);
+
+        let result = profiler.finalize_internal().unwrap();
+        assert_eq!(result.total_rows, 100);
+        assert_eq!(profiler.driver.source().progress.bytes_consumed, bytes.len() as u64);
+        assert!(profiler.driver.source().progress.skipped_blocks.is_empty());
+    }
+
+    #[test]
+    fn test_whole_file_push_decodes_one_block_per_batch() {
+        let bytes = write_blocks(Codec::Deflate, 4, 25);
+
+        let mut source = AvroSource::new();
+        source.push(&bytes).unwrap();
+        assert_eq!(source.progress.blocks_read, 0);
+        for block in 1..=4 {
+            assert_eq!(source.next_batch().unwrap().unwrap().len(), 25);
+            assert_eq!(source.progress.blocks_read, block);
+        }
+        source.finish().unwrap();
+        assert!(source.next_batch().unwrap().is_none());
+        assert_eq!(source.progress.bytes_consumed, bytes.len() as u64);
+        assert!(source.progress.skipped_blocks.is_empty());
+    }
+
+    #[test]
+    fn test_checkpoint_resume_matches_uninterrupted_run() {
+        let bytes = write_blocks(Codec::Deflate, 4, 25);
+        let expected = AvroProfiler::with_config(ProfilerConfig::default())
+            .parse_and_profile_internal(&bytes).unwrap();
+
+        // Interrupt inside the header, then inside a block
+        let mut profiler = AvroProfiler::with_config(ProfilerConfig::default());
+        let mut offset = 0;
+        for split in [20, bytes.len() / 2] {
+            profiler.process_chunk(&bytes[offset..split]).unwrap();
+            let checkpoint = profiler.driver.checkpoint().unwrap();
+            profiler = AvroProfiler { driver: ProfileDriver::resume(&checkpoint).unwrap() };
+            offset = profiler.driver.progress().bytes_received as usize;
+            assert_eq!(offset, split);
+        }
+        assert!(!profiler.driver.source().buffer.is_empty());
+        profiler.process_chunk(&bytes[offset..]).unwrap();
+        let result = profiler.finalize_internal().unwrap();
+
+        assert_eq!(result.total_rows, expected.total_rows);
+        assert_eq!(profiler.driver.source().progress.blocks_read, 4);
+        for (got, want) in result.column_profiles.iter().zip(&expected.column_profiles) {
+            assert_eq!(got.base_stats.distinct_estimate, want.base_stats.distinct_estimate);
+            assert_eq!(got.base_stats.inferred_type, want.base_stats.inferred_type);
+        }
+        assert_eq!(result.avro_schema, expected.avro_schema);
+    }
+
+    #[test]
+    fn test_corrupt_block_is_skipped() {
+        let mut bytes = write_blocks(Codec::Null, 3, 2);
+
+        // Locate the second block and overwrite its record count with -1
+        let (_, header_len) = parse_container_header(&bytes).unwrap().unwrap();
+        let (_, count_len) = read_long(&bytes[header_len..]).unwrap().unwrap();
+        let (size, size_len) = read_long(&bytes[header_len + count_len..]).unwrap().unwrap();
+        let second_block = header_len + count_len + size_len + size as usize + SYNC_SIZE;
+        bytes[second_block] = 0x01;
+
+        let mut profiler = AvroProfiler::with_config(ProfilerConfig::default());
+        for chunk in bytes.chunks(5) {
+            profiler.process_chunk(chunk).unwrap();
+        }
+        let result = profiler.finalize_internal().unwrap();
+
+        assert_eq!(result.total_rows, 4);
+        assert_eq!(profiler.driver.source().progress.blocks_read, 2);
+        assert_eq!(profiler.driver.source().progress.skipped_blocks.len(), 1);
+        let skipped = &profiler.driver.source().progress.skipped_blocks[0];
+        assert_eq!(skipped.block_index, 1);
+        assert_eq!(skipped.byte_offset, second_block as u64);
+    }
+
+    #[test]
+    fn test_forged_block_count_is_skipped() {
+        let bytes = write_blocks(Codec::Null, 3, 2);
+
+        // Replace the second block's record count with 2^40, zigzag encoded
+        let (_, header_len) = parse_container_header(&bytes).unwrap().unwrap();
+        let (_, count_len) = read_long(&bytes[header_len..]).unwrap().unwrap();
+        let (size, size_len) = read_long(&bytes[header_len + count_len..]).unwrap().unwrap();
+        let second_block = header_len + count_len + size_len + size as usize + SYNC_SIZE;
+        let mut forged = bytes[..second_block].to_vec();
+        forged.extend_from_slice(&[0x80, 0x80, 0x80, 0x80, 0x80, 0x40]);
+        forged.extend_from_slice(&bytes[second_block + count_len..]);
+
+        let mut profiler = AvroProfiler::with_config(ProfilerConfig::default());
+        profiler.process_chunk(&forged).unwrap();
+        let result = profiler.finalize_internal().unwrap();
+
+        assert_eq!(result.total_rows, 4);
+        let skipped = &profiler.driver.source().progress.skipped_blocks;
+        assert_eq!(skipped.len(), 1);
+        assert_eq!(skipped[0].block_index, 1);
+        assert!(skipped[0].reason.contains("invalid block header"));
+    }
+
+    #[test]
+    fn test_zero_byte_records() {
+        use apache_avro::types::Record;
+        use apache_avro::Writer;
+
+        let schema = Schema::parse_str(r#"
+            {"type": "record", "name": "row", "fields": [{"name": "nothing", "type": "null"}]}
+        "#).unwrap();
+        let mut writer = Writer::new(&schema, Vec::new());
+        for _ in 0..5 {
+            let mut record = Record::new(writer.schema()).unwrap();
+            record.put("nothing", AvroValue::Null);
+            writer.append(record).unwrap();
+        }
+        let bytes = writer.into_inner().unwrap();
+
+        let mut profiler = AvroProfiler::with_config(ProfilerConfig::default());
+        let result = profiler.parse_and_profile_internal(&bytes).unwrap();
+        assert_eq!(result.total_rows, 5);
+        assert!(profiler.driver.source().progress.skipped_blocks.is_empty());
+    }
+
+    #[test]
+    fn test_forged_count_of_zero_byte_records_is_skipped() {
+        use apache_avro::types::Record;
+        use apache_avro::Writer;
+
+        let schema = Schema::parse_str(r#"
+            {"type": "record", "name": "row", "fields": [{"name": "nothing", "type": "null"}]}
+        "#).unwrap();
+        let mut writer = Writer::new(&schema, Vec::new());
+        let mut record = Record::new(writer.schema()).unwrap();
+        record.put("nothing", AvroValue::Null);
+        writer.append(record).unwrap();
+        let bytes = writer.into_inner().unwrap();
+
+        // Replace the block's record count with 2^40; the records take no
+        // bytes, so the block size cannot catch it
+        let (_, header_len) = parse_container_header(&bytes).unwrap().unwrap();
+        let (_, count_len) = read_long(&bytes[header_len..]).unwrap().unwrap();
+        let mut forged = bytes[..header_len].to_vec();
+        forged.extend_from_slice(&[0x80, 0x80, 0x80, 0x80, 0x80, 0x40]);
+        forged.extend_from_slice(&bytes[header_len + count_len..]);
+
+        let mut profiler = AvroProfiler::with_config(ProfilerConfig::default());
+        let result = profiler.parse_and_profile_internal(&forged).unwrap();
+        assert_eq!(result.total_rows, 0);
+        let skipped = &profiler.driver.source().progress.skipped_blocks;
+        assert_eq!(skipped.len(), 1);
+        assert!(skipped[0].reason.contains("invalid block header"));
+    }
+
+    #[test]
+    fn test_truncated_file_reports_partial_block() {
+        let bytes = write_blocks(Codec::Null, 2, 3);
+        let mut profiler = AvroProfiler::with_config(ProfilerConfig::default());
+        profiler.process_chunk(&bytes[..bytes.len() - 4]).unwrap();
+        let result = profiler.finalize_internal().unwrap();
+
+        assert_eq!(result.total_rows, 3);
+        assert_eq!(profiler.driver.source().progress.skipped_blocks.len(), 1);
+        assert!(profiler.driver.source().progress.skipped_blocks[0].reason.contains("truncated"));
+    }
+
+    #[test]
+    fn test_rejects_non_container_input() {
+        let mut profiler = AvroProfiler::with_config(ProfilerConfig::default());
+        assert!(profiler.process_chunk(b"id,name\n1,a\n").is_err());
+    }
+
+    #[test]
+    fn test_metadata_count_of_i64_min_is_an_error() {
+        // Zigzag encoding of i64::MIN, followed by a block size of zero
+        let mut bytes = AVRO_MAGIC.to_vec();
+        bytes.extend_from_slice(&[0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x01, 0x00]);
+        assert!(parse_container_header(&bytes).is_err());
+    }
+
+    #[test]
+    fn test_logical_types() {
+        use apache_avro::types::Record;
+        use apache_avro::{Days, Decimal, Duration, Millis, Months, Writer};
+
+        let schema = Schema::parse_str(r#"
+            {
+                "type": "record",
+                "name": "payment",
+                "fields": [
+                    {"name": "amount", "type": {"type": "bytes", "logicalType": "decimal", "precision": 10, "scale": 2}},
+                    {"name": "booked", "type": ["null", {"type": "int", "logicalType": "date"}]},
+                    {"name": "at", "type": {"type": "long", "logicalType": "timestamp-micros"}},
+                    {"name": "local_at", "type": {"type": "long", "logicalType": "local-timestamp-millis"}},
+                    {"name": "cutoff", "type": {"type": "int", "logicalType": "time-millis"}},
+                    {"name": "id", "type": {"type": "string", "logicalType": "uuid"}},
+                    {"name": "term", "type": {"type": "fixed", "name": "term", "size": 12, "logicalType": "duration"}}
+                ]
+            }
+        "#).unwrap();
+        let mut writer = Writer::new(&schema, Vec::new());
+
+        // (unscaled amount bytes, days since epoch, micros since epoch)
+        let rows: [(&[u8], i32, i64); 3] = [
+            (&[0x30, 0x39], 20_742, 1_792_137_600_000_000),
+            (&[0xff, 0x06], 20_000, 1_700_000_000_123_456),
+            (&[0x01], 19_000, 1_600_000_000_000_000),
+        ];
+        for (i, (amount, days, micros)) in rows.iter().enumerate() {
+            let mut record = Record::new(writer.schema()).unwrap();
+            record.put("amount", AvroValue::Decimal(Decimal::from(amount.to_vec())));
+            let booked = if i == 2 {
+                AvroValue::Union(0, Box::new(AvroValue::Null))
+            } else {
+                AvroValue::Union(1, Box::new(AvroValue::Date(*days)))
+            };
+            record.put("booked", booked);
+            record.put("at", AvroValue::TimestampMicros(*micros));
+            record.put("local_at", AvroValue::LocalTimestampMillis(micros / 1_000));
+            record.put("cutoff", AvroValue::TimeMillis(61_000_005));
+            record.put("id", AvroValue::String("123e4567-e89b-12d3-a456-426614174000".to_string()));
+            record.put("term", AvroValue::Duration(Duration::new(Months::new(1), Days::new(2), Millis::new(3_500))));
+            writer.append(record).unwrap();
+        }
+
+        let bytes = writer.into_inner().unwrap();
+        let mut profiler = AvroProfiler::with_config(ProfilerConfig::default());
+        let result = profiler.parse_and_profile_internal(&bytes).unwrap();
+        let col = |name: &str| result.column_profiles.iter().find(|c| c.name == name).unwrap();
+
+        let amount = col("amount");
+        assert_eq!(amount.logical_type, Some(LogicalType::Decimal { precision: 10, scale: 2 }));
+        assert_eq!(amount.base_stats.inferred_type, DataType::Numeric);
+        let decimal = amount.decimal_stats.as_ref().unwrap();
+        assert_eq!(decimal.min.as_deref(), Some("-2.50"));
+        assert_eq!(decimal.max.as_deref(), Some("123.45"));
+
+        let booked = col("booked");
+        assert_eq!(booked.logical_type, Some(LogicalType::Date));
+        assert_eq!(booked.base_stats.missing, 1);
+        let range = booked.date_range.as_ref().unwrap();
+        assert_eq!(range.min, "2024-10-04");
+        assert_eq!(range.max, "2026-10-16");
+
+        let at = col("at");
+        assert_eq!(at.base_stats.inferred_type, DataType::Timestamp);
+        assert_eq!(at.date_range.as_ref().unwrap().min, "2020-09-13T12:26:40.000000Z");
+        assert!(col("local_at").sample_values[0].ends_with(".000"));
+
+        assert_eq!(col("cutoff").sample_values, vec!["16:56:40.005"]);
+        let id = col("id");
+        assert_eq!(id.logical_type, Some(LogicalType::Uuid));
+        assert_eq!(id.base_stats.inferred_type, DataType::String);
+        assert_eq!(col("term").sample_values, vec!["P1M2DT3.500S"]);
     }
 }
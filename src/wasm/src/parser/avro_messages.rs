@@ -0,0 +1,589 @@
+use wasm_bindgen::prelude::*;
+use apache_avro::rabin::Rabin;
+use apache_avro::Schema;
+use serde::Serialize;
+use ts_rs::TS;
+use crate::parser::avro::{AvroColumns, AvroUnionStats};
+use crate::parser::source::{ProfileDriver, RecordSource, SourceProgress, SourceSchema};
+use crate::stats::config::ProfilerConfig;
+use crate::stats::profiler::{Profiler, ProfilerResult};
+use std::collections::HashMap;
+
+/// How often a schema id (or single-object fingerprint) occurred in a message dump.
+#[derive(Serialize, Debug, Clone, TS)]
+#[ts(export)]
+pub struct AvroSchemaIdCount {
+    /// Confluent schema id; for single-object messages, the id the fingerprint is registered under
+    pub schema_id: Option<u32>,
+    /// Rabin fingerprint (hex) for single-object encoded messages
+    pub fingerprint: Option<String>,
+    pub count: u64,
+    /// False if no registered schema matched; those messages are skipped
+    pub registered: bool,
+}
+
+/// A message that could not be decoded.
+#[derive(Serialize, Debug, Clone, TS)]
+#[ts(export)]
+pub struct AvroSkippedMessage {
+    pub message_index: u64,
+    /// File offset of the message's length prefix
+    pub byte_offset: u64,
+    pub reason: String,
+}
+
+/// Progress of a message dump read, reported per chunk.
+#[derive(Serialize, Debug, Clone, Default, TS)]
+#[ts(export)]
+pub struct AvroMessageProgress {
+    pub bytes_consumed: u64,
+    pub messages_read: u64,
+    pub messages_skipped: u64,
+    pub schema_ids: Vec<AvroSchemaIdCount>,
+    /// First skipped messages, capped at 1000
+    pub skipped_messages: Vec<AvroSkippedMessage>,
+}
+
+/// Confluent wire format: magic 0x00 followed by a 4-byte big-endian schema id.
+const CONFLUENT_MAGIC: u8 = 0x00;
+const CONFLUENT_HEADER_LEN: usize = 5;
+/// Avro single-object encoding: marker C3 01 followed by an 8-byte Rabin fingerprint.
+const SINGLE_OBJECT_MARKER: [u8; 2] = [0xC3, 0x01];
+const SINGLE_OBJECT_HEADER_LEN: usize = 10;
+/// Every message in a dump is preceded by its length as a 4-byte big-endian integer.
+const LENGTH_PREFIX_LEN: usize = 4;
+/// Length prefixes above this are treated as a corrupt stream rather than
+/// buffered. Messages carry no sync marker, so the read stops there.
+const MAX_MESSAGE_BYTES: usize = 64 * 1024 * 1024;
+const MAX_SKIPPED_MESSAGES: usize = 1000;
+
+struct RegisteredSchema {
+    schema_id: u32,
+    fingerprint: Vec<u8>,
+    schema_json: serde_json::Value,
+    columns: AvroColumns,
+    union_stats: Vec<AvroUnionStats>,
+    // Position of each of this schema's columns in the combined headers
+    column_map: Vec<usize>,
+}
+
+/// Which registered schema (if any) a message header points at.
+#[derive(Clone, PartialEq, Eq, Hash)]
+enum SchemaKey {
+    Id(u32),
+    Fingerprint(Vec<u8>),
+}
+
+/// `RecordSource` for dumps of Kafka-style Avro messages. Every complete
+/// message in a chunk is decoded into a row over the combined headers of the
+/// registered schemas; a partial message stays buffered for the next chunk.
+pub struct AvroMessageSource {
+    headers: Vec<String>,
+    schemas: Vec<RegisteredSchema>,
+    buffer: Vec<u8>,
+    buffer_offset: u64,
+    bytes_received: u64,
+    message_index: u64,
+    id_counts: HashMap<SchemaKey, u64>,
+    // First-seen order of schema keys, for stable reporting
+    id_order: Vec<SchemaKey>,
+    progress: AvroMessageProgress,
+    // Set by the first `push` or `finish`; schemas are fixed from then on
+    started: bool,
+    // Set at a corrupt length prefix; later input is counted but not read
+    corrupt: bool,
+    finished: bool,
+    rows: Vec<Vec<String>>,
+    pending: bool,
+    batches_read: u64,
+}
+
+impl AvroMessageSource {
+    pub fn new() -> Self {
+        Self {
+            headers: Vec::new(),
+            schemas: Vec::new(),
+            buffer: Vec::new(),
+            buffer_offset: 0,
+            bytes_received: 0,
+            message_index: 0,
+            id_counts: HashMap::new(),
+            id_order: Vec::new(),
+            progress: AvroMessageProgress::default(),
+            started: false,
+            corrupt: false,
+            finished: false,
+            rows: Vec::new(),
+            pending: false,
+            batches_read: 0,
+        }
+    }
+
+    pub fn message_progress(&self) -> &AvroMessageProgress {
+        &self.progress
+    }
+
+    pub fn register_schema(&mut self, schema_id: u32, schema_json: &str) -> Result<(), String> {
+        if self.started {
+            return Err("Schemas must be registered before profiling starts".to_string());
+        }
+        if self.schemas.iter().any(|s| s.schema_id == schema_id) {
+            return Err(format!("Schema id {} is already registered", schema_id));
+        }
+
+        let schema = Schema::parse_str(schema_json).map_err(|e| e.to_string())?;
+        let fingerprint = schema.fingerprint::<Rabin>().bytes;
+        let schema_json = serde_json::to_value(&schema).map_err(|e| e.to_string())?;
+        let (columns, union_stats) = AvroColumns::new(schema)?;
+
+        let column_map = columns.headers.iter().map(|h| {
+            match self.headers.iter().position(|existing| existing == h) {
+                Some(idx) => idx,
+                None => {
+                    self.headers.push(h.clone());
+                    self.headers.len() - 1
+                }
+            }
+        }).collect();
+
+        self.schemas.push(RegisteredSchema {
+            schema_id,
+            fingerprint,
+            schema_json,
+            columns,
+            union_stats,
+            column_map,
+        });
+        Ok(())
+    }
+
+    fn start(&mut self) -> Result<(), String> {
+        self.started = true;
+        if self.schemas.is_empty() {
+            return Err("No Avro schemas registered".to_string());
+        }
+        Ok(())
+    }
+}
+
+impl RecordSource for AvroMessageSource {
+    fn push(&mut self, chunk: &[u8]) -> Result<(), String> {
+        self.start()?;
+        self.bytes_received += chunk.len() as u64;
+        if self.corrupt {
+            return Ok(());
+        }
+        // Taken out of self so messages can be decoded while borrowing it
+        let mut buffer = std::mem::take(&mut self.buffer);
+        buffer.extend_from_slice(chunk);
+
+        let mut pos = 0;
+        let mut rows = Vec::new();
+        while buffer.len() - pos >= LENGTH_PREFIX_LEN {
+            let mut len_bytes = [0u8; LENGTH_PREFIX_LEN];
+            len_bytes.copy_from_slice(&buffer[pos..pos + LENGTH_PREFIX_LEN]);
+            let len = u32::from_be_bytes(len_bytes) as usize;
+            if len > MAX_MESSAGE_BYTES {
+                // Messages before this one are kept; nothing after it can be framed
+                let reason = format!("invalid message length {}; the rest of the dump was not read", len);
+                self.skip_message(self.buffer_offset + pos as u64, reason);
+                self.message_index += 1;
+                self.corrupt = true;
+                break;
+            }
+
+            let end = pos + LENGTH_PREFIX_LEN + len;
+            if buffer.len() < end {
+                break;
+            }
+
+            let offset = self.buffer_offset + pos as u64;
+            let message = &buffer[pos + LENGTH_PREFIX_LEN..end];
+            match self.decode_message(message) {
+                Ok(row) => {
+                    rows.push(row);
+                    self.progress.messages_read += 1;
+                }
+                Err(reason) => self.skip_message(offset, reason),
+            }
+            self.message_index += 1;
+            pos = end;
+        }
+
+        buffer.drain(..pos);
+        if self.corrupt {
+            buffer.clear();
+        }
+        self.buffer = buffer;
+        self.buffer_offset += pos as u64;
+        self.progress.bytes_consumed = self.buffer_offset;
+        self.progress.schema_ids = self.schema_id_counts();
+
+        self.pending = !rows.is_empty();
+        self.rows = rows;
+        Ok(())
+    }
+
+    fn finish(&mut self) -> Result<(), String> {
+        self.finished = true;
+        self.start()?;
+        if !self.buffer.is_empty() {
+            self.skip_message(self.buffer_offset, "truncated message at end of file".to_string());
+            self.buffer_offset += self.buffer.len() as u64;
+            self.buffer.clear();
+            self.progress.bytes_consumed = self.buffer_offset;
+        }
+        Ok(())
+    }
+
+    fn schema(&self) -> Option<SourceSchema> {
+        if self.schemas.is_empty() {
+            return None;
+        }
+        // The first schema declaring a column decides its logical type
+        let mut logical_types = vec![None; self.headers.len()];
+        for registered in &self.schemas {
+            for (idx, logical_type) in registered.columns.logical_types.iter().enumerate() {
+                let column = registered.column_map[idx];
+                if logical_types[column].is_none() {
+                    logical_types[column] = logical_type.clone();
+                }
+            }
+        }
+        Some(SourceSchema {
+            headers: self.headers.clone(),
+            logical_types,
+            declared_types: Vec::new(),
+        })
+    }
+
+    fn next_batch(&mut self) -> Result<Option<&[Vec<String>]>, String> {
+        if !std::mem::take(&mut self.pending) {
+            return Ok(None);
+        }
+        self.batches_read += 1;
+        Ok(Some(&self.rows))
+    }
+
+    fn progress(&self) -> SourceProgress {
+        SourceProgress {
+            bytes_received: self.bytes_received,
+            records_read: self.progress.messages_read,
+            batches_read: self.batches_read,
+            finished: self.finished,
+        }
+    }
+
+    fn annotate(&self, profiler: &mut Profiler) {
+        let schema_map: serde_json::Map<String, serde_json::Value> = self.schemas.iter()
+            .map(|s| (s.schema_id.to_string(), s.schema_json.clone()))
+            .collect();
+        profiler.avro_schema = serde_json::to_string_pretty(&schema_map).ok();
+
+        let mut union_stats: Vec<AvroUnionStats> = Vec::new();
+        for registered in &self.schemas {
+            for stats in &registered.union_stats {
+                if !union_stats.iter_mut().any(|existing| existing.merge(stats)) {
+                    union_stats.push(stats.clone());
+                }
+            }
+        }
+        profiler.avro_union_stats = Some(union_stats);
+        profiler.avro_schema_ids = Some(self.schema_id_counts());
+    }
+}
+
+/// Profiles dumps of Kafka-style Avro messages: length-prefixed records that
+/// use either the Confluent wire format or Avro single-object encoding.
+/// Schemas are supplied up front from local `.avsc` files, keyed by schema id.
+/// Columns are the union of all registered schemas, in registration order.
+#[wasm_bindgen]
+pub struct AvroMessageProfiler {
+    driver: ProfileDriver<AvroMessageSource>,
+}
+
+#[wasm_bindgen]
+impl AvroMessageProfiler {
+    /// `config` is a `ProfilerConfig`; omitted fields keep their defaults.
+    #[wasm_bindgen(constructor)]
+    pub fn new(config: Option<JsValue>) -> Result<AvroMessageProfiler, JsValue> {
+        let config = ProfilerConfig::from_js(config).map_err(|e| JsValue::from_str(&e))?;
+        Ok(Self::with_config(config))
+    }
+
+    pub(crate) fn with_config(config: ProfilerConfig) -> Self {
+        Self {
+            driver: ProfileDriver::new(AvroMessageSource::new(), config),
+        }
+    }
+
+    /// Registers the `.avsc` schema JSON for a schema id. All schemas must be
+    /// registered before the first chunk is parsed.
+    pub fn register_schema(&mut self, schema_id: u32, schema_json: &str) -> Result<(), JsValue> {
+        self.register_schema_internal(schema_id, schema_json).map_err(|e| JsValue::from_str(&e))
+    }
+
+    /// Parses the next chunk of a message dump. Returns an `AvroMessageProgress`.
+    pub fn parse_and_profile_chunk(&mut self, chunk: &[u8]) -> Result<JsValue, JsValue> {
+        self.process_chunk(chunk).map_err(|e| JsValue::from_str(&e))?;
+        self.get_progress()
+    }
+
+    pub fn get_progress(&self) -> Result<JsValue, JsValue> {
+        serde_wasm_bindgen::to_value(self.driver.source().message_progress()).map_err(|e| JsValue::from_str(&e.to_string()))
+    }
+
+    pub fn finalize(&mut self) -> Result<JsValue, JsValue> {
+        match self.finalize_internal() {
+            Ok(stats) => serde_wasm_bindgen::to_value(&stats).map_err(|e| JsValue::from_str(&e.to_string())),
+            Err(e) => Err(JsValue::from_str(&e)),
+        }
+    }
+}
+
+impl AvroMessageProfiler {
+    fn register_schema_internal(&mut self, schema_id: u32, schema_json: &str) -> Result<(), String> {
+        self.driver.source_mut().register_schema(schema_id, schema_json)
+    }
+
+    fn process_chunk(&mut self, chunk: &[u8]) -> Result<(), String> {
+        self.driver.push(chunk)
+    }
+
+    fn finalize_internal(&mut self) -> Result<ProfilerResult, String> {
+        self.driver.finish()
+    }
+}
+
+impl AvroMessageSource {
+    /// Decodes one framed message into a row over the combined headers.
+    fn decode_message(&mut self, message: &[u8]) -> Result<Vec<String>, String> {
+        let (key, body) = if message.first() == Some(&CONFLUENT_MAGIC) && message.len() >= CONFLUENT_HEADER_LEN {
+            let mut id_bytes = [0u8; 4];
+            id_bytes.copy_from_slice(&message[1..CONFLUENT_HEADER_LEN]);
+            (SchemaKey::Id(u32::from_be_bytes(id_bytes)), &message[CONFLUENT_HEADER_LEN..])
+        } else if message.starts_with(&SINGLE_OBJECT_MARKER) && message.len() >= SINGLE_OBJECT_HEADER_LEN {
+            let fingerprint = message[SINGLE_OBJECT_MARKER.len()..SINGLE_OBJECT_HEADER_LEN].to_vec();
+            (SchemaKey::Fingerprint(fingerprint), &message[SINGLE_OBJECT_HEADER_LEN..])
+        } else {
+            return Err("unrecognised message framing".to_string());
+        };
+
+        self.count_schema_key(&key);
+        let registered = self.schemas.iter_mut()
+            .find(|s| match &key {
+                SchemaKey::Id(id) => s.schema_id == *id,
+                SchemaKey::Fingerprint(fp) => s.fingerprint == *fp,
+            })
+            .ok_or_else(|| "no schema registered for message".to_string())?;
+
+        let mut reader = body;
+        let record = apache_avro::from_avro_datum(&registered.columns.schema, &mut reader, None)
+            .map_err(|e| e.to_string())?;
+        let values = registered.columns.flatten_record(&record, &mut registered.union_stats);
+
+        let mut row = vec![String::new(); self.headers.len()];
+        for (idx, value) in values.into_iter().enumerate() {
+            row[registered.column_map[idx]] = value;
+        }
+        Ok(row)
+    }
+
+    fn count_schema_key(&mut self, key: &SchemaKey) {
+        let count = self.id_counts.entry(key.clone()).or_insert(0);
+        if *count == 0 {
+            self.id_order.push(key.clone());
+        }
+        *count += 1;
+    }
+
+    fn schema_id_counts(&self) -> Vec<AvroSchemaIdCount> {
+        self.id_order.iter().map(|key| {
+            let registered = self.schemas.iter().find(|s| match key {
+                SchemaKey::Id(id) => s.schema_id == *id,
+                SchemaKey::Fingerprint(fp) => s.fingerprint == *fp,
+            });
+            let (schema_id, fingerprint) = match key {
+                SchemaKey::Id(id) => (Some(*id), None),
+                SchemaKey::Fingerprint(fp) => (
+                    registered.map(|s| s.schema_id),
+                    Some(fp.iter().map(|b| format!("{:02x}", b)).collect()),
+                ),
+            };
+            AvroSchemaIdCount {
+                schema_id,
+                fingerprint,
+                count: self.id_counts.get(key).copied().unwrap_or(0),
+                registered: registered.is_some(),
+            }
+        }).collect()
+    }
+
+    fn skip_message(&mut self, byte_offset: u64, reason: String) {
+        self.progress.messages_skipped += 1;
+        if self.progress.skipped_messages.len() < MAX_SKIPPED_MESSAGES {
+            self.progress.skipped_messages.push(AvroSkippedMessage {
+                message_index: self.message_index,
+                byte_offset,
+                reason,
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use apache_avro::types::Record;
+
+    const USER_V1: &str = r#"
+        {
+            "type": "record",
+            "name": "user",
+            "fields": [
+                {"name": "id", "type": "long"},
+                {"name": "name", "type": "string"}
+            ]
+        }
+    "#;
+
+    const USER_V2: &str = r#"
+        {
+            "type": "record",
+            "name": "user",
+            "fields": [
+                {"name": "id", "type": "long"},
+                {"name": "name", "type": "string"},
+                {"name": "email", "type": ["null", "string"]}
+            ]
+        }
+    "#;
+
+    fn encode_body(schema_json: &str, fill: impl FnOnce(&mut Record)) -> Vec<u8> {
+        let schema = Schema::parse_str(schema_json).unwrap();
+        let mut record = Record::new(&schema).unwrap();
+        fill(&mut record);
+        apache_avro::to_avro_datum(&schema, record).unwrap()
+    }
+
+    fn confluent(schema_id: u32, body: &[u8]) -> Vec<u8> {
+        let mut message = vec![CONFLUENT_MAGIC];
+        message.extend_from_slice(&schema_id.to_be_bytes());
+        message.extend_from_slice(body);
+        message
+    }
+
+    fn single_object(schema_json: &str, body: &[u8]) -> Vec<u8> {
+        let schema = Schema::parse_str(schema_json).unwrap();
+        let mut message = SINGLE_OBJECT_MARKER.to_vec();
+        message.extend_from_slice(&schema.fingerprint::<Rabin>().bytes);
+        message.extend_from_slice(body);
+        message
+    }
+
+    fn dump(messages: &[Vec<u8>]) -> Vec<u8> {
+        let mut out = Vec::new();
+        for message in messages {
+            out.extend_from_slice(&(message.len() as u32).to_be_bytes());
+            out.extend_from_slice(message);
+        }
+        out
+    }
+
+    #[test]
+    fn test_profiles_mixed_schema_ids() {
+        let v1 = encode_body(USER_V1, |r| {
+            r.put("id", 1i64);
+            r.put("name", "Ada");
+        });
+        let v2 = encode_body(USER_V2, |r| {
+            r.put("id", 2i64);
+            r.put("name", "Grace");
+            r.put("email", apache_avro::types::Value::Union(1, Box::new("g@example.com".into())));
+        });
+        let bytes = dump(&[
+            confluent(7, &v1),
+            confluent(8, &v2),
+            single_object(USER_V2, &v2),
+            confluent(99, &v1),
+        ]);
+
+        let mut profiler = AvroMessageProfiler::with_config(ProfilerConfig::default());
+        profiler.register_schema_internal(7, USER_V1).unwrap();
+        profiler.register_schema_internal(8, USER_V2).unwrap();
+        for chunk in bytes.chunks(3) {
+            profiler.process_chunk(chunk).unwrap();
+        }
+        let result = profiler.finalize_internal().unwrap();
+
+        assert_eq!(profiler.driver.source().headers, vec!["id", "name", "email"]);
+        assert_eq!(result.total_rows, 3);
+        assert_eq!(result.column_profiles[2].base_stats.missing, 1);
+
+        let ids = result.avro_schema_ids.unwrap();
+        assert_eq!(ids.len(), 4);
+        assert_eq!((ids[0].schema_id, ids[0].count, ids[0].registered), (Some(7), 1, true));
+        assert_eq!((ids[1].schema_id, ids[1].count), (Some(8), 1));
+        assert_eq!(ids[2].schema_id, Some(8));
+        assert!(ids[2].fingerprint.is_some());
+        assert_eq!((ids[3].schema_id, ids[3].registered), (Some(99), false));
+
+        assert_eq!(profiler.driver.source().message_progress().messages_skipped, 1);
+        assert_eq!(profiler.driver.source().message_progress().skipped_messages[0].message_index, 3);
+    }
+
+    #[test]
+    fn test_corrupt_length_prefix_stops_the_read() {
+        let body = encode_body(USER_V1, |r| {
+            r.put("id", 1i64);
+            r.put("name", "Ada");
+        });
+        let first = dump(&[confluent(1, &body), confluent(1, &body)]);
+        let mut bytes = first.clone();
+        bytes.extend_from_slice(&[0xff, 0xff, 0xff, 0xff]);
+        bytes.extend_from_slice(&dump(&[confluent(1, &body)]));
+
+        let mut profiler = AvroMessageProfiler::with_config(ProfilerConfig::default());
+        profiler.register_schema_internal(1, USER_V1).unwrap();
+        for chunk in bytes.chunks(6) {
+            profiler.process_chunk(chunk).unwrap();
+        }
+        let result = profiler.finalize_internal().unwrap();
+
+        assert_eq!(result.total_rows, 2);
+        let progress = profiler.driver.source().message_progress();
+        assert_eq!(progress.messages_skipped, 1);
+        assert_eq!(progress.bytes_consumed, first.len() as u64);
+        let skipped = &progress.skipped_messages[0];
+        assert_eq!((skipped.message_index, skipped.byte_offset), (2, first.len() as u64));
+        assert!(skipped.reason.starts_with("invalid message length"));
+    }
+
+    #[test]
+    fn test_register_after_start_fails() {
+        let mut profiler = AvroMessageProfiler::with_config(ProfilerConfig::default());
+        profiler.register_schema_internal(1, USER_V1).unwrap();
+        assert!(profiler.register_schema_internal(1, USER_V2).is_err());
+        profiler.process_chunk(&[]).unwrap();
+        assert!(profiler.register_schema_internal(2, USER_V2).is_err());
+    }
+
+    #[test]
+    fn test_truncated_and_unframed_messages_are_skipped() {
+        let body = encode_body(USER_V1, |r| {
+            r.put("id", 1i64);
+            r.put("name", "Ada");
+        });
+        let mut bytes = dump(&[b"plain".to_vec(), confluent(1, &body)]);
+        bytes.extend_from_slice(&[0, 0, 0, 9, 0]);
+
+        let mut profiler = AvroMessageProfiler::with_config(ProfilerConfig::default());
+        profiler.register_schema_internal(1, USER_V1).unwrap();
+        profiler.process_chunk(&bytes).unwrap();
+        let result = profiler.finalize_internal().unwrap();
+
+        assert_eq!(result.total_rows, 1);
+        let reasons: Vec<&str> = profiler.driver.source().message_progress().skipped_messages.iter().map(|m| m.reason.as_str()).collect();
+        assert_eq!(reasons, vec!["unrecognised message framing", "truncated message at end of file"]);
+    }
+}
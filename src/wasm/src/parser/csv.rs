@@ -1,5 +1,6 @@
 use csv::{ReaderBuilder};
 use serde::{Deserialize, Serialize};
+use crate::parser::source::{ChunkParser, ChunkedSource};
 
 #[derive(Serialize, Deserialize, Debug)]
 pub struct ParseResult {
@@ -9,6 +10,7 @@ pub struct ParseResult {
     pub total_rows: u32,
 }
 
+#[derive(Serialize, Deserialize)]
 pub struct CsvParser {
     delimiter: u8,
     has_headers: bool,
@@ -149,6 +151,28 @@ impl CsvParser {
     }
 }
 
+impl ChunkParser for CsvParser {
+    type Output = ParseResult;
+
+    fn parse_chunk(&mut self, chunk: &[u8]) -> Result<ParseResult, String> {
+        Ok(CsvParser::parse_chunk(self, chunk))
+    }
+
+    fn flush(&mut self) -> Result<ParseResult, String> {
+        Ok(CsvParser::flush(self))
+    }
+
+    fn headers(&self) -> &[String] {
+        self.header_data.as_deref().unwrap_or_default()
+    }
+
+    fn rows(output: &ParseResult) -> &[Vec<String>] {
+        &output.rows
+    }
+}
+
+pub type CsvSource = ChunkedSource<CsvParser>;
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -203,4 +227,112 @@ mod tests {
         let res2 = parser.flush();
         assert_eq!(res2.total_rows, 3);
     }
+
+    #[test]
+    fn test_chunks_after_stop_have_no_result() {
+        use crate::parser::source::ProfileDriver;
+        use crate::stats::config::ProfilerConfig;
+
+        let config = ProfilerConfig { max_rows: Some(2), ..ProfilerConfig::default() };
+        let mut driver = ProfileDriver::new(CsvSource::new(CsvParser::new(None, true)), config);
+        driver.push(b"a,b\n1,2\n3,4\n5,6\n").unwrap();
+        assert!(driver.is_stopped());
+        assert_eq!(driver.source_mut().take_last_result().map(|r| r.rows.len()), Some(3));
+
+        driver.push(b"7,8\n").unwrap();
+        assert!(driver.source_mut().take_last_result().is_none());
+    }
+
+    #[test]
+    fn test_byte_limit_cuts_the_crossing_chunk_at_a_record_boundary() {
+        use crate::parser::source::ProfileDriver;
+        use crate::stats::config::ProfilerConfig;
+
+        let data = "id,name\n1,alpha\n2,beta\n3,gamma\n";
+        let config = ProfilerConfig { max_bytes: Some(20), ..ProfilerConfig::default() };
+        let mut driver = ProfileDriver::new(CsvSource::new(CsvParser::new(Some(b','), true)), config);
+        // Counted before the header completes a schema
+        driver.push(&data.as_bytes()[..4]).unwrap();
+        driver.push(&data.as_bytes()[4..]).unwrap();
+        assert!(driver.is_stopped());
+        assert_eq!(driver.progress().bytes_received, 20);
+
+        // "2,be" is cut off and dropped
+        let result = driver.finish().unwrap();
+        assert_eq!(result.total_rows, 1);
+        assert_eq!(result.sampling.unwrap().stop_reason, Some(crate::stats::sampling::StopReason::ByteLimit));
+
+        let config = ProfilerConfig { max_bytes: Some(3), ..ProfilerConfig::default() };
+        let mut driver = ProfileDriver::new(CsvSource::new(CsvParser::new(Some(b','), true)), config);
+        driver.push(data.as_bytes()).unwrap();
+        assert!(driver.is_stopped());
+        assert!(driver.finish().is_err());
+
+        // Input that exactly fills the budget is read in full; only a
+        // further push finds the limit reached
+        let data = "a,b\n1,2\n3,4";
+        let config = ProfilerConfig { max_bytes: Some(11), ..ProfilerConfig::default() };
+        let mut driver = ProfileDriver::new(CsvSource::new(CsvParser::new(Some(b','), true)), config.clone());
+        driver.push(data.as_bytes()).unwrap();
+        assert!(!driver.is_stopped());
+        let result = driver.finish().unwrap();
+        assert_eq!(result.total_rows, 2);
+        assert_eq!(result.sampling.unwrap().stop_reason, None);
+
+        let mut driver = ProfileDriver::new(CsvSource::new(CsvParser::new(Some(b','), true)), config);
+        driver.push(data.as_bytes()).unwrap();
+        driver.push(b"\n5,6\n").unwrap();
+        assert!(driver.is_stopped());
+        // "3,4" ends past the budget once more input follows
+        let result = driver.finish().unwrap();
+        assert_eq!(result.total_rows, 1);
+        assert_eq!(result.sampling.unwrap().stop_reason, Some(crate::stats::sampling::StopReason::ByteLimit));
+    }
+
+    #[test]
+    fn test_checkpoint_resume_matches_uninterrupted_run() {
+        use crate::parser::source::ProfileDriver;
+        use crate::stats::config::ProfilerConfig;
+
+        let mut data = b"id,city,amount\n".to_vec();
+        for i in 0..500 {
+            data.extend(format!("{},city{},{}.5\n", i, i % 7, i * 3).as_bytes());
+        }
+        let new_driver = || ProfileDriver::new(CsvSource::new(CsvParser::new(None, true)), ProfilerConfig::default());
+
+        let mut whole = new_driver();
+        for chunk in data.chunks(333) {
+            whole.push(chunk).unwrap();
+        }
+        let expected = whole.finish().unwrap();
+
+        // Interrupt after a chunk that ends mid-line
+        let mut driver = new_driver();
+        for chunk in data.chunks(333).take(10) {
+            driver.push(chunk).unwrap();
+        }
+        let checkpoint = driver.checkpoint().unwrap();
+        drop(driver);
+
+        let mut resumed = ProfileDriver::<CsvSource>::resume(&checkpoint).unwrap();
+        let offset = resumed.progress().bytes_received as usize;
+        assert_eq!(offset, 3330);
+        for chunk in data[offset..].chunks(333) {
+            resumed.push(chunk).unwrap();
+        }
+        let result = resumed.finish().unwrap();
+
+        assert_eq!(result.total_rows, expected.total_rows);
+        for (got, want) in result.column_profiles.iter().zip(&expected.column_profiles) {
+            assert_eq!(got.base_stats.distinct_estimate, want.base_stats.distinct_estimate);
+            assert_eq!(got.base_stats.inferred_type, want.base_stats.inferred_type);
+            assert_eq!(got.numeric_stats.as_ref().map(|n| (n.mean, n.median)), want.numeric_stats.as_ref().map(|n| (n.mean, n.median)));
+        }
+        assert!(ProfileDriver::<CsvSource>::resume(b"not a checkpoint").is_err());
+        // A state size the compressed block cannot hold is rejected
+        let mut forged = checkpoint[..5].to_vec();
+        forged.extend(u32::MAX.to_le_bytes());
+        forged.extend(&checkpoint[9..]);
+        assert!(ProfileDriver::<CsvSource>::resume(&forged).is_err());
+    }
 }
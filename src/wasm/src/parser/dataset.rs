@@ -0,0 +1,616 @@
+use wasm_bindgen::prelude::*;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+use ts_rs::TS;
+use crate::parser::arrow::ArrowSource;
+use crate::parser::avro::AvroSource;
+use crate::parser::csv::{CsvParser, CsvSource};
+use crate::parser::json::{JsonParser, JsonSource};
+use crate::parser::parquet::ParquetSource;
+use crate::parser::source::{ProfileDriver, RecordSource, SourceProgress, SourceSchema};
+use crate::parser::xml::{XmlParser, XmlSource};
+use crate::stats::config::ProfilerConfig;
+use crate::stats::profiler::{Profiler, ProfilerResult};
+
+/// Value Hive writes for a NULL partition key.
+const HIVE_DEFAULT_PARTITION: &str = "__HIVE_DEFAULT_PARTITION__";
+const CHUNK_SIZE: usize = 1024 * 1024;
+
+/// A column whose declared type differs from the one in an earlier file.
+#[derive(Serialize, Debug, Clone, TS)]
+#[ts(export)]
+pub struct DatasetTypeChange {
+    pub column: String,
+    pub expected: String,
+    pub found: String,
+}
+
+/// What one file contributed to the dataset.
+#[derive(Serialize, Debug, Clone, TS)]
+#[ts(export)]
+pub struct DatasetFileSummary {
+    pub path: String,
+    pub rows: u64,
+    /// Hive partition keys parsed from the path; None for `__HIVE_DEFAULT_PARTITION__`
+    pub partitions: BTreeMap<String, Option<String>>,
+    /// Columns this file was the first to contain
+    pub new_columns: Vec<String>,
+    /// Dataset columns absent from this file, profiled as missing for its rows
+    pub missing_columns: Vec<String>,
+    /// Declared types that differ from the first file declaring the column
+    /// (Parquet and Arrow files only; other formats carry no declared types)
+    pub type_changes: Vec<DatasetTypeChange>,
+    /// Read error; rows read before it are still profiled
+    pub error: Option<String>,
+}
+
+/// A column of the reconciled dataset schema.
+#[derive(Serialize, Debug, Clone, TS)]
+#[ts(export)]
+pub struct DatasetColumnInfo {
+    pub name: String,
+    /// True for virtual columns built from Hive partition keys
+    pub partition_key: bool,
+    /// Number of files containing the column (or its partition key)
+    pub files_present: u32,
+    pub declared_type: Option<String>,
+}
+
+/// Dataset-level metadata attached to the `ProfilerResult`.
+#[derive(Serialize, Debug, Clone, TS)]
+#[ts(export)]
+pub struct DatasetMetadata {
+    pub format: String,
+    pub columns: Vec<DatasetColumnInfo>,
+    pub files: Vec<DatasetFileSummary>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum DatasetFormat {
+    Csv,
+    Tsv,
+    Json,
+    Xml,
+    Parquet,
+    Arrow,
+    Avro,
+}
+
+impl DatasetFormat {
+    fn parse(format: &str) -> Result<Self, String> {
+        match format.to_ascii_lowercase().as_str() {
+            "csv" => Ok(DatasetFormat::Csv),
+            "tsv" => Ok(DatasetFormat::Tsv),
+            "json" | "jsonl" | "ndjson" => Ok(DatasetFormat::Json),
+            "xml" => Ok(DatasetFormat::Xml),
+            "parquet" => Ok(DatasetFormat::Parquet),
+            "arrow" | "feather" => Ok(DatasetFormat::Arrow),
+            "avro" => Ok(DatasetFormat::Avro),
+            other => Err(format!("Unsupported dataset format '{}'", other)),
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            DatasetFormat::Csv => "csv",
+            DatasetFormat::Tsv => "tsv",
+            DatasetFormat::Json => "json",
+            DatasetFormat::Xml => "xml",
+            DatasetFormat::Parquet => "parquet",
+            DatasetFormat::Arrow => "arrow",
+            DatasetFormat::Avro => "avro",
+        }
+    }
+}
+
+/// The reconciled columns of all files read so far.
+struct DatasetSchema {
+    columns: Vec<DatasetColumnInfo>,
+    index: HashMap<String, usize>,
+}
+
+impl DatasetSchema {
+    /// Index of a dataset column, adding it when first seen. The flag is
+    /// true if the column was added.
+    fn column(&mut self, name: &str, partition_key: bool) -> (usize, bool) {
+        if let Some(&idx) = self.index.get(name) {
+            return (idx, false);
+        }
+        let idx = self.columns.len();
+        self.columns.push(DatasetColumnInfo {
+            name: name.to_string(),
+            partition_key,
+            files_present: 0,
+            declared_type: None,
+        });
+        self.index.insert(name.to_string(), idx);
+        (idx, true)
+    }
+
+    /// Data columns first, then partition keys, each in order of appearance.
+    fn order(&self) -> Vec<usize> {
+        (0..self.columns.len())
+            .filter(|&i| !self.columns[i].partition_key)
+            .chain((0..self.columns.len()).filter(|&i| self.columns[i].partition_key))
+            .collect()
+    }
+}
+
+/// A file's input and the format source it is fed to, one chunk at a time
+/// so only a chunk's rows are held at once.
+struct FileInput {
+    source: Box<dyn RecordSource>,
+    bytes: Vec<u8>,
+    offset: usize,
+    finished: bool,
+}
+
+impl FileInput {
+    /// Pushes the next chunk, or finishes the source once all input was
+    /// pushed. Returns false when there is nothing left to do.
+    fn advance(&mut self) -> Result<bool, String> {
+        if self.offset < self.bytes.len() {
+            let end = (self.offset + CHUNK_SIZE).min(self.bytes.len());
+            self.source.push(&self.bytes[self.offset..end])?;
+            self.offset = end;
+            Ok(true)
+        } else if !self.finished {
+            self.finished = true;
+            self.source.finish()?;
+            Ok(true)
+        } else {
+            Ok(false)
+        }
+    }
+}
+
+/// The file currently being read.
+struct DatasetFile {
+    /// None until the file's bytes were pushed, and again after a read error
+    input: Option<FileInput>,
+    // Dataset column of each file column, extended as JSON/XML headers grow
+    mapping: Vec<usize>,
+    // Virtual partition columns and their value, set with the file's first schema
+    partition_columns: Option<Vec<(usize, String)>>,
+    summary: DatasetFileSummary,
+}
+
+impl DatasetFile {
+    /// Maps the columns the file's source announced since the last call onto
+    /// dataset columns. Returns true if the dataset gained columns.
+    fn sync_columns(&mut self, schema: &mut DatasetSchema, first_file: bool) -> bool {
+        let Some(file_schema) = self.input.as_ref().and_then(|input| input.source.schema()) else {
+            return false;
+        };
+        let before = schema.columns.len();
+        for (idx, name) in file_schema.headers.iter().enumerate().skip(self.mapping.len()) {
+            let (column, added) = schema.column(name, false);
+            if added && !first_file {
+                self.summary.new_columns.push(name.clone());
+            }
+            if let Some(found) = file_schema.declared_types.get(idx).cloned().flatten() {
+                match &schema.columns[column].declared_type {
+                    Some(expected) if *expected != found => self.summary.type_changes.push(DatasetTypeChange {
+                        column: name.clone(),
+                        expected: expected.clone(),
+                        found,
+                    }),
+                    Some(_) => {}
+                    None => schema.columns[column].declared_type = Some(found),
+                }
+            }
+            self.mapping.push(column);
+        }
+        if self.partition_columns.is_none() {
+            let mut partition_columns = Vec::new();
+            for (key, value) in &self.summary.partitions {
+                if !file_schema.headers.contains(key) {
+                    let (column, added) = schema.column(key, true);
+                    if added && !first_file {
+                        self.summary.new_columns.push(key.clone());
+                    }
+                    partition_columns.push((column, value.clone().unwrap_or_default()));
+                }
+            }
+            self.partition_columns = Some(partition_columns);
+        }
+        schema.columns.len() > before
+    }
+}
+
+/// `RecordSource` over the files of a dataset. Each file is opened with
+/// `start_file`, handed over whole in one `push` and closed with `end_file`;
+/// its rows are read through the format's own source and mapped onto the
+/// reconciled dataset columns. A file's read error is recorded in its
+/// summary instead of failing the dataset.
+pub struct DatasetSource {
+    format: DatasetFormat,
+    schema: DatasetSchema,
+    files: Vec<DatasetFileSummary>,
+    // Columns each file contained, to derive `missing_columns`
+    file_columns: Vec<Vec<usize>>,
+    file: Option<DatasetFile>,
+    batch: Vec<Vec<String>>,
+    progress: SourceProgress,
+}
+
+impl DatasetSource {
+    fn new(format: DatasetFormat) -> Self {
+        Self {
+            format,
+            schema: DatasetSchema { columns: Vec::new(), index: HashMap::new() },
+            files: Vec::new(),
+            file_columns: Vec::new(),
+            file: None,
+            batch: Vec::new(),
+            progress: SourceProgress::default(),
+        }
+    }
+
+    /// Opens a file; `path` is relative to the dataset root.
+    fn start_file(&mut self, path: &str) {
+        self.file = Some(DatasetFile {
+            input: None,
+            mapping: Vec::new(),
+            partition_columns: None,
+            summary: DatasetFileSummary {
+                path: path.to_string(),
+                rows: 0,
+                partitions: parse_partitions(path),
+                new_columns: Vec::new(),
+                missing_columns: Vec::new(),
+                type_changes: Vec::new(),
+                error: None,
+            },
+        });
+    }
+
+    /// Closes the file opened by `start_file` and returns its summary.
+    /// `error` is a failure to push the file's bytes.
+    fn end_file(&mut self, error: Option<String>) -> DatasetFileSummary {
+        let mut file = match self.file.take() {
+            Some(file) => file,
+            None => unreachable!("end_file is only called after start_file"),
+        };
+        if file.summary.error.is_none() {
+            file.summary.error = error;
+        }
+        let mut present = file.mapping;
+        present.extend(file.partition_columns.iter().flatten().map(|(column, _)| *column));
+        for &column in &present {
+            self.schema.columns[column].files_present += 1;
+        }
+        self.file_columns.push(present);
+        self.files.push(file.summary.clone());
+        file.summary
+    }
+
+    fn metadata(&self) -> DatasetMetadata {
+        let columns = &self.schema.columns;
+        let files = self.files.iter().zip(&self.file_columns).map(|(summary, present)| DatasetFileSummary {
+            missing_columns: (0..columns.len())
+                .filter(|idx| !present.contains(idx))
+                .map(|idx| columns[idx].name.clone())
+                .collect(),
+            ..summary.clone()
+        }).collect();
+        DatasetMetadata {
+            format: self.format.name().to_string(),
+            columns: self.schema.order().iter().map(|&i| columns[i].clone()).collect(),
+            files,
+        }
+    }
+}
+
+impl RecordSource for DatasetSource {
+    fn push(&mut self, chunk: &[u8]) -> Result<(), String> {
+        let file = self.file.as_mut().ok_or_else(|| "No dataset file was started".to_string())?;
+        self.progress.bytes_received += chunk.len() as u64;
+        match open_source(self.format, chunk) {
+            Ok(source) => file.input = Some(FileInput { source, bytes: chunk.to_vec(), offset: 0, finished: false }),
+            Err(e) => file.summary.error = Some(e),
+        }
+        Ok(())
+    }
+
+    fn finish(&mut self) -> Result<(), String> {
+        self.progress.finished = true;
+        Ok(())
+    }
+
+    fn schema(&self) -> Option<SourceSchema> {
+        if self.files.is_empty() && self.file.is_none() {
+            return None;
+        }
+        Some(SourceSchema::untyped(self.schema.columns.iter().map(|c| c.name.clone()).collect()))
+    }
+
+    fn next_batch(&mut self) -> Result<Option<&[Vec<String>]>, String> {
+        let Some(file) = self.file.as_mut() else {
+            return Ok(None);
+        };
+        let first_file = self.files.is_empty();
+        loop {
+            if file.sync_columns(&mut self.schema, first_file) {
+                // An empty batch lets the driver add the new columns before
+                // rows that use them
+                self.batch.clear();
+                return Ok(Some(&self.batch));
+            }
+            let Some(input) = file.input.as_mut() else {
+                return Ok(None);
+            };
+
+            let failure = match input.source.next_batch() {
+                Ok(Some(rows)) => {
+                    let width = self.schema.columns.len();
+                    self.batch = rows.iter().map(|row| {
+                        let mut values = vec![String::new(); width];
+                        for (value, &column) in row.iter().zip(&file.mapping) {
+                            values[column] = value.clone();
+                        }
+                        for (column, value) in file.partition_columns.iter().flatten() {
+                            values[*column] = value.clone();
+                        }
+                        values
+                    }).collect();
+                    file.summary.rows += self.batch.len() as u64;
+                    self.progress.record_batch(self.batch.len());
+                    return Ok(Some(&self.batch));
+                }
+                Ok(None) => match input.advance() {
+                    Ok(true) => continue,
+                    Ok(false) => return Ok(None),
+                    Err(e) => e,
+                },
+                Err(e) => e,
+            };
+            // Rows read before the error stay profiled
+            file.summary.error = Some(failure);
+            file.input = None;
+            return Ok(None);
+        }
+    }
+
+    fn progress(&self) -> SourceProgress {
+        self.progress.clone()
+    }
+
+    fn streaming(&self) -> bool {
+        false
+    }
+
+    fn annotate(&self, profiler: &mut Profiler) {
+        profiler.dataset = Some(self.metadata());
+    }
+}
+
+/// The format's own source for one file.
+fn open_source(format: DatasetFormat, bytes: &[u8]) -> Result<Box<dyn RecordSource>, String> {
+    Ok(match format {
+        DatasetFormat::Csv | DatasetFormat::Tsv => {
+            let delimiter = if format == DatasetFormat::Tsv { b'\t' } else { CsvParser::auto_detect_delimiter(bytes) };
+            Box::new(CsvSource::new(CsvParser::new(Some(delimiter), true)))
+        }
+        DatasetFormat::Json => Box::new(JsonSource::new(JsonParser::new(None))),
+        DatasetFormat::Xml => Box::new(XmlSource::new(XmlParser::new(None, None)?)),
+        DatasetFormat::Parquet => Box::new(ParquetSource::new()),
+        DatasetFormat::Arrow => Box::new(ArrowSource::new()),
+        DatasetFormat::Avro => Box::new(AvroSource::new()),
+    })
+}
+
+/// Profiles a set of files of one format as a single logical table.
+/// Columns are reconciled by name: a column missing from a file is profiled
+/// as missing for that file's rows. Hive-style `key=value` directories in the
+/// file paths become virtual columns, placed after the data columns. A
+/// partition key that is also a data column is profiled as the data column.
+#[wasm_bindgen]
+pub struct DatasetProfiler {
+    driver: ProfileDriver<DatasetSource>,
+}
+
+#[wasm_bindgen]
+impl DatasetProfiler {
+    /// `format` is one of "csv", "tsv", "json" (array or JSON Lines), "xml",
+    /// "parquet", "arrow" or "avro". `config` is a `ProfilerConfig`; omitted
+    /// fields keep their defaults.
+    #[wasm_bindgen(constructor)]
+    pub fn new(format: &str, config: Option<JsValue>) -> Result<DatasetProfiler, JsValue> {
+        let format = DatasetFormat::parse(format).map_err(|e| JsValue::from_str(&e))?;
+        let config = ProfilerConfig::from_js(config).map_err(|e| JsValue::from_str(&e))?;
+        Ok(Self::with_format(format, config))
+    }
+
+    /// Profiles one file of the dataset. `path` is its path relative to the
+    /// dataset root, e.g. "date=2026-10-01/part-0000.parquet". Returns the
+    /// file's `DatasetFileSummary`.
+    pub fn add_file(&mut self, path: &str, file_bytes: &[u8]) -> Result<JsValue, JsValue> {
+        let summary = self.add_file_internal(path, file_bytes);
+        serde_wasm_bindgen::to_value(&summary).map_err(|e| JsValue::from_str(&e.to_string()))
+    }
+
+    pub fn finalize(&mut self) -> Result<JsValue, JsValue> {
+        match self.finalize_internal() {
+            Ok(stats) => serde_wasm_bindgen::to_value(&stats).map_err(|e| JsValue::from_str(&e.to_string())),
+            Err(e) => Err(JsValue::from_str(&e)),
+        }
+    }
+}
+
+impl DatasetProfiler {
+    fn with_format(format: DatasetFormat, config: ProfilerConfig) -> Self {
+        Self {
+            driver: ProfileDriver::new(DatasetSource::new(format), config),
+        }
+    }
+
+    fn add_file_internal(&mut self, path: &str, file_bytes: &[u8]) -> DatasetFileSummary {
+        self.driver.source_mut().start_file(path);
+        let error = self.driver.push(file_bytes).err();
+        self.driver.source_mut().end_file(error)
+    }
+
+    fn finalize_internal(&mut self) -> Result<ProfilerResult, String> {
+        if self.driver.source().files.is_empty() {
+            return Err("No files were added to the dataset".to_string());
+        }
+
+        let mut result = self.driver.finish()?;
+        let mut profiles: Vec<Option<_>> = result.column_profiles.drain(..).map(Some).collect();
+        result.column_profiles = self.driver.source().schema.order().iter().filter_map(|&i| profiles[i].take()).collect();
+        Ok(result)
+    }
+}
+
+/// Parses Hive-style `key=value` directory names from a file path. Values are
+/// percent-decoded as Hive escapes them.
+fn parse_partitions(path: &str) -> BTreeMap<String, Option<String>> {
+    let segments: Vec<&str> = path.split(['/', '\\']).collect();
+    let directories = &segments[..segments.len().saturating_sub(1)];
+    directories.iter()
+        .filter_map(|segment| segment.split_once('='))
+        .filter(|(key, _)| !key.is_empty())
+        .map(|(key, value)| {
+            let value = percent_decode(value);
+            let value = if value == HIVE_DEFAULT_PARTITION { None } else { Some(value) };
+            (percent_decode(key), value)
+        })
+        .collect()
+}
+
+fn percent_decode(text: &str) -> String {
+    let bytes = text.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let escaped = bytes.get(i + 1..i + 3)
+            .filter(|_| bytes[i] == b'%')
+            .and_then(|hex| std::str::from_utf8(hex).ok())
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match escaped {
+            Some(byte) => {
+                out.push(byte);
+                i += 3;
+            }
+            None => {
+                out.push(bytes[i]);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow_array::{ArrayRef, Int64Array, RecordBatch, StringArray};
+    use arrow_ipc::writer::FileWriter;
+    use std::sync::Arc;
+
+    fn profile(files: &[(&str, &str)]) -> (Vec<DatasetFileSummary>, ProfilerResult) {
+        let mut dataset = DatasetProfiler::with_format(DatasetFormat::Csv, ProfilerConfig::default());
+        let summaries = files.iter().map(|(path, data)| dataset.add_file_internal(path, data.as_bytes())).collect();
+        (summaries, dataset.finalize_internal().unwrap())
+    }
+
+    #[test]
+    fn test_parse_partitions() {
+        let partitions = parse_partitions("events/date=2026-10-01/region=eu%2Fwest/part-0.parquet");
+        assert_eq!(partitions.get("date"), Some(&Some("2026-10-01".to_string())));
+        assert_eq!(partitions.get("region"), Some(&Some("eu/west".to_string())));
+
+        let partitions = parse_partitions("t/k=__HIVE_DEFAULT_PARTITION__/a=b.csv");
+        assert_eq!(partitions.len(), 1);
+        assert_eq!(partitions.get("k"), Some(&None));
+    }
+
+    #[test]
+    fn test_partition_keys_become_columns() {
+        let (summaries, result) = profile(&[
+            ("events/date=2026-10-01/part-0.csv", "id,amount\n1,10\n2,20\n"),
+            ("events/date=2026-10-02/part-0.csv", "id,amount\n3,30\n"),
+        ]);
+
+        assert_eq!(result.total_rows, 3);
+        let names: Vec<&str> = result.column_profiles.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, ["id", "amount", "date"]);
+        assert_eq!(result.column_profiles[2].base_stats.distinct_estimate, 2);
+        assert_eq!(summaries[1].rows, 1);
+
+        let dataset = result.dataset.unwrap();
+        assert!(dataset.columns[2].partition_key);
+        assert_eq!(dataset.columns[2].files_present, 2);
+    }
+
+    #[test]
+    fn test_schemas_are_reconciled_by_name() {
+        let (summaries, result) = profile(&[
+            ("a.csv", "id,name\n1,x\n2,y\n"),
+            ("b.csv", "name,id,email\nz,3,z@example.com\n"),
+        ]);
+
+        let names: Vec<&str> = result.column_profiles.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, ["id", "name", "email"]);
+        let email = &result.column_profiles[2];
+        assert_eq!((email.base_stats.count, email.base_stats.missing), (3, 2));
+        assert_eq!(result.column_profiles[0].base_stats.missing, 0);
+
+        assert_eq!(summaries[1].new_columns, ["email"]);
+        let files = result.dataset.unwrap().files;
+        assert_eq!(files[0].missing_columns, ["email"]);
+        assert!(files[1].missing_columns.is_empty());
+    }
+
+    fn arrow_file(column: ArrayRef) -> Vec<u8> {
+        let batch = RecordBatch::try_from_iter([("id", column)]).unwrap();
+        let mut writer = FileWriter::try_new(Vec::new(), &batch.schema()).unwrap();
+        writer.write(&batch).unwrap();
+        writer.into_inner().unwrap()
+    }
+
+    #[test]
+    fn test_declared_type_changes() {
+        let mut dataset = DatasetProfiler::with_format(DatasetFormat::Arrow, ProfilerConfig::default());
+        dataset.add_file_internal("a.arrow", &arrow_file(Arc::new(Int64Array::from(vec![1, 2]))));
+        let summary = dataset.add_file_internal("b.arrow", &arrow_file(Arc::new(StringArray::from(vec!["x"]))));
+
+        assert!(summary.error.is_none());
+        assert_eq!(summary.type_changes.len(), 1);
+        assert_eq!(summary.type_changes[0].expected, "Int64");
+        assert_eq!(summary.type_changes[0].found, "Utf8");
+
+        let result = dataset.finalize_internal().unwrap();
+        assert_eq!(result.total_rows, 3);
+        assert_eq!(result.dataset.unwrap().columns[0].declared_type.as_deref(), Some("Int64"));
+    }
+
+    #[test]
+    fn test_keys_first_seen_mid_file() {
+        let mut dataset = DatasetProfiler::with_format(DatasetFormat::Json, ProfilerConfig::default());
+        dataset.add_file_internal("k=1/a.jsonl", "{\"id\": 1}\n{\"id\": 2, \"tag\": \"x\"}\n".as_bytes());
+        let summary = dataset.add_file_internal("k=2/b.jsonl", "{\"tag\": \"y\", \"id\": 3}\n".as_bytes());
+        assert!(summary.new_columns.is_empty());
+
+        let result = dataset.finalize_internal().unwrap();
+        let names: Vec<&str> = result.column_profiles.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, ["id", "tag", "k"]);
+        assert_eq!(result.column_profiles[1].base_stats.missing, 1);
+        assert_eq!(result.column_profiles[2].base_stats.missing, 0);
+    }
+
+    #[test]
+    fn test_unreadable_file_is_reported() {
+        let mut dataset = DatasetProfiler::with_format(DatasetFormat::Parquet, ProfilerConfig::default());
+        let summary = dataset.add_file_internal("broken.parquet", b"not parquet");
+        assert!(summary.error.is_some());
+        assert_eq!(summary.rows, 0);
+    }
+
+    #[test]
+    fn test_unsupported_format() {
+        assert!(DatasetFormat::parse("sqlite").is_err());
+        assert_eq!(DatasetFormat::parse("JSONL").unwrap(), DatasetFormat::Json);
+    }
+}
@@ -0,0 +1,235 @@
+use wasm_bindgen::prelude::*;
+use flate2::read::MultiGzDecoder;
+use serde::Serialize;
+use std::io::Read;
+use ts_rs::TS;
+use crate::parser::csv::CsvParser;
+use crate::parser::orc;
+
+/// Bytes of decompressed data inspected when sniffing a gzip stream.
+const GZIP_SNIFF_BYTES: usize = 64 * 1024;
+/// Lines inspected by the text heuristics.
+const SNIFF_LINES: usize = 50;
+/// Bytes after a 3-byte "ORC" header checked for binary content.
+const ORC_SNIFF_BYTES: usize = 64;
+
+/// Result of format sniffing.
+#[derive(Serialize, Debug, Clone, PartialEq, TS)]
+#[ts(export)]
+pub struct FormatDetection {
+    /// "csv", "tsv", "json", "jsonl", "xml", "parquet", "avro", "arrow",
+    /// "orc", "xlsx", "xls", "ods", "sqlite", "zip" or "unknown"
+    pub format: String,
+    /// 1.0 for magic bytes; lower for content heuristics
+    pub confidence: f64,
+    /// "gzip" when the format was detected inside a gzip stream
+    pub compression: Option<String>,
+    /// Field delimiter for "csv" and "tsv"
+    pub delimiter: Option<String>,
+    /// What the decision was based on, e.g. "PAR1 magic bytes"
+    pub reason: String,
+}
+
+impl FormatDetection {
+    fn new(format: &str, confidence: f64, reason: &str) -> Self {
+        Self {
+            format: format.to_string(),
+            confidence,
+            compression: None,
+            delimiter: None,
+            reason: reason.to_string(),
+        }
+    }
+}
+
+/// Detects the format of a file from its first bytes (a 64 KB prefix is
+/// plenty). Binary formats are recognised by magic bytes; text formats by
+/// content heuristics. Gzip streams are decompressed and sniffed inside.
+/// ORC's "ORC" header is also how text can start, so ORC is only certain
+/// when the whole file is passed and its postscript checks out.
+#[wasm_bindgen]
+pub fn detect_format(bytes: &[u8]) -> Result<JsValue, JsValue> {
+    serde_wasm_bindgen::to_value(&sniff(bytes)).map_err(|e| JsValue::from_str(&e.to_string()))
+}
+
+pub fn sniff(bytes: &[u8]) -> FormatDetection {
+    if bytes.starts_with(&[0x1f, 0x8b]) {
+        let mut inner = Vec::new();
+        // A prefix of a larger stream ends in a truncation error; whatever
+        // was decompressed up to that point is still usable
+        let _ = MultiGzDecoder::new(bytes).take(GZIP_SNIFF_BYTES as u64).read_to_end(&mut inner);
+        let mut detection = sniff(&inner);
+        detection.compression = Some("gzip".to_string());
+        detection.reason = format!("gzip stream containing {}", detection.reason);
+        return detection;
+    }
+    if let Some(detection) = sniff_magic(bytes) {
+        return detection;
+    }
+    sniff_text(bytes)
+}
+
+fn sniff_magic(bytes: &[u8]) -> Option<FormatDetection> {
+    let detection = if bytes.starts_with(b"PAR1") {
+        FormatDetection::new("parquet", 1.0, "PAR1 magic bytes")
+    } else if bytes.starts_with(b"Obj\x01") {
+        FormatDetection::new("avro", 1.0, "Avro object container magic bytes")
+    } else if bytes.starts_with(b"ARROW1") {
+        FormatDetection::new("arrow", 1.0, "ARROW1 magic bytes")
+    } else if bytes.starts_with(b"ORC") {
+        sniff_orc(bytes)?
+    } else if bytes.starts_with(b"SQLite format 3\0") {
+        FormatDetection::new("sqlite", 1.0, "SQLite database header")
+    } else if bytes.starts_with(&[0xd0, 0xcf, 0x11, 0xe0, 0xa1, 0xb1, 0x1a, 0xe1]) {
+        FormatDetection::new("xls", 0.9, "OLE2 compound document header")
+    } else if bytes.starts_with(b"PK\x03\x04") {
+        sniff_zip(bytes)
+    } else if bytes.starts_with(&[0xff, 0xff, 0xff, 0xff]) && bytes.len() >= 8 {
+        // Arrow IPC streams start with a continuation marker and a message length
+        FormatDetection::new("arrow", 0.8, "Arrow IPC stream continuation marker")
+    } else {
+        return None;
+    };
+    Some(detection)
+}
+
+/// Confirms an "ORC" header from the postscript at the end of the file, or
+/// failing that from binary data after the header. Text such as a CSV whose
+/// first column is `ORCID` is left to the text heuristics.
+fn sniff_orc(bytes: &[u8]) -> Option<FormatDetection> {
+    if orc::has_postscript(bytes) {
+        return Some(FormatDetection::new("orc", 1.0, "ORC header and postscript"));
+    }
+    let after_header = &bytes[3..bytes.len().min(3 + ORC_SNIFF_BYTES)];
+    after_header.iter().any(|&b| is_control(b))
+        .then(|| FormatDetection::new("orc", 0.8, "ORC header followed by binary data"))
+}
+
+fn is_control(byte: u8) -> bool {
+    byte < 0x09 || (0x0e..0x20).contains(&byte)
+}
+
+/// Tells spreadsheet packages apart from other zip archives by the entry
+/// names visible in the prefix.
+fn sniff_zip(bytes: &[u8]) -> FormatDetection {
+    let contains = |needle: &[u8]| bytes.windows(needle.len()).any(|w| w == needle);
+    if contains(b"application/vnd.oasis.opendocument.spreadsheet") {
+        FormatDetection::new("ods", 1.0, "OpenDocument spreadsheet mimetype")
+    } else if contains(b"xl/") || contains(b"[Content_Types].xml") {
+        FormatDetection::new("xlsx", 0.95, "zip archive with Office Open XML entries")
+    } else {
+        FormatDetection::new("zip", 0.9, "zip archive")
+    }
+}
+
+fn sniff_text(bytes: &[u8]) -> FormatDetection {
+    let bytes = bytes.strip_prefix(&[0xef, 0xbb, 0xbf]).unwrap_or(bytes);
+    if bytes.is_empty() {
+        return FormatDetection::new("unknown", 0.0, "empty input");
+    }
+    let control = bytes.iter().take(4096).filter(|&&b| is_control(b)).count();
+    if control * 20 > bytes.len().min(4096) {
+        return FormatDetection::new("unknown", 0.0, "binary content");
+    }
+
+    let text = String::from_utf8_lossy(bytes);
+    let trimmed = text.trim_start();
+    let lines: Vec<&str> = trimmed.lines().filter(|l| !l.trim().is_empty()).take(SNIFF_LINES).collect();
+
+    match trimmed.chars().next() {
+        Some('<') => {
+            let confidence = if trimmed.starts_with("<?xml") { 0.95 } else { 0.7 };
+            return FormatDetection::new("xml", confidence, "markup content");
+        }
+        Some('[') => return FormatDetection::new("json", 0.9, "leading '['"),
+        Some('{') => {
+            // Complete lines (the last one may be cut off) that each hold an object
+            let complete = &lines[..lines.len().saturating_sub(1).max(1)];
+            let objects = complete.iter()
+                .filter(|l| serde_json::from_str::<serde_json::Value>(l).is_ok_and(|v| v.is_object()))
+                .count();
+            if complete.len() > 1 && objects == complete.len() {
+                return FormatDetection::new("jsonl", 0.95, "one JSON object per line");
+            }
+            if objects > 0 && complete.len() == 1 && lines.len() > 1 {
+                return FormatDetection::new("jsonl", 0.8, "one JSON object per line");
+            }
+            return FormatDetection::new("json", 0.8, "leading '{'");
+        }
+        _ => {}
+    }
+
+    let delimiter = CsvParser::auto_detect_delimiter(bytes);
+    let counts: Vec<usize> = lines.iter().map(|l| l.matches(delimiter as char).count()).collect();
+    let consistent = counts.iter().filter(|&&c| c > 0 && c == counts[0]).count();
+    let share = consistent as f64 / counts.len().max(1) as f64;
+    let (format, name) = if delimiter == b'\t' { ("tsv", "tab") } else { ("csv", "delimiter") };
+    let mut detection = if counts.first().is_some_and(|&c| c > 0) {
+        FormatDetection::new(format, (0.5 + 0.4 * share).min(0.9), &format!("consistent {} counts", name))
+    } else {
+        // A single column of text is still readable as CSV
+        FormatDetection::new("csv", 0.3, "plain text without delimiters")
+    };
+    detection.delimiter = Some((delimiter as char).to_string());
+    detection
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::write::GzEncoder;
+    use flate2::Compression;
+    use std::io::Write;
+
+    fn format_of(bytes: &[u8]) -> String {
+        sniff(bytes).format
+    }
+
+    #[test]
+    fn test_magic_bytes() {
+        assert_eq!(format_of(b"PAR1\x15\x04"), "parquet");
+        assert_eq!(format_of(b"Obj\x01\x04\x14avro"), "avro");
+        assert_eq!(format_of(b"SQLite format 3\0\x10\x00"), "sqlite");
+        assert_eq!(format_of(b"PK\x03\x04....[Content_Types].xml"), "xlsx");
+        assert_eq!(format_of(b"PK\x03\x04....readme.txt"), "zip");
+        assert_eq!(format_of(b"ORC\x00\x01\x1e"), "orc");
+        assert!(sniff(b"ORC\x00\x01\x1e").confidence < 1.0);
+    }
+
+    #[test]
+    fn test_orc_header_needs_binary_data_or_a_postscript() {
+        assert_eq!(format_of(b"ORCID,name\n0000-0001,a\n0000-0002,b\n"), "csv");
+
+        // Smallest whole file: header, then a postscript holding only the magic
+        let mut file = b"ORC".to_vec();
+        file.extend_from_slice(&[0x82, 0xf4, 0x03, 0x03]);
+        file.extend_from_slice(b"ORC");
+        file.push(7);
+        let detection = sniff(&file);
+        assert_eq!((detection.format.as_str(), detection.confidence), ("orc", 1.0));
+    }
+
+    #[test]
+    fn test_text_heuristics() {
+        assert_eq!(format_of(b"id,name\n1,a\n2,b\n"), "csv");
+        assert_eq!(sniff(b"id;name\n1;a\n2;b\n").delimiter.as_deref(), Some(";"));
+        assert_eq!(format_of(b"id\tname\n1\ta\n2\tb\n"), "tsv");
+        assert_eq!(format_of(b"\xef\xbb\xbf[{\"a\": 1}]"), "json");
+        assert_eq!(format_of(b"{\"a\": 1}\n{\"a\": 2}\n{\"a\": 3"), "jsonl");
+        assert_eq!(format_of(b"{\n  \"a\": 1\n}\n"), "json");
+        assert_eq!(format_of(b"<?xml version=\"1.0\"?><a/>"), "xml");
+        assert_eq!(format_of(b"\x00\x01\x02\x03\x04"), "unknown");
+    }
+
+    #[test]
+    fn test_gzip_is_sniffed_inside() {
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(b"{\"a\": 1}\n{\"a\": 2}\n{\"a\": 3}\n").unwrap();
+        let compressed = encoder.finish().unwrap();
+
+        // A truncated prefix still decompresses far enough to sniff
+        let detection = sniff(&compressed[..compressed.len() - 8]);
+        assert_eq!(detection.format, "jsonl");
+        assert_eq!(detection.compression.as_deref(), Some("gzip"));
+    }
+}
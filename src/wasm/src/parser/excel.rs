@@ -0,0 +1,497 @@
+use wasm_bindgen::prelude::*;
+use calamine::{open_workbook_auto_from_rs, Data, Range, Reader, SheetVisible, Sheets};
+use serde::Serialize;
+use ts_rs::TS;
+use std::io::Cursor;
+use crate::parser::source::{ProfileDriver, RecordSource, SourceProgress, SourceSchema};
+use crate::stats::config::ProfilerConfig;
+use crate::stats::profiler::ProfilerResult;
+
+/// Layout of a single worksheet as detected before profiling.
+#[derive(Serialize, Debug, Clone, TS)]
+#[ts(export)]
+pub struct ExcelSheetInfo {
+    pub name: String,
+    pub hidden: bool,
+    /// Bounding box of non-empty cells in A1 notation, e.g. "B3:F120"
+    pub used_range: Option<String>,
+    /// 1-based sheet row holding the column names; None if the sheet has no header row
+    pub header_row: Option<u32>,
+    pub data_rows: u64,
+    pub columns: u32,
+    /// Fully blank rows inside the used range, which are not profiled
+    pub blank_rows: u64,
+    /// Cells holding Excel errors such as #N/A or #DIV/0!, profiled as missing
+    pub error_cells: u64,
+}
+
+/// Profile of one worksheet.
+#[derive(Serialize, Debug, TS)]
+#[ts(export)]
+pub struct ExcelSheetProfile {
+    pub sheet: ExcelSheetInfo,
+    pub profile: ProfilerResult,
+}
+
+/// Number of leading non-blank rows inspected when looking for the header row.
+const HEADER_SCAN_ROWS: usize = 10;
+const BATCH_SIZE: usize = 1000;
+
+/// `RecordSource` over one worksheet. The workbook is parsed before its
+/// sheets are profiled, so the cells arrive with the source instead of
+/// through `push`; rows are emitted once `finish` was called.
+pub struct SheetSource {
+    range: Range<Data>,
+    layout: SheetLayout,
+    // Next row offset within the used range
+    cursor: usize,
+    error_cells: u64,
+    batch: Vec<Vec<String>>,
+    progress: SourceProgress,
+}
+
+impl SheetSource {
+    fn new(range: Range<Data>, layout: SheetLayout) -> Self {
+        let cursor = layout.data_start;
+        Self {
+            range,
+            layout,
+            cursor,
+            error_cells: 0,
+            batch: Vec::with_capacity(BATCH_SIZE),
+            progress: SourceProgress::default(),
+        }
+    }
+
+    /// Cells holding Excel errors in the rows emitted so far.
+    pub fn error_cells(&self) -> u64 {
+        self.error_cells
+    }
+}
+
+impl RecordSource for SheetSource {
+    fn push(&mut self, _chunk: &[u8]) -> Result<(), String> {
+        Err("Worksheet cells are read from the workbook, not pushed".to_string())
+    }
+
+    fn finish(&mut self) -> Result<(), String> {
+        self.progress.finished = true;
+        Ok(())
+    }
+
+    fn schema(&self) -> Option<SourceSchema> {
+        Some(SourceSchema::untyped(self.layout.headers.clone()))
+    }
+
+    fn next_batch(&mut self) -> Result<Option<&[Vec<String>]>, String> {
+        if !self.progress.finished {
+            return Ok(None);
+        }
+        self.batch.clear();
+        for row in self.range.rows().skip(self.cursor) {
+            self.cursor += 1;
+            if row.iter().all(|cell| matches!(cell, Data::Empty)) {
+                continue;
+            }
+            self.error_cells += row.iter().filter(|cell| matches!(cell, Data::Error(_))).count() as u64;
+            self.batch.push(self.layout.columns.iter().map(|&idx| cell_to_string(&row[idx])).collect());
+            if self.batch.len() >= BATCH_SIZE {
+                break;
+            }
+        }
+
+        if self.batch.is_empty() {
+            return Ok(None);
+        }
+        self.progress.record_batch(self.batch.len());
+        Ok(Some(&self.batch))
+    }
+
+    fn progress(&self) -> SourceProgress {
+        self.progress.clone()
+    }
+}
+
+/// Profiles `.xlsx`, `.xlsm`, `.xlsb`, `.xls` and `.ods` workbooks, producing
+/// one `ProfilerResult` per worksheet.
+#[wasm_bindgen]
+pub struct ExcelProfiler {
+    format: String,
+    config: ProfilerConfig,
+}
+
+#[wasm_bindgen]
+impl ExcelProfiler {
+    /// `config` is a `ProfilerConfig`; omitted fields keep their defaults.
+    #[wasm_bindgen(constructor)]
+    pub fn new(config: Option<JsValue>) -> Result<ExcelProfiler, JsValue> {
+        let config = ProfilerConfig::from_js(config).map_err(|e| JsValue::from_str(&e))?;
+        Ok(Self::with_config(config))
+    }
+
+    pub(crate) fn with_config(config: ProfilerConfig) -> Self {
+        Self {
+            format: String::new(),
+            config,
+        }
+    }
+
+    /// Returns "xlsx", "xlsb", "xls" or "ods" once a workbook has been opened.
+    pub fn get_format(&self) -> String {
+        self.format.clone()
+    }
+
+    /// Lists the worksheets with their used range and detected header row.
+    pub fn list_sheets(&mut self, file_bytes: &[u8]) -> Result<JsValue, JsValue> {
+        match self.list_sheets_internal(file_bytes) {
+            Ok(sheets) => serde_wasm_bindgen::to_value(&sheets).map_err(|e| JsValue::from_str(&e.to_string())),
+            Err(e) => Err(JsValue::from_str(&e)),
+        }
+    }
+
+    /// Profiles every worksheet. Returns an array of `ExcelSheetProfile`.
+    pub fn parse_and_profile(&mut self, file_bytes: &[u8]) -> Result<JsValue, JsValue> {
+        match self.parse_and_profile_internal(file_bytes, None) {
+            Ok(sheets) => serde_wasm_bindgen::to_value(&sheets).map_err(|e| JsValue::from_str(&e.to_string())),
+            Err(e) => Err(JsValue::from_str(&e)),
+        }
+    }
+
+    /// Profiles a single worksheet by name. Returns an `ExcelSheetProfile`.
+    pub fn parse_sheet(&mut self, file_bytes: &[u8], sheet_name: &str) -> Result<JsValue, JsValue> {
+        let mut sheets = self.parse_and_profile_internal(file_bytes, Some(sheet_name))
+            .map_err(|e| JsValue::from_str(&e))?;
+        match sheets.pop() {
+            Some(sheet) => serde_wasm_bindgen::to_value(&sheet).map_err(|e| JsValue::from_str(&e.to_string())),
+            None => Err(JsValue::from_str(&format!("Sheet '{}' not found", sheet_name))),
+        }
+    }
+
+    fn list_sheets_internal(&mut self, file_bytes: &[u8]) -> Result<Vec<ExcelSheetInfo>, String> {
+        let mut workbook = self.open(file_bytes)?;
+        let sheets = workbook.sheets_metadata().to_vec();
+
+        let mut infos = Vec::with_capacity(sheets.len());
+        for sheet in sheets {
+            let range = workbook.worksheet_range(&sheet.name).map_err(|e| e.to_string())?;
+            let layout = SheetLayout::detect(&range);
+            infos.push(layout.info(&sheet.name, sheet.visible != SheetVisible::Visible, &range));
+        }
+        Ok(infos)
+    }
+
+    pub(crate) fn parse_and_profile_internal(&mut self, file_bytes: &[u8], only: Option<&str>) -> Result<Vec<ExcelSheetProfile>, String> {
+        let mut workbook = self.open(file_bytes)?;
+        let sheets = workbook.sheets_metadata().to_vec();
+
+        let mut profiles = Vec::new();
+        for sheet in sheets {
+            if only.is_some_and(|name| name != sheet.name) {
+                continue;
+            }
+            let range = workbook.worksheet_range(&sheet.name).map_err(|e| e.to_string())?;
+            let layout = SheetLayout::detect(&range);
+            let mut info = layout.info(&sheet.name, sheet.visible != SheetVisible::Visible, &range);
+
+            let mut driver = ProfileDriver::new(SheetSource::new(range, layout), self.config.clone());
+            let profile = driver.finish()?;
+            info.error_cells = driver.source().error_cells();
+            profiles.push(ExcelSheetProfile { sheet: info, profile });
+        }
+        Ok(profiles)
+    }
+
+    fn open<'a>(&mut self, file_bytes: &'a [u8]) -> Result<Sheets<Cursor<&'a [u8]>>, String> {
+        let workbook = open_workbook_auto_from_rs(Cursor::new(file_bytes)).map_err(|e| e.to_string())?;
+        self.format = match workbook {
+            Sheets::Xls(_) => "xls",
+            Sheets::Xlsx(_) => "xlsx",
+            Sheets::Xlsb(_) => "xlsb",
+            Sheets::Ods(_) => "ods",
+        }.to_string();
+        Ok(workbook)
+    }
+}
+
+/// Where the header and data rows sit within a sheet's used range.
+struct SheetLayout {
+    headers: Vec<String>,
+    // Range column offsets that hold at least one value
+    columns: Vec<usize>,
+    // Row offsets relative to the start of the used range
+    header_offset: Option<usize>,
+    data_start: usize,
+    data_rows: u64,
+    blank_rows: u64,
+}
+
+impl SheetLayout {
+    /// Picks the header row as the first of the leading non-blank rows that is
+    /// made only of text cells and fills at least half of the widest row.
+    /// Title rows above a table are skipped that way; sheets whose leading rows
+    /// hold numbers or dates get generated column names instead.
+    fn detect(range: &Range<Data>) -> Self {
+        let width = range.width();
+        let filled = |row: &[Data]| row.iter().filter(|cell| !matches!(cell, Data::Empty)).count();
+
+        let leading: Vec<(usize, &[Data])> = range.rows()
+            .enumerate()
+            .filter(|(_, row)| filled(row) > 0)
+            .take(HEADER_SCAN_ROWS)
+            .collect();
+        let widest = leading.iter().map(|(_, row)| filled(row)).max().unwrap_or(0);
+
+        let header = leading.iter().find(|(_, row)| {
+            filled(row) * 2 >= widest
+                && row.iter().all(|cell| matches!(cell, Data::Empty | Data::String(_)))
+        });
+
+        let (header_offset, data_start, headers) = match header {
+            Some((offset, row)) => (Some(*offset), offset + 1, header_names(row, range_start_column(range))),
+            None => {
+                let first = leading.first().map(|(offset, _)| *offset).unwrap_or(0);
+                let start = range_start_column(range);
+                (None, first, (0..width).map(|i| format!("Column {}", column_letter(start + i as u32))).collect())
+            }
+        };
+
+        let mut used = vec![false; width];
+        if let Some((offset, _)) = header {
+            for (idx, cell) in range.rows().nth(*offset).unwrap_or(&[]).iter().enumerate() {
+                used[idx] |= !matches!(cell, Data::Empty);
+            }
+        }
+        let (mut data_rows, mut blank_rows) = (0, 0);
+        for row in range.rows().skip(data_start) {
+            if filled(row) == 0 {
+                blank_rows += 1;
+                continue;
+            }
+            data_rows += 1;
+            for (idx, cell) in row.iter().enumerate() {
+                used[idx] |= !matches!(cell, Data::Empty);
+            }
+        }
+
+        // Columns that are empty below the header (e.g. under a title cell) are dropped
+        let columns: Vec<usize> = (0..width).filter(|&idx| used[idx]).collect();
+        let headers = columns.iter().map(|&idx| headers[idx].clone()).collect();
+
+        Self {
+            headers,
+            columns,
+            header_offset,
+            data_start,
+            data_rows,
+            blank_rows,
+        }
+    }
+
+    fn info(&self, name: &str, hidden: bool, range: &Range<Data>) -> ExcelSheetInfo {
+        let used_range = match (range.start(), range.end()) {
+            (Some(start), Some(end)) => Some(format!("{}:{}", cell_ref(start), cell_ref(end))),
+            _ => None,
+        };
+        let first_row = range.start().map(|(row, _)| row).unwrap_or(0);
+
+        ExcelSheetInfo {
+            name: name.to_string(),
+            hidden,
+            used_range,
+            header_row: self.header_offset.map(|offset| first_row + offset as u32 + 1),
+            data_rows: self.data_rows,
+            columns: self.headers.len() as u32,
+            blank_rows: self.blank_rows,
+            error_cells: 0,
+        }
+    }
+}
+
+fn range_start_column(range: &Range<Data>) -> u32 {
+    range.start().map(|(_, col)| col).unwrap_or(0)
+}
+
+/// Column names from a header row. Blank cells are named after their column
+/// letter and repeated names get a numeric suffix so every column is distinct.
+fn header_names(row: &[Data], start_column: u32) -> Vec<String> {
+    let mut names: Vec<String> = Vec::with_capacity(row.len());
+    for (idx, cell) in row.iter().enumerate() {
+        let base = match cell {
+            Data::String(s) if !s.trim().is_empty() => s.trim().to_string(),
+            _ => format!("Column {}", column_letter(start_column + idx as u32)),
+        };
+        let mut name = base.clone();
+        let mut suffix = 2;
+        while names.contains(&name) {
+            name = format!("{}_{}", base, suffix);
+            suffix += 1;
+        }
+        names.push(name);
+    }
+    names
+}
+
+/// Converts a zero-based column index into Excel letters (0 → A, 27 → AB).
+fn column_letter(mut column: u32) -> String {
+    let mut letters = Vec::new();
+    loop {
+        letters.push((b'A' + (column % 26) as u8) as char);
+        if column < 26 {
+            break;
+        }
+        column = column / 26 - 1;
+    }
+    letters.iter().rev().collect()
+}
+
+fn cell_ref((row, column): (u32, u32)) -> String {
+    format!("{}{}", column_letter(column), row + 1)
+}
+
+/// Renders a typed cell as the string the Profiler expects. Date-formatted
+/// serials become ISO dates, times or timestamps; whole floats drop their
+/// fractional part so they are inferred as integers; errors count as missing.
+fn cell_to_string(cell: &Data) -> String {
+    match cell {
+        Data::Empty | Data::Error(_) => String::new(),
+        Data::Int(i) => i.to_string(),
+        Data::Float(f) => {
+            if f.fract() == 0.0 && f.abs() < 1e15 {
+                (*f as i64).to_string()
+            } else {
+                f.to_string()
+            }
+        }
+        Data::String(s) => s.clone(),
+        Data::Bool(b) => b.to_string(),
+        Data::DateTime(dt) if dt.is_duration() => {
+            let total = dt.as_duration().map(|d| d.num_seconds()).unwrap_or(0);
+            let sign = if total < 0 { "-" } else { "" };
+            let total = total.unsigned_abs();
+            format!("{}{:02}:{:02}:{:02}", sign, total / 3600, (total % 3600) / 60, total % 60)
+        }
+        Data::DateTime(dt) => {
+            let serial = dt.as_f64();
+            let format = if serial.fract() == 0.0 {
+                "%Y-%m-%d"
+            } else if serial < 1.0 {
+                "%H:%M:%S"
+            } else {
+                "%Y-%m-%dT%H:%M:%S"
+            };
+            dt.as_datetime().map(|d| d.format(format).to_string()).unwrap_or_default()
+        }
+        Data::DateTimeIso(s) | Data::DurationIso(s) => s.clone(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stats::types::DataType;
+    use rust_xlsxwriter::{ExcelDateTime, Format, Workbook};
+
+    fn sample_workbook() -> Vec<u8> {
+        let mut workbook = Workbook::new();
+        let date_format = Format::new().set_num_format("yyyy-mm-dd");
+
+        let report = workbook.add_worksheet().set_name("Report").unwrap();
+        report.write_string(0, 0, "Quarterly orders").unwrap();
+        report.write_string(2, 1, "order_id").unwrap();
+        report.write_string(2, 2, "placed").unwrap();
+        report.write_string(2, 3, "amount").unwrap();
+        report.write_string(2, 4, "paid").unwrap();
+        for i in 0..4u32 {
+            let row = 3 + i;
+            report.write_number(row, 1, (100 + i) as f64).unwrap();
+            let date = ExcelDateTime::from_ymd(2024, 3, 1 + i as u8).unwrap();
+            report.write_datetime_with_format(row, 2, &date, &date_format).unwrap();
+            report.write_number(row, 3, 9.5 * (i + 1) as f64).unwrap();
+            report.write_boolean(row, 4, i % 2 == 0).unwrap();
+        }
+        report.write_number(8, 1, 104.0).unwrap();
+
+        let raw = workbook.add_worksheet().set_name("Raw").unwrap();
+        for i in 0..3u32 {
+            raw.write_number(i, 0, i as f64).unwrap();
+            raw.write_number(i, 1, i as f64 * 0.5).unwrap();
+        }
+
+        workbook.save_to_buffer().unwrap()
+    }
+
+    #[test]
+    fn test_column_letter() {
+        assert_eq!(column_letter(0), "A");
+        assert_eq!(column_letter(25), "Z");
+        assert_eq!(column_letter(26), "AA");
+        assert_eq!(column_letter(27), "AB");
+        assert_eq!(column_letter(701), "ZZ");
+        assert_eq!(column_letter(702), "AAA");
+    }
+
+    #[test]
+    fn test_header_names_are_unique() {
+        let row = vec![
+            Data::String("id".to_string()),
+            Data::Empty,
+            Data::String("id".to_string()),
+        ];
+        assert_eq!(header_names(&row, 1), vec!["id", "Column C", "id_2"]);
+    }
+
+    #[test]
+    fn test_list_sheets_detects_layout() {
+        let bytes = sample_workbook();
+        let mut profiler = ExcelProfiler::with_config(ProfilerConfig::default());
+        let sheets = profiler.list_sheets_internal(&bytes).unwrap();
+
+        assert_eq!(profiler.get_format(), "xlsx");
+        assert_eq!(sheets.len(), 2);
+        assert_eq!(sheets[0].name, "Report");
+        assert_eq!(sheets[0].used_range.as_deref(), Some("A1:E9"));
+        assert_eq!(sheets[0].header_row, Some(3));
+        assert_eq!(sheets[0].data_rows, 5);
+        assert_eq!(sheets[0].blank_rows, 1);
+        assert_eq!(sheets[1].header_row, None);
+        assert_eq!(sheets[1].data_rows, 3);
+    }
+
+    #[test]
+    fn test_profiles_each_sheet() {
+        let bytes = sample_workbook();
+        let mut profiler = ExcelProfiler::with_config(ProfilerConfig::default());
+        let sheets = profiler.parse_and_profile_internal(&bytes, None).unwrap();
+
+        let report = &sheets[0];
+        let names: Vec<&str> = report.profile.column_profiles.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, vec!["order_id", "placed", "amount", "paid"]);
+        assert_eq!(report.profile.total_rows, 5);
+        assert_eq!(report.profile.column_profiles[1].base_stats.missing, 1);
+        assert_eq!(report.profile.column_profiles[0].base_stats.inferred_type, DataType::Integer);
+        assert_eq!(report.profile.column_profiles[1].base_stats.inferred_type, DataType::Date);
+
+        let raw = &sheets[1];
+        let names: Vec<&str> = raw.profile.column_profiles.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, vec!["Column A", "Column B"]);
+        assert_eq!(raw.profile.total_rows, 3);
+
+        let single = profiler.parse_and_profile_internal(&bytes, Some("Raw")).unwrap();
+        assert_eq!(single.len(), 1);
+        assert_eq!(single[0].sheet.name, "Raw");
+    }
+
+    #[test]
+    fn test_cell_to_string() {
+        assert_eq!(cell_to_string(&Data::Float(42.0)), "42");
+        assert_eq!(cell_to_string(&Data::Float(2.5)), "2.5");
+        assert_eq!(cell_to_string(&Data::Bool(true)), "true");
+        let date = calamine::ExcelDateTime::new(45352.0, calamine::ExcelDateTimeType::DateTime, false);
+        assert_eq!(cell_to_string(&Data::DateTime(date)), "2024-03-01");
+        let time = calamine::ExcelDateTime::new(0.5, calamine::ExcelDateTimeType::DateTime, false);
+        assert_eq!(cell_to_string(&Data::DateTime(time)), "12:00:00");
+        let duration = |seconds: f64| calamine::ExcelDateTime::new(seconds / 86400.0, calamine::ExcelDateTimeType::TimeDelta, false);
+        assert_eq!(cell_to_string(&Data::DateTime(duration(30.0 * 3600.0 + 5.0))), "30:00:05");
+        assert_eq!(cell_to_string(&Data::DateTime(duration(-90.0))), "-00:01:30");
+    }
+}
@@ -1,6 +1,7 @@
 use serde::{Deserialize, Serialize};
 use serde_json::{Value, Map};
 use std::collections::HashMap;
+use crate::parser::source::{ChunkParser, ChunkedSource};
 
 /// Result of JSON parsing, compatible with CSV ParseResult structure
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -66,7 +67,7 @@ pub enum JsonStructure {
 }
 
 /// Configuration for the JSON parser
-#[derive(Clone)]
+#[derive(Serialize, Deserialize, Clone)]
 pub struct JsonParserConfig {
     pub max_nested_depth: usize,
     pub max_keys_per_object: usize,
@@ -82,6 +83,7 @@ impl Default for JsonParserConfig {
 }
 
 /// Streaming JSON/JSONL parser
+#[derive(Serialize, Deserialize)]
 pub struct JsonParser {
     config: JsonParserConfig,
     format: JsonFormat,
@@ -340,7 +342,7 @@ impl JsonParser {
     }
 
     /// Flatten any JSON Value into a row
-    fn flatten_value(&mut self, val: &Value) -> Vec<String> {
+    pub(crate) fn flatten_value(&mut self, val: &Value) -> Vec<String> {
         match val {
             Value::Object(map) => self.flatten_object(map, "", 0),
             _ => {
@@ -561,6 +563,11 @@ impl JsonParser {
         &self.format
     }
 
+    /// Headers discovered so far, in first-seen order
+    pub(crate) fn headers(&self) -> &[String] {
+        &self.headers
+    }
+
     /// Get array field statistics
     pub fn get_array_stats(&self) -> &HashMap<String, ArrayFieldStats> {
         &self.array_stats
@@ -641,13 +648,13 @@ pub fn analyze_json_structure(
 }
 
 /// Helper struct to track paths during scanning
-struct PathTracker {
-    paths: HashMap<String, PathInfo>,
-    max_depth: usize,
+pub(crate) struct PathTracker {
+    pub(crate) paths: HashMap<String, PathInfo>,
+    pub(crate) max_depth: usize,
     collect_examples: bool,
 }
 
-struct PathInfo {
+pub(crate) struct PathInfo {
     count: usize,
     types_seen: std::collections::HashSet<String>,
     examples: Vec<String>,
@@ -655,7 +662,7 @@ struct PathInfo {
 }
 
 impl PathTracker {
-    fn new(collect_examples: bool) -> Self {
+    pub(crate) fn new(collect_examples: bool) -> Self {
         Self {
             paths: HashMap::new(),
             max_depth: 0,
@@ -663,7 +670,7 @@ impl PathTracker {
         }
     }
     
-    fn track_value(&mut self, path: &str, value: &Value, depth: usize) {
+    pub(crate) fn track_value(&mut self, path: &str, value: &Value, depth: usize) {
         self.max_depth = self.max_depth.max(depth);
         
         // Record this path
@@ -719,7 +726,7 @@ impl PathTracker {
         }
     }
     
-    fn build_tree(&self, total_rows: usize) -> TreeNode {
+    pub(crate) fn build_tree(&self, total_rows: usize) -> TreeNode {
         let mut root = TreeNode::new("$".to_string(), 0, NodeType::Object);
         
         // Group paths by their parent
@@ -793,6 +800,28 @@ impl PathTracker {
     }
 }
 
+impl ChunkParser for JsonParser {
+    type Output = JsonParseResult;
+
+    fn parse_chunk(&mut self, chunk: &[u8]) -> Result<JsonParseResult, String> {
+        Ok(JsonParser::parse_chunk(self, chunk))
+    }
+
+    fn flush(&mut self) -> Result<JsonParseResult, String> {
+        Ok(JsonParser::flush(self))
+    }
+
+    fn headers(&self) -> &[String] {
+        JsonParser::headers(self)
+    }
+
+    fn rows(output: &JsonParseResult) -> &[Vec<String>] {
+        &output.rows
+    }
+}
+
+pub type JsonSource = ChunkedSource<JsonParser>;
+
 #[cfg(test)]
 mod tests {
     use super::*;
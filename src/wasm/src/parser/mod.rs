@@ -1,11 +1,31 @@
+pub mod source;
 pub mod csv;
 pub mod json;
 pub mod parquet;
 pub mod avro;
+pub mod avro_messages;
+pub mod arrow;
+pub mod excel;
+pub mod orc;
+pub mod sqlite;
+pub mod xml;
 pub mod extractor;
+pub mod dataset;
+pub mod detect;
+pub mod universal;
 
-pub use self::csv::CsvParser;
-pub use self::json::{JsonParser, JsonParseResult, JsonFormat, JsonParserConfig, ArrayFieldStats};
+pub use self::source::ProfileDriver;
+pub use self::csv::{CsvParser, CsvSource};
+pub use self::json::{JsonParser, JsonSource, JsonParseResult, JsonFormat, JsonParserConfig, ArrayFieldStats};
 pub use self::parquet::ParquetProfiler;
 pub use self::avro::AvroProfiler;
-pub use self::extractor::RowExtractor;
\ No newline at end of file
+pub use self::avro_messages::AvroMessageProfiler;
+pub use self::arrow::ArrowProfiler;
+pub use self::excel::ExcelProfiler;
+pub use self::orc::OrcProfiler;
+pub use self::sqlite::SqliteProfiler;
+pub use self::xml::{XmlParser, XmlSource};
+pub use self::extractor::RowExtractor;
+pub use self::dataset::DatasetProfiler;
+pub use self::detect::FormatDetection;
+pub use self::universal::UniversalProfiler;
\ No newline at end of file
@@ -0,0 +1,650 @@
+mod proto;
+mod reader;
+mod rle;
+
+use wasm_bindgen::prelude::*;
+use serde::Serialize;
+use ts_rs::TS;
+use chrono::DateTime;
+use crate::parser::source::{ProfileDriver, RecordSource, SourceProgress, SourceSchema};
+use crate::stats::config::ProfilerConfig;
+use crate::stats::profiler::{Profiler, ProfilerResult};
+use crate::stats::types::LogicalType;
+use self::proto::{ColumnStats, Footer, OrcType, PostScript, StatsValue, TypeKind};
+use self::reader::{decompress, OrcValue, StripeData};
+
+const ORC_MAGIC: &[u8; 3] = b"ORC";
+
+/// Statistics an ORC writer recorded for one column, from the file footer or
+/// the per-stripe metadata section. Values are rendered like profiled values.
+#[derive(Serialize, Debug, Clone, TS)]
+#[ts(export)]
+pub struct OrcColumnStatistics {
+    /// Flattened column name, or the dotted path of a nested struct field
+    pub column: String,
+    pub orc_type: String,
+    pub number_of_values: Option<u64>,
+    pub has_null: Option<bool>,
+    pub minimum: Option<String>,
+    pub maximum: Option<String>,
+    pub sum: Option<String>,
+    /// Boolean columns: number of true values
+    pub true_count: Option<u64>,
+}
+
+#[derive(Serialize, Debug, Clone, TS)]
+#[ts(export)]
+pub struct OrcStripeSummary {
+    pub offset: u64,
+    pub rows: u64,
+    pub data_length: u64,
+    pub statistics: Vec<OrcColumnStatistics>,
+}
+
+/// File-level ORC metadata attached to the `ProfilerResult`.
+#[derive(Serialize, Debug, Clone, TS)]
+#[ts(export)]
+pub struct OrcMetadata {
+    pub compression: String,
+    pub file_version: String,
+    pub writer_version: u32,
+    pub row_index_stride: u32,
+    pub number_of_rows: u64,
+    pub statistics: Vec<OrcColumnStatistics>,
+    pub stripes: Vec<OrcStripeSummary>,
+}
+
+/// A profiled column: a top-level field, or a field of a nested struct
+/// reached through `path` (child positions from the root struct).
+struct OrcColumn {
+    name: String,
+    type_id: u32,
+    path: Vec<usize>,
+}
+
+/// A file opened from its tail: everything needed to decode stripes.
+struct OrcFile {
+    postscript: PostScript,
+    footer: Footer,
+    columns: Vec<OrcColumn>,
+    metadata: OrcMetadata,
+}
+
+/// `RecordSource` for ORC files. The tail holds the schema, so input is
+/// buffered until `finish`; each stripe then becomes one batch of rows.
+pub struct OrcSource {
+    buffer: Vec<u8>,
+    file: Option<OrcFile>,
+    next_stripe: usize,
+    rows: Vec<Vec<String>>,
+    progress: SourceProgress,
+}
+
+impl OrcSource {
+    pub fn new() -> Self {
+        Self {
+            buffer: Vec::new(),
+            file: None,
+            next_stripe: 0,
+            rows: Vec::new(),
+            progress: SourceProgress::default(),
+        }
+    }
+}
+
+impl RecordSource for OrcSource {
+    fn push(&mut self, chunk: &[u8]) -> Result<(), String> {
+        self.progress.bytes_received += chunk.len() as u64;
+        self.buffer.extend_from_slice(chunk);
+        Ok(())
+    }
+
+    fn finish(&mut self) -> Result<(), String> {
+        self.progress.finished = true;
+        self.file = Some(open_file(&self.buffer)?);
+        Ok(())
+    }
+
+    fn schema(&self) -> Option<SourceSchema> {
+        let file = self.file.as_ref()?;
+        Some(SourceSchema {
+            headers: file.columns.iter().map(|c| c.name.clone()).collect(),
+            logical_types: file.columns.iter()
+                .map(|c| logical_type_of(&file.footer.types[c.type_id as usize]))
+                .collect(),
+            declared_types: Vec::new(),
+        })
+    }
+
+    fn next_batch(&mut self) -> Result<Option<&[Vec<String>]>, String> {
+        let file = match self.file.as_ref() {
+            Some(file) => file,
+            None => return Ok(None),
+        };
+        let stripe = match file.footer.stripes.get(self.next_stripe) {
+            Some(stripe) => stripe,
+            None => return Ok(None),
+        };
+        self.next_stripe += 1;
+
+        let data = StripeData::load(&self.buffer, stripe, &file.postscript, &file.footer.types)?;
+        let rows = stripe.number_of_rows as usize;
+        let root = data.read_column(0, rows)?;
+        self.rows = root.iter()
+            .map(|record| file.columns.iter().map(|c| field_at(record, &c.path).to_profile_string()).collect())
+            .collect();
+        self.progress.record_batch(self.rows.len());
+        Ok(Some(&self.rows))
+    }
+
+    fn progress(&self) -> SourceProgress {
+        self.progress.clone()
+    }
+
+    fn streaming(&self) -> bool {
+        false
+    }
+
+    fn annotate(&self, profiler: &mut Profiler) {
+        if let Some(ref file) = self.file {
+            profiler.orc_metadata = Some(file.metadata.clone());
+        }
+    }
+}
+
+/// Profiles ORC files that are uncompressed or use zlib, Snappy, LZ4 or
+/// ZSTD; LZO-compressed files are rejected.
+#[wasm_bindgen]
+pub struct OrcProfiler {
+    driver: ProfileDriver<OrcSource>,
+}
+
+#[wasm_bindgen]
+impl OrcProfiler {
+    /// `config` is a `ProfilerConfig`; omitted fields keep their defaults.
+    #[wasm_bindgen(constructor)]
+    pub fn new(config: Option<JsValue>) -> Result<OrcProfiler, JsValue> {
+        let config = ProfilerConfig::from_js(config).map_err(|e| JsValue::from_str(&e))?;
+        Ok(Self::with_config(config))
+    }
+
+    /// Parses an entire ORC file buffer, stripe by stripe, and profiles it.
+    pub fn parse_and_profile(&mut self, file_bytes: &[u8]) -> Result<JsValue, JsValue> {
+        match self.parse_and_profile_internal(file_bytes) {
+            Ok(stats) => serde_wasm_bindgen::to_value(&stats).map_err(|e| JsValue::from_str(&e.to_string())),
+            Err(e) => Err(JsValue::from_str(&e)),
+        }
+    }
+}
+
+impl OrcProfiler {
+    pub(crate) fn with_config(config: ProfilerConfig) -> Self {
+        Self {
+            driver: ProfileDriver::new(OrcSource::new(), config),
+        }
+    }
+
+    pub(crate) fn parse_and_profile_internal(&mut self, file_bytes: &[u8]) -> Result<ProfilerResult, String> {
+        self.driver.push(file_bytes)?;
+        self.driver.finish()
+    }
+}
+
+/// True if `file_bytes` is a whole ORC file: it starts with the ORC header
+/// and ends with a postscript carrying the ORC magic, followed by its length.
+pub(crate) fn has_postscript(file_bytes: &[u8]) -> bool {
+    let Some((&ps_len, rest)) = file_bytes.split_last() else { return false };
+    let Some(ps_start) = rest.len().checked_sub(ps_len as usize) else { return false };
+    file_bytes.starts_with(ORC_MAGIC)
+        && rest.ends_with(ORC_MAGIC)
+        && ps_start >= ORC_MAGIC.len()
+        && PostScript::decode(&rest[ps_start..]).is_ok()
+}
+
+/// Reads the file tail: postscript, footer and stripe statistics.
+fn open_file(file_bytes: &[u8]) -> Result<OrcFile, String> {
+    if !file_bytes.starts_with(ORC_MAGIC) {
+        return Err("Not an ORC file: missing ORC header".to_string());
+    }
+
+    // File tail: ... | metadata | footer | postscript | postscript length (1 byte)
+    let ps_len = *file_bytes.last().ok_or_else(|| "Empty file".to_string())? as usize;
+    let ps_start = file_bytes.len().checked_sub(ps_len + 1)
+        .ok_or_else(|| "Truncated ORC PostScript".to_string())?;
+    let postscript = PostScript::decode(&file_bytes[ps_start..file_bytes.len() - 1])?;
+
+    let footer_start = ps_start.checked_sub(postscript.footer_length as usize)
+        .ok_or_else(|| "Truncated ORC footer".to_string())?;
+    let footer_bytes = decompress(postscript.compression, postscript.block_size, &file_bytes[footer_start..ps_start])?;
+    let footer = Footer::decode(&footer_bytes)?;
+
+    let metadata_start = footer_start.checked_sub(postscript.metadata_length as usize)
+        .ok_or_else(|| "Truncated ORC metadata".to_string())?;
+    let stripe_statistics = if postscript.metadata_length > 0 {
+        let bytes = decompress(postscript.compression, postscript.block_size, &file_bytes[metadata_start..footer_start])?;
+        proto::decode_stripe_statistics(&bytes)?
+    } else {
+        Vec::new()
+    };
+
+    let columns = flatten_columns(&footer.types)?;
+    let metadata = OrcMetadata {
+        compression: postscript.compression.name().to_string(),
+        file_version: postscript.version.iter().map(|v| v.to_string()).collect::<Vec<_>>().join("."),
+        writer_version: postscript.writer_version,
+        row_index_stride: footer.row_index_stride,
+        number_of_rows: footer.number_of_rows,
+        statistics: summarize_statistics(&footer.types, &footer.statistics),
+        stripes: footer.stripes.iter().enumerate().map(|(idx, stripe)| OrcStripeSummary {
+            offset: stripe.offset,
+            rows: stripe.number_of_rows,
+            data_length: stripe.data_length,
+            statistics: stripe_statistics.get(idx)
+                .map(|stats| summarize_statistics(&footer.types, stats))
+                .unwrap_or_default(),
+        }).collect(),
+    };
+
+    Ok(OrcFile { postscript, footer, columns, metadata })
+}
+
+/// Columns for the Profiler: fields of the root struct, with nested structs
+/// flattened into dotted names. Lists, maps and unions stay single columns.
+/// A file whose root is not a struct yields one column named "value".
+fn flatten_columns(types: &[OrcType]) -> Result<Vec<OrcColumn>, String> {
+    let mut columns = Vec::new();
+    if types[0].kind == TypeKind::Struct {
+        collect_columns(types, 0, "", &mut Vec::new(), &mut columns)?;
+    } else {
+        columns.push(OrcColumn { name: "value".to_string(), type_id: 0, path: Vec::new() });
+    }
+    Ok(columns)
+}
+
+fn collect_columns(types: &[OrcType], type_id: u32, prefix: &str, path: &mut Vec<usize>, out: &mut Vec<OrcColumn>) -> Result<(), String> {
+    let ty = types.get(type_id as usize).ok_or_else(|| format!("Unknown ORC type id {}", type_id))?;
+    for (idx, child) in ty.subtypes.iter().enumerate() {
+        let field = ty.field_names.get(idx).cloned().unwrap_or_else(|| format!("_col{}", idx));
+        let name = if prefix.is_empty() { field } else { format!("{}.{}", prefix, field) };
+        let child_ty = types.get(*child as usize).ok_or_else(|| format!("Unknown ORC type id {}", child))?;
+
+        path.push(idx);
+        if child_ty.kind == TypeKind::Struct {
+            collect_columns(types, *child, &name, path, out)?;
+        } else {
+            out.push(OrcColumn { name, type_id: *child, path: path.clone() });
+        }
+        path.pop();
+    }
+    Ok(())
+}
+
+fn field_at<'v>(record: &'v OrcValue, path: &[usize]) -> &'v OrcValue {
+    path.iter().fold(record, |value, idx| match value {
+        OrcValue::Struct(fields) => fields.get(*idx).unwrap_or(&OrcValue::Null),
+        _ => &OrcValue::Null,
+    })
+}
+
+fn logical_type_of(ty: &OrcType) -> Option<LogicalType> {
+    match ty.kind {
+        TypeKind::Date => Some(LogicalType::Date),
+        TypeKind::Timestamp => Some(LogicalType::LocalTimestampMicros),
+        TypeKind::TimestampInstant => Some(LogicalType::TimestampMicros),
+        TypeKind::Decimal => Some(LogicalType::Decimal {
+            precision: ty.precision as usize,
+            scale: ty.scale as usize,
+        }),
+        _ => None,
+    }
+}
+
+/// Pairs each column's writer statistics with its flattened name. Statistics
+/// are indexed by type id, so nested struct fields are included too.
+fn summarize_statistics(types: &[OrcType], stats: &[ColumnStats]) -> Vec<OrcColumnStatistics> {
+    let mut names = vec![String::new(); types.len()];
+    for (id, ty) in types.iter().enumerate() {
+        if ty.kind != TypeKind::Struct {
+            continue;
+        }
+        for (idx, child) in ty.subtypes.iter().enumerate() {
+            if let (Some(field), Some(slot)) = (ty.field_names.get(idx), names.get(*child as usize)) {
+                if slot.is_empty() {
+                    let parent = &names[id];
+                    names[*child as usize] = if parent.is_empty() { field.clone() } else { format!("{}.{}", parent, field) };
+                }
+            }
+        }
+    }
+
+    stats.iter().enumerate()
+        .filter_map(|(id, stat)| {
+            let ty = types.get(id)?;
+            let (minimum, maximum, sum, true_count) = stats_values(ty, &stat.value);
+            Some(OrcColumnStatistics {
+                column: names[id].clone(),
+                orc_type: ty.kind.name().to_string(),
+                number_of_values: stat.number_of_values,
+                has_null: stat.has_null,
+                minimum,
+                maximum,
+                sum,
+                true_count,
+            })
+        })
+        .collect()
+}
+
+type StatsStrings = (Option<String>, Option<String>, Option<String>, Option<u64>);
+
+fn stats_values(ty: &OrcType, value: &StatsValue) -> StatsStrings {
+    let date = |days: Option<i32>| days.and_then(|d| DateTime::from_timestamp(d as i64 * 86_400, 0))
+        .map(|dt| dt.format("%Y-%m-%d").to_string());
+    let timestamp = |millis: Option<i64>| millis.and_then(DateTime::from_timestamp_millis)
+        .map(|dt| dt.naive_utc().format("%Y-%m-%dT%H:%M:%S%.3f").to_string());
+
+    match value {
+        StatsValue::Int { min, max, sum } => (min.map(|v| v.to_string()), max.map(|v| v.to_string()), sum.map(|v| v.to_string()), None),
+        StatsValue::Double { min, max, sum } => (min.map(|v| v.to_string()), max.map(|v| v.to_string()), sum.map(|v| v.to_string()), None),
+        StatsValue::String { min, max, sum } => (min.clone(), max.clone(), sum.map(|v| v.to_string()), None),
+        StatsValue::Decimal { min, max, sum } => (min.clone(), max.clone(), sum.clone(), None),
+        StatsValue::Date { min, max } => (date(*min), date(*max), None, None),
+        StatsValue::Timestamp { min, max } => (timestamp(*min), timestamp(*max), None, None),
+        StatsValue::Binary { sum } => (None, None, sum.map(|v| v.to_string()), None),
+        StatsValue::Bucket { true_count } if ty.kind == TypeKind::Boolean => (None, None, None, Some(*true_count)),
+        _ => (None, None, None, None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use super::proto::Compression;
+
+    fn varint(out: &mut Vec<u8>, mut value: u64) {
+        while value >= 0x80 {
+            out.push((value as u8) | 0x80);
+            value >>= 7;
+        }
+        out.push(value as u8);
+    }
+
+    fn zigzag(value: i64) -> u64 {
+        ((value << 1) ^ (value >> 63)) as u64
+    }
+
+    fn field_varint(out: &mut Vec<u8>, field: u64, value: u64) {
+        varint(out, field << 3);
+        varint(out, value);
+    }
+
+    fn field_bytes(out: &mut Vec<u8>, field: u64, bytes: &[u8]) {
+        varint(out, (field << 3) | 2);
+        varint(out, bytes.len() as u64);
+        out.extend_from_slice(bytes);
+    }
+
+    fn orc_type(kind: u64, subtypes: &[u64], names: &[&str], precision_scale: Option<(u64, u64)>) -> Vec<u8> {
+        let mut out = Vec::new();
+        field_varint(&mut out, 1, kind);
+        for sub in subtypes {
+            field_varint(&mut out, 2, *sub);
+        }
+        for name in names {
+            field_bytes(&mut out, 3, name.as_bytes());
+        }
+        if let Some((precision, scale)) = precision_scale {
+            field_varint(&mut out, 5, precision);
+            field_varint(&mut out, 6, scale);
+        }
+        out
+    }
+
+    fn int_stats(values: u64, min: i64, max: i64, sum: i64) -> Vec<u8> {
+        let mut int = Vec::new();
+        field_varint(&mut int, 1, zigzag(min));
+        field_varint(&mut int, 2, zigzag(max));
+        field_varint(&mut int, 3, zigzag(sum));
+        let mut out = Vec::new();
+        field_varint(&mut out, 1, values);
+        field_bytes(&mut out, 2, &int);
+        field_varint(&mut out, 10, 0);
+        out
+    }
+
+    /// Compresses a section the way ORC writers do: codec chunks with a
+    /// 3-byte header, or the raw bytes when the file is uncompressed.
+    fn compress(compression: Compression, data: &[u8]) -> Vec<u8> {
+        let body = match compression {
+            Compression::None => return data.to_vec(),
+            Compression::Zlib => {
+                let mut encoder = flate2::write::DeflateEncoder::new(Vec::new(), flate2::Compression::default());
+                encoder.write_all(data).unwrap();
+                encoder.finish().unwrap()
+            }
+            Compression::Zstd => ruzstd::encoding::compress_to_vec(data, ruzstd::encoding::CompressionLevel::Fastest),
+            Compression::Snappy => snap::raw::Encoder::new().compress_vec(data).unwrap(),
+            Compression::Lz4 => lz4_flex::block::compress(data),
+            Compression::Lzo => panic!("no LZO encoder for test files"),
+        };
+        let header = body.len() << 1;
+        let mut out = vec![header as u8, (header >> 8) as u8, (header >> 16) as u8];
+        out.extend_from_slice(&body);
+        out
+    }
+
+    /// Three rows of struct<id:bigint, name:string, price:decimal(10,2),
+    /// day:date, active:boolean, address:struct<city:string>, tags:array<string>>,
+    /// written with RLE v1 in a single stripe of `rows` rows (3 unless forged).
+    fn sample_file(compression: Compression, rows: u64) -> Vec<u8> {
+        // (column, kind, bytes); kinds: 0 PRESENT, 1 DATA, 2 LENGTH, 5 SECONDARY
+        let streams: Vec<(u64, u64, Vec<u8>)> = vec![
+            (1, 1, vec![0xfd, 2, 4, 6]),
+            (2, 0, vec![0xff, 0xa0]),
+            (2, 1, b"ac".to_vec()),
+            (2, 2, vec![0xfe, 1, 1]),
+            (3, 1, vec![0xc4, 0x13, 0xd8, 0x04, 0x95, 0x01]),
+            (3, 5, vec![0xfd, 4, 4, 4]),
+            (4, 1, vec![0x00, 0x01, 0xf0, 0xa8, 0x02]),
+            (5, 1, vec![0xff, 0xa0]),
+            (6, 0, vec![0xff, 0xa0]),
+            (7, 1, b"OsloRome".to_vec()),
+            (7, 2, vec![0xfe, 4, 4]),
+            (8, 2, vec![0xfd, 2, 0, 1]),
+            (9, 1, b"xyz".to_vec()),
+            (9, 2, vec![0xfd, 1, 1, 1]),
+        ];
+
+        let mut file = b"ORC".to_vec();
+        let stripe_offset = file.len() as u64;
+        let mut stripe_footer = Vec::new();
+        for (column, kind, bytes) in &streams {
+            let data = compress(compression, bytes);
+            let mut stream = Vec::new();
+            field_varint(&mut stream, 1, *kind);
+            field_varint(&mut stream, 2, *column);
+            field_varint(&mut stream, 3, data.len() as u64);
+            field_bytes(&mut stripe_footer, 1, &stream);
+            file.extend_from_slice(&data);
+        }
+        for _ in 0..10 {
+            let mut encoding = Vec::new();
+            field_varint(&mut encoding, 1, 0);
+            field_bytes(&mut stripe_footer, 2, &encoding);
+        }
+        let data_length = file.len() as u64 - stripe_offset;
+        let stripe_footer = compress(compression, &stripe_footer);
+        file.extend_from_slice(&stripe_footer);
+
+        let mut stripe_stats = Vec::new();
+        field_bytes(&mut stripe_stats, 1, &int_stats(3, 0, 0, 0));
+        field_bytes(&mut stripe_stats, 1, &int_stats(3, 1, 3, 6));
+        let mut metadata = Vec::new();
+        field_bytes(&mut metadata, 1, &stripe_stats);
+        let metadata = compress(compression, &metadata);
+        file.extend_from_slice(&metadata);
+
+        let mut stripe = Vec::new();
+        field_varint(&mut stripe, 1, stripe_offset);
+        field_varint(&mut stripe, 2, 0);
+        field_varint(&mut stripe, 3, data_length);
+        field_varint(&mut stripe, 4, stripe_footer.len() as u64);
+        field_varint(&mut stripe, 5, rows);
+
+        let mut footer = Vec::new();
+        field_varint(&mut footer, 1, 3);
+        field_bytes(&mut footer, 3, &stripe);
+        let types = [
+            orc_type(12, &[1, 2, 3, 4, 5, 6, 8], &["id", "name", "price", "day", "active", "address", "tags"], None),
+            orc_type(4, &[], &[], None),
+            orc_type(7, &[], &[], None),
+            orc_type(14, &[], &[], Some((10, 2))),
+            orc_type(15, &[], &[], None),
+            orc_type(0, &[], &[], None),
+            orc_type(12, &[7], &["city"], None),
+            orc_type(7, &[], &[], None),
+            orc_type(10, &[9], &[], None),
+            orc_type(7, &[], &[], None),
+        ];
+        for ty in &types {
+            field_bytes(&mut footer, 4, ty);
+        }
+        field_varint(&mut footer, 6, 3);
+        field_bytes(&mut footer, 7, &int_stats(3, 0, 0, 0));
+        field_bytes(&mut footer, 7, &int_stats(3, 1, 3, 6));
+        let footer = compress(compression, &footer);
+        file.extend_from_slice(&footer);
+
+        let mut postscript = Vec::new();
+        field_varint(&mut postscript, 1, footer.len() as u64);
+        field_varint(&mut postscript, 2, compression as u64);
+        field_varint(&mut postscript, 3, 256 * 1024);
+        field_varint(&mut postscript, 4, 0);
+        field_varint(&mut postscript, 4, 12);
+        field_varint(&mut postscript, 5, metadata.len() as u64);
+        field_bytes(&mut postscript, 8000, b"ORC");
+        file.extend_from_slice(&postscript);
+        file.push(postscript.len() as u8);
+        file
+    }
+
+    #[test]
+    fn test_profile_uncompressed_file() {
+        let mut profiler = OrcProfiler::with_config(ProfilerConfig::default());
+        let result = profiler.parse_and_profile_internal(&sample_file(Compression::None, 3)).unwrap();
+
+        let names: Vec<&str> = result.column_profiles.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, vec!["id", "name", "price", "day", "active", "address.city", "tags"]);
+        assert_eq!(result.total_rows, 3);
+        assert_eq!(result.column_profiles[1].base_stats.missing, 1);
+        assert_eq!(result.column_profiles[5].base_stats.missing, 1);
+
+        let decimal = result.column_profiles[2].decimal_stats.as_ref().unwrap();
+        assert_eq!(decimal.min.as_deref(), Some("-0.75"));
+        assert_eq!(decimal.max.as_deref(), Some("12.50"));
+        let days = result.column_profiles[3].date_range.as_ref().unwrap();
+        assert_eq!((days.min.as_str(), days.max.as_str()), ("2022-01-08", "2022-01-10"));
+
+        let metadata = result.orc_metadata.unwrap();
+        assert_eq!(metadata.compression, "none");
+        assert_eq!(metadata.file_version, "0.12");
+        assert_eq!(metadata.statistics[1].column, "id");
+        assert_eq!(metadata.statistics[1].maximum.as_deref(), Some("3"));
+        assert_eq!(metadata.stripes[0].statistics[1].sum.as_deref(), Some("6"));
+    }
+
+    #[test]
+    fn test_profile_zlib_file_matches_uncompressed() {
+        let mut plain = OrcProfiler::with_config(ProfilerConfig::default());
+        let plain = plain.parse_and_profile_internal(&sample_file(Compression::None, 3)).unwrap();
+        let mut zlib = OrcProfiler::with_config(ProfilerConfig::default());
+        let zlib = zlib.parse_and_profile_internal(&sample_file(Compression::Zlib, 3)).unwrap();
+
+        assert_eq!(zlib.orc_metadata.as_ref().unwrap().compression, "zlib");
+        assert_eq!(zlib.total_rows, plain.total_rows);
+        let top = |r: &ProfilerResult, col: usize| r.column_profiles[col].categorical_stats.as_ref()
+            .map(|c| {
+                let mut values: Vec<String> = c.top_values.iter().map(|v| v.value.clone()).collect();
+                values.sort();
+                values
+            });
+        for col in 0..plain.column_profiles.len() {
+            assert_eq!(top(&zlib, col), top(&plain, col));
+        }
+        let tags = top(&zlib, 6).unwrap();
+        assert!(tags.contains(&r#"["x","y"]"#.to_string()));
+        assert!(tags.contains(&"[]".to_string()));
+    }
+
+    #[test]
+    fn test_profile_zstd_file() {
+        let mut profiler = OrcProfiler::with_config(ProfilerConfig::default());
+        let result = profiler.parse_and_profile_internal(&sample_file(Compression::Zstd, 3)).unwrap();
+        assert_eq!(result.orc_metadata.as_ref().unwrap().compression, "zstd");
+        assert_eq!(result.total_rows, 3);
+        assert_eq!(result.column_profiles[1].base_stats.missing, 1);
+    }
+
+    #[test]
+    fn test_profile_lz4_file() {
+        let mut profiler = OrcProfiler::with_config(ProfilerConfig::default());
+        let result = profiler.parse_and_profile_internal(&sample_file(Compression::Lz4, 3)).unwrap();
+        assert_eq!(result.orc_metadata.as_ref().unwrap().compression, "lz4");
+        assert_eq!(result.total_rows, 3);
+        assert_eq!(result.column_profiles[1].base_stats.missing, 1);
+    }
+
+    #[test]
+    fn test_forged_row_count_is_an_error() {
+        let profile = |rows| OrcProfiler::with_config(ProfilerConfig::default()).parse_and_profile_internal(&sample_file(Compression::None, rows));
+        assert!(profile(1 << 40).is_err());
+        // Within what the streams could hold, but more than they do
+        assert!(profile(50).is_err());
+    }
+
+    #[test]
+    fn test_type_tree_cycles_are_errors() {
+        let footer_with = |types: &[Vec<u8>]| {
+            let mut footer = Vec::new();
+            for ty in types {
+                field_bytes(&mut footer, 4, ty);
+            }
+            proto::Footer::decode(&footer)
+        };
+        let valid = footer_with(&[orc_type(12, &[1], &["a"], None), orc_type(4, &[], &[], None)]);
+        assert!(valid.is_ok());
+        assert!(footer_with(&[orc_type(12, &[0], &["a"], None)]).is_err());
+        assert!(footer_with(&[orc_type(12, &[1], &["a"], None), orc_type(12, &[0], &["b"], None)]).is_err());
+        assert!(footer_with(&[orc_type(12, &[5], &["a"], None)]).is_err());
+    }
+
+    #[test]
+    fn test_forged_block_size_is_an_error() {
+        let postscript = |block_size| {
+            let mut postscript = Vec::new();
+            field_varint(&mut postscript, 2, 4);
+            field_varint(&mut postscript, 3, block_size);
+            field_bytes(&mut postscript, 8000, b"ORC");
+            proto::PostScript::decode(&postscript)
+        };
+        assert!(postscript(256 * 1024).is_ok());
+        assert!(postscript(1 << 40).is_err());
+    }
+
+    #[test]
+    fn test_chunks_larger_than_the_block_size_are_an_error() {
+        let data = vec![0u8; 4096];
+        for compression in [Compression::Zlib, Compression::Snappy, Compression::Lz4, Compression::Zstd] {
+            let chunk = compress(compression, &data);
+            assert_eq!(decompress(compression, 4096, &chunk).unwrap(), data);
+            assert!(decompress(compression, 4095, &chunk).is_err(), "{:?}", compression);
+        }
+    }
+
+    #[test]
+    fn test_rejects_non_orc_input() {
+        let mut profiler = OrcProfiler::with_config(ProfilerConfig::default());
+        assert!(profiler.parse_and_profile_internal(b"PAR1not orc").is_err());
+    }
+}
@@ -0,0 +1,637 @@
+//! Just enough of a protobuf decoder to read the ORC file tail (PostScript,
+//! Footer, Metadata) and stripe footers. Unknown fields are skipped.
+
+use crate::parser::orc::rle::read_uvarint;
+
+/// Largest compression chunk a 3-byte chunk header can describe.
+const MAX_BLOCK_SIZE: u64 = (1 << 23) - 1;
+
+enum WireValue<'a> {
+    Varint(u64),
+    Fixed64(u64),
+    Bytes(&'a [u8]),
+    Fixed32(u32),
+}
+
+impl<'a> WireValue<'a> {
+    fn as_u64(&self) -> u64 {
+        match self {
+            WireValue::Varint(v) | WireValue::Fixed64(v) => *v,
+            WireValue::Fixed32(v) => *v as u64,
+            WireValue::Bytes(_) => 0,
+        }
+    }
+
+    fn as_sint64(&self) -> i64 {
+        let v = self.as_u64();
+        ((v >> 1) as i64) ^ -((v & 1) as i64)
+    }
+
+    fn as_f64(&self) -> f64 {
+        match self {
+            WireValue::Fixed64(v) => f64::from_bits(*v),
+            _ => 0.0,
+        }
+    }
+
+    fn as_bytes(&self) -> &'a [u8] {
+        match self {
+            WireValue::Bytes(b) => b,
+            _ => &[],
+        }
+    }
+
+    fn as_string(&self) -> String {
+        String::from_utf8_lossy(self.as_bytes()).into_owned()
+    }
+
+    /// Repeated scalar fields may be written packed or one value per tag.
+    fn push_u32s(&self, out: &mut Vec<u32>) -> Result<(), String> {
+        match self {
+            WireValue::Bytes(b) => {
+                let mut pos = 0;
+                while pos < b.len() {
+                    out.push(read_uvarint(b, &mut pos)? as u32);
+                }
+            }
+            other => out.push(other.as_u64() as u32),
+        }
+        Ok(())
+    }
+}
+
+struct ProtoReader<'a> {
+    buf: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> ProtoReader<'a> {
+    fn new(buf: &'a [u8]) -> Self {
+        Self { buf, pos: 0 }
+    }
+
+    fn next_field(&mut self) -> Result<Option<(u32, WireValue<'a>)>, String> {
+        if self.pos >= self.buf.len() {
+            return Ok(None);
+        }
+        let key = read_uvarint(self.buf, &mut self.pos)?;
+        let field = (key >> 3) as u32;
+        let value = match key & 0x7 {
+            0 => WireValue::Varint(read_uvarint(self.buf, &mut self.pos)?),
+            1 => WireValue::Fixed64(u64::from_le_bytes(self.take(8)?.try_into().unwrap())),
+            2 => {
+                let len = read_uvarint(self.buf, &mut self.pos)? as usize;
+                WireValue::Bytes(self.take(len)?)
+            }
+            5 => WireValue::Fixed32(u32::from_le_bytes(self.take(4)?.try_into().unwrap())),
+            wire => return Err(format!("Unsupported protobuf wire type {}", wire)),
+        };
+        Ok(Some((field, value)))
+    }
+
+    fn take(&mut self, len: usize) -> Result<&'a [u8], String> {
+        let end = self.pos.checked_add(len).filter(|&end| end <= self.buf.len())
+            .ok_or_else(|| "Truncated protobuf message".to_string())?;
+        let bytes = &self.buf[self.pos..end];
+        self.pos = end;
+        Ok(bytes)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum Compression {
+    None,
+    Zlib,
+    Snappy,
+    Lzo,
+    Lz4,
+    Zstd,
+}
+
+impl Compression {
+    pub(crate) fn name(&self) -> &'static str {
+        match self {
+            Compression::None => "none",
+            Compression::Zlib => "zlib",
+            Compression::Snappy => "snappy",
+            Compression::Lzo => "lzo",
+            Compression::Lz4 => "lz4",
+            Compression::Zstd => "zstd",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct PostScript {
+    pub(crate) footer_length: u64,
+    pub(crate) compression: Compression,
+    pub(crate) block_size: u64,
+    pub(crate) version: Vec<u32>,
+    pub(crate) metadata_length: u64,
+    pub(crate) writer_version: u32,
+}
+
+impl PostScript {
+    pub(crate) fn decode(buf: &[u8]) -> Result<Self, String> {
+        let mut ps = PostScript {
+            footer_length: 0,
+            compression: Compression::None,
+            block_size: 256 * 1024,
+            version: Vec::new(),
+            metadata_length: 0,
+            writer_version: 0,
+        };
+        let mut magic = String::new();
+        let mut reader = ProtoReader::new(buf);
+        while let Some((field, value)) = reader.next_field()? {
+            match field {
+                1 => ps.footer_length = value.as_u64(),
+                2 => {
+                    ps.compression = match value.as_u64() {
+                        0 => Compression::None,
+                        1 => Compression::Zlib,
+                        2 => Compression::Snappy,
+                        3 => Compression::Lzo,
+                        4 => Compression::Lz4,
+                        5 => Compression::Zstd,
+                        other => return Err(format!("Unknown ORC compression kind {}", other)),
+                    }
+                }
+                3 => ps.block_size = value.as_u64(),
+                4 => value.push_u32s(&mut ps.version)?,
+                5 => ps.metadata_length = value.as_u64(),
+                6 => ps.writer_version = value.as_u64() as u32,
+                8000 => magic = value.as_string(),
+                _ => {}
+            }
+        }
+        if magic != "ORC" {
+            return Err("Not an ORC file: PostScript magic missing".to_string());
+        }
+        // Chunk headers hold a 23-bit length, so no chunk can be larger
+        if ps.block_size > MAX_BLOCK_SIZE {
+            return Err(format!("Invalid ORC compression block size {}", ps.block_size));
+        }
+        Ok(ps)
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub(crate) struct StripeInfo {
+    pub(crate) offset: u64,
+    pub(crate) index_length: u64,
+    pub(crate) data_length: u64,
+    pub(crate) footer_length: u64,
+    pub(crate) number_of_rows: u64,
+}
+
+impl StripeInfo {
+    fn decode(buf: &[u8]) -> Result<Self, String> {
+        let mut stripe = StripeInfo::default();
+        let mut reader = ProtoReader::new(buf);
+        while let Some((field, value)) = reader.next_field()? {
+            match field {
+                1 => stripe.offset = value.as_u64(),
+                2 => stripe.index_length = value.as_u64(),
+                3 => stripe.data_length = value.as_u64(),
+                4 => stripe.footer_length = value.as_u64(),
+                5 => stripe.number_of_rows = value.as_u64(),
+                _ => {}
+            }
+        }
+        Ok(stripe)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum TypeKind {
+    Boolean,
+    Byte,
+    Short,
+    Int,
+    Long,
+    Float,
+    Double,
+    String,
+    Binary,
+    Timestamp,
+    List,
+    Map,
+    Struct,
+    Union,
+    Decimal,
+    Date,
+    Varchar,
+    Char,
+    TimestampInstant,
+}
+
+impl TypeKind {
+    fn from_proto(kind: u64) -> Result<Self, String> {
+        Ok(match kind {
+            0 => TypeKind::Boolean,
+            1 => TypeKind::Byte,
+            2 => TypeKind::Short,
+            3 => TypeKind::Int,
+            4 => TypeKind::Long,
+            5 => TypeKind::Float,
+            6 => TypeKind::Double,
+            7 => TypeKind::String,
+            8 => TypeKind::Binary,
+            9 => TypeKind::Timestamp,
+            10 => TypeKind::List,
+            11 => TypeKind::Map,
+            12 => TypeKind::Struct,
+            13 => TypeKind::Union,
+            14 => TypeKind::Decimal,
+            15 => TypeKind::Date,
+            16 => TypeKind::Varchar,
+            17 => TypeKind::Char,
+            18 => TypeKind::TimestampInstant,
+            other => return Err(format!("Unknown ORC type kind {}", other)),
+        })
+    }
+
+    pub(crate) fn name(&self) -> &'static str {
+        match self {
+            TypeKind::Boolean => "boolean",
+            TypeKind::Byte => "tinyint",
+            TypeKind::Short => "smallint",
+            TypeKind::Int => "int",
+            TypeKind::Long => "bigint",
+            TypeKind::Float => "float",
+            TypeKind::Double => "double",
+            TypeKind::String => "string",
+            TypeKind::Binary => "binary",
+            TypeKind::Timestamp => "timestamp",
+            TypeKind::List => "array",
+            TypeKind::Map => "map",
+            TypeKind::Struct => "struct",
+            TypeKind::Union => "uniontype",
+            TypeKind::Decimal => "decimal",
+            TypeKind::Date => "date",
+            TypeKind::Varchar => "varchar",
+            TypeKind::Char => "char",
+            TypeKind::TimestampInstant => "timestamp with local time zone",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct OrcType {
+    pub(crate) kind: TypeKind,
+    pub(crate) subtypes: Vec<u32>,
+    pub(crate) field_names: Vec<String>,
+    pub(crate) precision: u32,
+    pub(crate) scale: u32,
+}
+
+impl OrcType {
+    fn decode(buf: &[u8]) -> Result<Self, String> {
+        let mut kind = None;
+        let mut ty = OrcType {
+            kind: TypeKind::Struct,
+            subtypes: Vec::new(),
+            field_names: Vec::new(),
+            precision: 0,
+            scale: 0,
+        };
+        let mut reader = ProtoReader::new(buf);
+        while let Some((field, value)) = reader.next_field()? {
+            match field {
+                1 => kind = Some(TypeKind::from_proto(value.as_u64())?),
+                2 => value.push_u32s(&mut ty.subtypes)?,
+                3 => ty.field_names.push(value.as_string()),
+                5 => ty.precision = value.as_u64() as u32,
+                6 => ty.scale = value.as_u64() as u32,
+                _ => {}
+            }
+        }
+        ty.kind = kind.ok_or_else(|| "ORC type without a kind".to_string())?;
+        Ok(ty)
+    }
+}
+
+/// Type-specific part of an ORC `ColumnStatistics` message.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum StatsValue {
+    Int { min: Option<i64>, max: Option<i64>, sum: Option<i64> },
+    Double { min: Option<f64>, max: Option<f64>, sum: Option<f64> },
+    String { min: Option<String>, max: Option<String>, sum: Option<i64> },
+    Bucket { true_count: u64 },
+    Decimal { min: Option<String>, max: Option<String>, sum: Option<String> },
+    Date { min: Option<i32>, max: Option<i32> },
+    Binary { sum: Option<i64> },
+    /// Milliseconds since the Unix epoch
+    Timestamp { min: Option<i64>, max: Option<i64> },
+    None,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct ColumnStats {
+    pub(crate) number_of_values: Option<u64>,
+    pub(crate) has_null: Option<bool>,
+    pub(crate) value: StatsValue,
+}
+
+impl ColumnStats {
+    fn decode(buf: &[u8]) -> Result<Self, String> {
+        let mut stats = ColumnStats {
+            number_of_values: None,
+            has_null: None,
+            value: StatsValue::None,
+        };
+        let mut reader = ProtoReader::new(buf);
+        while let Some((field, value)) = reader.next_field()? {
+            match field {
+                1 => stats.number_of_values = Some(value.as_u64()),
+                2 => stats.value = decode_int_stats(value.as_bytes())?,
+                3 => stats.value = decode_double_stats(value.as_bytes())?,
+                4 => stats.value = decode_string_stats(value.as_bytes())?,
+                5 => stats.value = decode_bucket_stats(value.as_bytes())?,
+                6 => stats.value = decode_decimal_stats(value.as_bytes())?,
+                7 => stats.value = decode_date_stats(value.as_bytes())?,
+                8 => stats.value = decode_binary_stats(value.as_bytes())?,
+                9 => stats.value = decode_timestamp_stats(value.as_bytes())?,
+                10 => stats.has_null = Some(value.as_u64() != 0),
+                _ => {}
+            }
+        }
+        Ok(stats)
+    }
+}
+
+fn decode_int_stats(buf: &[u8]) -> Result<StatsValue, String> {
+    let (mut min, mut max, mut sum) = (None, None, None);
+    let mut reader = ProtoReader::new(buf);
+    while let Some((field, value)) = reader.next_field()? {
+        match field {
+            1 => min = Some(value.as_sint64()),
+            2 => max = Some(value.as_sint64()),
+            3 => sum = Some(value.as_sint64()),
+            _ => {}
+        }
+    }
+    Ok(StatsValue::Int { min, max, sum })
+}
+
+fn decode_double_stats(buf: &[u8]) -> Result<StatsValue, String> {
+    let (mut min, mut max, mut sum) = (None, None, None);
+    let mut reader = ProtoReader::new(buf);
+    while let Some((field, value)) = reader.next_field()? {
+        match field {
+            1 => min = Some(value.as_f64()),
+            2 => max = Some(value.as_f64()),
+            3 => sum = Some(value.as_f64()),
+            _ => {}
+        }
+    }
+    Ok(StatsValue::Double { min, max, sum })
+}
+
+fn decode_string_stats(buf: &[u8]) -> Result<StatsValue, String> {
+    let (mut min, mut max, mut sum) = (None, None, None);
+    let mut reader = ProtoReader::new(buf);
+    while let Some((field, value)) = reader.next_field()? {
+        match field {
+            1 => min = Some(value.as_string()),
+            2 => max = Some(value.as_string()),
+            3 => sum = Some(value.as_sint64()),
+            _ => {}
+        }
+    }
+    Ok(StatsValue::String { min, max, sum })
+}
+
+fn decode_bucket_stats(buf: &[u8]) -> Result<StatsValue, String> {
+    let mut counts = Vec::new();
+    let mut reader = ProtoReader::new(buf);
+    while let Some((field, value)) = reader.next_field()? {
+        if field == 1 {
+            let mut values = Vec::new();
+            match value {
+                WireValue::Bytes(b) => {
+                    let mut pos = 0;
+                    while pos < b.len() {
+                        values.push(read_uvarint(b, &mut pos)?);
+                    }
+                }
+                other => values.push(other.as_u64()),
+            }
+            counts.extend(values);
+        }
+    }
+    Ok(StatsValue::Bucket { true_count: counts.first().copied().unwrap_or(0) })
+}
+
+fn decode_decimal_stats(buf: &[u8]) -> Result<StatsValue, String> {
+    let (mut min, mut max, mut sum) = (None, None, None);
+    let mut reader = ProtoReader::new(buf);
+    while let Some((field, value)) = reader.next_field()? {
+        match field {
+            1 => min = Some(value.as_string()),
+            2 => max = Some(value.as_string()),
+            3 => sum = Some(value.as_string()),
+            _ => {}
+        }
+    }
+    Ok(StatsValue::Decimal { min, max, sum })
+}
+
+fn decode_date_stats(buf: &[u8]) -> Result<StatsValue, String> {
+    let (mut min, mut max) = (None, None);
+    let mut reader = ProtoReader::new(buf);
+    while let Some((field, value)) = reader.next_field()? {
+        match field {
+            1 => min = Some(value.as_sint64() as i32),
+            2 => max = Some(value.as_sint64() as i32),
+            _ => {}
+        }
+    }
+    Ok(StatsValue::Date { min, max })
+}
+
+fn decode_binary_stats(buf: &[u8]) -> Result<StatsValue, String> {
+    let mut sum = None;
+    let mut reader = ProtoReader::new(buf);
+    while let Some((field, value)) = reader.next_field()? {
+        if field == 1 {
+            sum = Some(value.as_sint64());
+        }
+    }
+    Ok(StatsValue::Binary { sum })
+}
+
+fn decode_timestamp_stats(buf: &[u8]) -> Result<StatsValue, String> {
+    let (mut min, mut max) = (None, None);
+    let (mut min_utc, mut max_utc) = (None, None);
+    let mut reader = ProtoReader::new(buf);
+    while let Some((field, value)) = reader.next_field()? {
+        match field {
+            1 => min = Some(value.as_sint64()),
+            2 => max = Some(value.as_sint64()),
+            3 => min_utc = Some(value.as_sint64()),
+            4 => max_utc = Some(value.as_sint64()),
+            _ => {}
+        }
+    }
+    // The UTC variants were added later; writers that have them prefer them
+    Ok(StatsValue::Timestamp { min: min_utc.or(min), max: max_utc.or(max) })
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct Footer {
+    pub(crate) stripes: Vec<StripeInfo>,
+    pub(crate) types: Vec<OrcType>,
+    pub(crate) number_of_rows: u64,
+    pub(crate) statistics: Vec<ColumnStats>,
+    pub(crate) row_index_stride: u32,
+}
+
+impl Footer {
+    pub(crate) fn decode(buf: &[u8]) -> Result<Self, String> {
+        let mut footer = Footer {
+            stripes: Vec::new(),
+            types: Vec::new(),
+            number_of_rows: 0,
+            statistics: Vec::new(),
+            row_index_stride: 0,
+        };
+        let mut reader = ProtoReader::new(buf);
+        while let Some((field, value)) = reader.next_field()? {
+            match field {
+                3 => footer.stripes.push(StripeInfo::decode(value.as_bytes())?),
+                4 => footer.types.push(OrcType::decode(value.as_bytes())?),
+                6 => footer.number_of_rows = value.as_u64(),
+                7 => footer.statistics.push(ColumnStats::decode(value.as_bytes())?),
+                8 => footer.row_index_stride = value.as_u64() as u32,
+                _ => {}
+            }
+        }
+        if footer.types.is_empty() {
+            return Err("ORC footer has no type information".to_string());
+        }
+        // Children follow their parent in the type list, so the tree has no
+        // cycles and every recursion over it ends
+        for (id, ty) in footer.types.iter().enumerate() {
+            if let Some(&child) = ty.subtypes.iter().find(|&&child| child as usize <= id || child as usize >= footer.types.len()) {
+                return Err(format!("ORC type {} has invalid subtype {}", id, child));
+            }
+        }
+        Ok(footer)
+    }
+}
+
+/// The optional Metadata section: per-stripe column statistics.
+pub(crate) fn decode_stripe_statistics(buf: &[u8]) -> Result<Vec<Vec<ColumnStats>>, String> {
+    let mut stripes = Vec::new();
+    let mut reader = ProtoReader::new(buf);
+    while let Some((field, value)) = reader.next_field()? {
+        if field == 1 {
+            let mut columns = Vec::new();
+            let mut inner = ProtoReader::new(value.as_bytes());
+            while let Some((field, value)) = inner.next_field()? {
+                if field == 1 {
+                    columns.push(ColumnStats::decode(value.as_bytes())?);
+                }
+            }
+            stripes.push(columns);
+        }
+    }
+    Ok(stripes)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(crate) enum StreamKind {
+    Present,
+    Data,
+    Length,
+    DictionaryData,
+    Secondary,
+    Other,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct StreamInfo {
+    pub(crate) kind: StreamKind,
+    pub(crate) column: u32,
+    pub(crate) length: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum EncodingKind {
+    Direct,
+    Dictionary,
+    DirectV2,
+    DictionaryV2,
+}
+
+impl EncodingKind {
+    pub(crate) fn is_v2(&self) -> bool {
+        matches!(self, EncodingKind::DirectV2 | EncodingKind::DictionaryV2)
+    }
+
+    pub(crate) fn is_dictionary(&self) -> bool {
+        matches!(self, EncodingKind::Dictionary | EncodingKind::DictionaryV2)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct StripeFooter {
+    pub(crate) streams: Vec<StreamInfo>,
+    pub(crate) encodings: Vec<EncodingKind>,
+}
+
+impl StripeFooter {
+    pub(crate) fn decode(buf: &[u8]) -> Result<Self, String> {
+        let mut footer = StripeFooter {
+            streams: Vec::new(),
+            encodings: Vec::new(),
+        };
+        let mut reader = ProtoReader::new(buf);
+        while let Some((field, value)) = reader.next_field()? {
+            match field {
+                1 => {
+                    let mut stream = StreamInfo { kind: StreamKind::Other, column: 0, length: 0 };
+                    let mut inner = ProtoReader::new(value.as_bytes());
+                    while let Some((field, value)) = inner.next_field()? {
+                        match field {
+                            1 => {
+                                stream.kind = match value.as_u64() {
+                                    0 => StreamKind::Present,
+                                    1 => StreamKind::Data,
+                                    2 => StreamKind::Length,
+                                    3 => StreamKind::DictionaryData,
+                                    5 => StreamKind::Secondary,
+                                    _ => StreamKind::Other,
+                                }
+                            }
+                            2 => stream.column = value.as_u64() as u32,
+                            3 => stream.length = value.as_u64(),
+                            _ => {}
+                        }
+                    }
+                    footer.streams.push(stream);
+                }
+                2 => {
+                    let mut kind = EncodingKind::Direct;
+                    let mut inner = ProtoReader::new(value.as_bytes());
+                    while let Some((field, value)) = inner.next_field()? {
+                        if field == 1 {
+                            kind = match value.as_u64() {
+                                0 => EncodingKind::Direct,
+                                1 => EncodingKind::Dictionary,
+                                2 => EncodingKind::DirectV2,
+                                3 => EncodingKind::DictionaryV2,
+                                other => return Err(format!("Unknown ORC column encoding {}", other)),
+                            };
+                        }
+                    }
+                    footer.encodings.push(kind);
+                }
+                _ => {}
+            }
+        }
+        Ok(footer)
+    }
+}
@@ -0,0 +1,433 @@
+//! Stripe loading and column decoding. A stripe's streams are decompressed up
+//! front and every column is decoded into a tree of `OrcValue`s, mirroring the
+//! ORC type tree: child columns only hold entries for non-null parents.
+
+use std::collections::HashMap;
+use std::io::Read;
+use chrono::DateTime;
+use crate::parser::orc::proto::{Compression, EncodingKind, OrcType, PostScript, StreamKind, StripeFooter, StripeInfo, TypeKind};
+use crate::parser::orc::rle::{decode_booleans, decode_byte_rle, decode_ints, read_zigzag_i128, MAX_VALUES_PER_BYTE};
+use crate::stats::logical::format_scaled_decimal;
+
+/// ORC timestamps count seconds from 2015-01-01 00:00:00 UTC.
+const ORC_EPOCH_SECONDS: i64 = 1_420_070_400;
+/// Compressed chunks start with a 3-byte little-endian header.
+const CHUNK_HEADER_LEN: usize = 3;
+/// Largest expansion an LZ4 block can encode.
+const LZ4_MAX_RATIO: usize = 255;
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum OrcValue {
+    Null,
+    Boolean(bool),
+    Int(i64),
+    Float(f32),
+    Double(f64),
+    String(String),
+    Binary(Vec<u8>),
+    Decimal { unscaled: i128, scale: u32 },
+    /// Days since the Unix epoch
+    Date(i64),
+    /// Seconds since the Unix epoch plus nanoseconds
+    Timestamp { seconds: i64, nanos: u32 },
+    List(Vec<OrcValue>),
+    Map(Vec<(OrcValue, OrcValue)>),
+    Struct(Vec<OrcValue>),
+    Union(u8, Box<OrcValue>),
+}
+
+impl OrcValue {
+    /// Renders a value as the string handed to the Profiler. Dates and
+    /// timestamps use fixed-width ISO 8601 forms, decimals keep their scale,
+    /// binary is hex and nested values are serialized as JSON.
+    pub(crate) fn to_profile_string(&self) -> String {
+        match self {
+            OrcValue::Null => String::new(),
+            OrcValue::String(s) => s.clone(),
+            OrcValue::Union(_, inner) => inner.to_profile_string(),
+            OrcValue::List(_) | OrcValue::Map(_) | OrcValue::Struct(_) => self.to_json().to_string(),
+            other => other.scalar_string(),
+        }
+    }
+
+    fn scalar_string(&self) -> String {
+        match self {
+            OrcValue::Boolean(b) => b.to_string(),
+            OrcValue::Int(i) => i.to_string(),
+            OrcValue::Float(f) => f.to_string(),
+            OrcValue::Double(d) => d.to_string(),
+            OrcValue::String(s) => s.clone(),
+            OrcValue::Binary(bytes) => bytes.iter().map(|b| format!("{:02x}", b)).collect(),
+            OrcValue::Decimal { unscaled, scale } => format_scaled_decimal(&unscaled.to_string(), *scale as usize),
+            OrcValue::Date(days) => DateTime::from_timestamp(days * 86_400, 0)
+                .map(|dt| dt.format("%Y-%m-%d").to_string())
+                .unwrap_or_default(),
+            OrcValue::Timestamp { seconds, nanos } => DateTime::from_timestamp(*seconds, *nanos)
+                .map(|dt| dt.naive_utc().format("%Y-%m-%dT%H:%M:%S%.9f").to_string())
+                .unwrap_or_default(),
+            _ => String::new(),
+        }
+    }
+
+    fn to_json(&self) -> serde_json::Value {
+        use serde_json::Value;
+        match self {
+            OrcValue::Null => Value::Null,
+            OrcValue::Boolean(b) => Value::Bool(*b),
+            OrcValue::Int(i) => Value::from(*i),
+            OrcValue::Double(d) => Value::from(*d),
+            OrcValue::Float(f) => Value::from(*f as f64),
+            OrcValue::List(items) => Value::Array(items.iter().map(|v| v.to_json()).collect()),
+            OrcValue::Map(entries) => {
+                let map = entries.iter()
+                    .map(|(k, v)| (k.to_profile_string(), v.to_json()))
+                    .collect();
+                Value::Object(map)
+            }
+            OrcValue::Struct(fields) => Value::Array(fields.iter().map(|v| v.to_json()).collect()),
+            OrcValue::Union(_, inner) => inner.to_json(),
+            other => Value::String(other.scalar_string()),
+        }
+    }
+}
+
+/// Undoes ORC's chunked compression. Each chunk is either stored as-is or
+/// compressed with the file's codec, as flagged in its header.
+pub(crate) fn decompress(compression: Compression, block_size: u64, data: &[u8]) -> Result<Vec<u8>, String> {
+    if compression == Compression::None {
+        return Ok(data.to_vec());
+    }
+
+    let mut out = Vec::new();
+    let mut pos = 0;
+    while pos < data.len() {
+        let header = data.get(pos..pos + CHUNK_HEADER_LEN)
+            .ok_or_else(|| "Truncated compression chunk header".to_string())?;
+        let header = header[0] as usize | (header[1] as usize) << 8 | (header[2] as usize) << 16;
+        let original = header & 1 == 1;
+        let len = header >> 1;
+        pos += CHUNK_HEADER_LEN;
+        let chunk = data.get(pos..pos + len).ok_or_else(|| "Truncated compression chunk".to_string())?;
+        pos += len;
+
+        if original {
+            out.extend_from_slice(chunk);
+            continue;
+        }
+        // No chunk decompresses to more than the file's block size
+        let block_size = block_size as usize;
+        match compression {
+            Compression::Zlib => {
+                read_chunk(flate2::read::DeflateDecoder::new(chunk), block_size, &mut out)
+                    .map_err(|e| format!("zlib: {}", e))?;
+            }
+            Compression::Snappy => {
+                let len = snap::raw::decompress_len(chunk).map_err(|e| format!("snappy: {}", e))?;
+                if len > block_size {
+                    return Err(format!("snappy: chunk exceeds the block size of {} bytes", block_size));
+                }
+                let decoded = snap::raw::Decoder::new().decompress_vec(chunk).map_err(|e| format!("snappy: {}", e))?;
+                out.extend_from_slice(&decoded);
+            }
+            Compression::Lz4 => {
+                let decoded = decompress_lz4(chunk, block_size)?;
+                out.extend_from_slice(&decoded);
+            }
+            Compression::Zstd => {
+                let decoder = ruzstd::decoding::StreamingDecoder::new(chunk).map_err(|e| format!("zstd: {}", e))?;
+                read_chunk(decoder, block_size, &mut out).map_err(|e| format!("zstd: {}", e))?;
+            }
+            other => return Err(format!("Unsupported ORC compression: {}", other.name())),
+        }
+    }
+    Ok(out)
+}
+
+/// Appends the output of a streaming decoder to `out`, failing once it
+/// passes `block_size` bytes.
+fn read_chunk(decoder: impl Read, block_size: usize, out: &mut Vec<u8>) -> Result<(), String> {
+    let start = out.len();
+    decoder.take(block_size as u64 + 1).read_to_end(out).map_err(|e| e.to_string())?;
+    if out.len() - start > block_size {
+        return Err(format!("chunk exceeds the block size of {} bytes", block_size));
+    }
+    Ok(())
+}
+
+/// Decompresses one LZ4 chunk. Raw LZ4 blocks do not store their size, so the
+/// output starts small and doubles until it fits, up to the smaller of the
+/// file's block size and the largest expansion LZ4 can encode.
+fn decompress_lz4(chunk: &[u8], block_size: usize) -> Result<Vec<u8>, String> {
+    let limit = block_size.min(chunk.len().saturating_mul(LZ4_MAX_RATIO));
+    let mut size = limit.min(chunk.len().saturating_mul(4).max(64));
+    loop {
+        let mut decoded = vec![0; size];
+        match lz4_flex::block::decompress_into(chunk, &mut decoded) {
+            Ok(len) => {
+                decoded.truncate(len);
+                return Ok(decoded);
+            }
+            Err(lz4_flex::block::DecompressError::OutputTooSmall { .. }) if size < limit => {
+                size = size.saturating_mul(2).min(limit);
+            }
+            Err(e) => return Err(format!("lz4: {}", e)),
+        }
+    }
+}
+
+/// The decompressed data streams and column encodings of one stripe.
+pub(crate) struct StripeData<'a> {
+    types: &'a [OrcType],
+    streams: HashMap<(u32, StreamKind), Vec<u8>>,
+    encodings: Vec<EncodingKind>,
+}
+
+impl<'a> StripeData<'a> {
+    pub(crate) fn load(file: &[u8], stripe: &StripeInfo, postscript: &PostScript, types: &'a [OrcType]) -> Result<Self, String> {
+        let slice = |start: u64, len: u64| -> Result<&[u8], String> {
+            let start = start as usize;
+            let end = start.checked_add(len as usize).filter(|&end| end <= file.len())
+                .ok_or_else(|| format!("Stripe at offset {} extends past end of file", stripe.offset))?;
+            Ok(&file[start..end])
+        };
+
+        let footer_start = stripe.offset + stripe.index_length + stripe.data_length;
+        let footer_bytes = decompress(postscript.compression, postscript.block_size, slice(footer_start, stripe.footer_length)?)?;
+        let footer = StripeFooter::decode(&footer_bytes)?;
+
+        // Streams are laid out back to back from the start of the stripe,
+        // index streams first; only the data streams are kept
+        let mut streams = HashMap::new();
+        let mut offset = stripe.offset;
+        for stream in &footer.streams {
+            if stream.kind != StreamKind::Other {
+                let bytes = decompress(postscript.compression, postscript.block_size, slice(offset, stream.length)?)?;
+                streams.insert((stream.column, stream.kind), bytes);
+            }
+            offset += stream.length;
+        }
+
+        Ok(Self {
+            types,
+            streams,
+            encodings: footer.encodings,
+        })
+    }
+
+    fn stream(&self, column: u32, kind: StreamKind) -> &[u8] {
+        self.streams.get(&(column, kind)).map(|s| s.as_slice()).unwrap_or(&[])
+    }
+
+    fn encoding(&self, column: u32) -> EncodingKind {
+        self.encodings.get(column as usize).copied().unwrap_or(EncodingKind::Direct)
+    }
+
+    /// Decodes `count` entries of a column (and its children).
+    pub(crate) fn read_column(&self, column: u32, count: usize) -> Result<Vec<OrcValue>, String> {
+        let ty = self.types.get(column as usize)
+            .ok_or_else(|| format!("Stripe references unknown column {}", column))?;
+        // Booleans pack 8 to a byte, so a present stream holds the most values
+        let stream_bytes: usize = self.streams.values().map(Vec::len).sum();
+        if count > stream_bytes.saturating_mul(8 * MAX_VALUES_PER_BYTE) {
+            return Err(format!("Column {} claims {} values, more than the stripe's streams can hold", column, count));
+        }
+        let present = match self.streams.get(&(column, StreamKind::Present)) {
+            Some(bytes) => decode_booleans(bytes, count)?,
+            None => {
+                let values = self.read_values(column, ty, count)?;
+                if values.len() < count {
+                    return Err(format!("Column {} has {} values, expected {}", column, values.len(), count));
+                }
+                return Ok(values);
+            }
+        };
+        let non_null = present.iter().filter(|p| **p).count();
+        let values = self.read_values(column, ty, non_null)?;
+
+        let mut values = values.into_iter();
+        Ok(present.into_iter()
+            .map(|p| if p { values.next().unwrap_or(OrcValue::Null) } else { OrcValue::Null })
+            .collect())
+    }
+
+    fn read_values(&self, column: u32, ty: &OrcType, count: usize) -> Result<Vec<OrcValue>, String> {
+        let encoding = self.encoding(column);
+        let v2 = encoding.is_v2();
+        let data = self.stream(column, StreamKind::Data);
+
+        Ok(match ty.kind {
+            TypeKind::Boolean => decode_booleans(data, count)?.into_iter().map(OrcValue::Boolean).collect(),
+            TypeKind::Byte => decode_byte_rle(data, count)?.into_iter().map(|b| OrcValue::Int(b as i8 as i64)).collect(),
+            TypeKind::Short | TypeKind::Int | TypeKind::Long => {
+                decode_ints(data, count, true, v2)?.into_iter().map(OrcValue::Int).collect()
+            }
+            TypeKind::Float => data.chunks_exact(4).take(count)
+                .map(|b| OrcValue::Float(f32::from_le_bytes([b[0], b[1], b[2], b[3]])))
+                .collect(),
+            TypeKind::Double => data.chunks_exact(8).take(count)
+                .map(|b| OrcValue::Double(f64::from_le_bytes(b.try_into().unwrap())))
+                .collect(),
+            TypeKind::String | TypeKind::Varchar | TypeKind::Char => self.read_bytes(column, count, encoding)?
+                .into_iter()
+                .map(|b| OrcValue::String(String::from_utf8_lossy(&b).into_owned()))
+                .collect(),
+            TypeKind::Binary => self.read_bytes(column, count, encoding)?.into_iter().map(OrcValue::Binary).collect(),
+            TypeKind::Date => decode_ints(data, count, true, v2)?.into_iter().map(OrcValue::Date).collect(),
+            TypeKind::Timestamp | TypeKind::TimestampInstant => {
+                let seconds = decode_ints(data, count, true, v2)?;
+                let nanos = decode_ints(self.stream(column, StreamKind::Secondary), count, false, v2)?;
+                seconds.into_iter().zip(nanos)
+                    .map(|(secs, raw)| {
+                        let nanos = decode_nanos(raw as u64)?;
+                        let secs = ORC_EPOCH_SECONDS.checked_add(secs)
+                            .ok_or_else(|| format!("Column {} has an out of range timestamp", column))?;
+                        // Writers truncate pre-1970 seconds towards zero
+                        let secs = if secs < 0 && nanos > 999_999 { secs - 1 } else { secs };
+                        Ok(OrcValue::Timestamp { seconds: secs, nanos })
+                    })
+                    .collect::<Result<_, String>>()?
+            }
+            TypeKind::Decimal => {
+                let scales = decode_ints(self.stream(column, StreamKind::Secondary), count, true, v2)?;
+                let mut pos = 0;
+                let mut values = Vec::with_capacity(count);
+                for scale in scales {
+                    let unscaled = read_zigzag_i128(data, &mut pos)?;
+                    values.push(rescale_decimal(unscaled, scale as u32, ty.scale));
+                }
+                values
+            }
+            TypeKind::Struct => {
+                let children = ty.subtypes.iter()
+                    .map(|child| self.read_column(*child, count))
+                    .collect::<Result<Vec<_>, _>>()?;
+                let mut children: Vec<_> = children.into_iter().map(|c| c.into_iter()).collect();
+                (0..count)
+                    .map(|_| OrcValue::Struct(children.iter_mut().map(|c| c.next().unwrap_or(OrcValue::Null)).collect()))
+                    .collect()
+            }
+            TypeKind::List => {
+                let lengths = decode_ints(self.stream(column, StreamKind::Length), count, false, v2)?;
+                let child = *ty.subtypes.first().ok_or_else(|| "List type without element type".to_string())?;
+                let total = total_length(&lengths)?;
+                let mut items = self.read_column(child, total)?.into_iter();
+                lengths.into_iter()
+                    .map(|len| OrcValue::List(items.by_ref().take(len as usize).collect()))
+                    .collect()
+            }
+            TypeKind::Map => {
+                let lengths = decode_ints(self.stream(column, StreamKind::Length), count, false, v2)?;
+                if ty.subtypes.len() != 2 {
+                    return Err("Map type without key and value types".to_string());
+                }
+                let total = total_length(&lengths)?;
+                let mut keys = self.read_column(ty.subtypes[0], total)?.into_iter();
+                let mut values = self.read_column(ty.subtypes[1], total)?.into_iter();
+                lengths.into_iter()
+                    .map(|len| OrcValue::Map(keys.by_ref().zip(values.by_ref()).take(len as usize).collect()))
+                    .collect()
+            }
+            TypeKind::Union => {
+                let tags = decode_byte_rle(data, count)?;
+                let mut branches = Vec::with_capacity(ty.subtypes.len());
+                for (idx, child) in ty.subtypes.iter().enumerate() {
+                    let branch_count = tags.iter().filter(|t| **t as usize == idx).count();
+                    branches.push(self.read_column(*child, branch_count)?.into_iter());
+                }
+                tags.into_iter()
+                    .map(|tag| {
+                        let value = branches.get_mut(tag as usize).and_then(|b| b.next()).unwrap_or(OrcValue::Null);
+                        OrcValue::Union(tag, Box::new(value))
+                    })
+                    .collect()
+            }
+        })
+    }
+
+    /// Variable-length values, stored directly (DATA + LENGTH) or through a
+    /// dictionary (DICTIONARY_DATA + LENGTH, with DATA holding indices).
+    fn read_bytes(&self, column: u32, count: usize, encoding: EncodingKind) -> Result<Vec<Vec<u8>>, String> {
+        let v2 = encoding.is_v2();
+        let data = self.stream(column, StreamKind::Data);
+        let lengths_stream = self.stream(column, StreamKind::Length);
+
+        if encoding.is_dictionary() {
+            let indices = decode_ints(data, count, false, v2)?;
+            let dictionary_size = indices.iter().map(|i| (*i as usize).saturating_add(1)).max().unwrap_or(0);
+            let lengths = decode_ints(lengths_stream, dictionary_size, false, v2)?;
+            let dictionary = split_by_lengths(self.stream(column, StreamKind::DictionaryData), &lengths)?;
+            indices.into_iter()
+                .map(|i| dictionary.get(i as usize).cloned().ok_or_else(|| "Dictionary index out of range".to_string()))
+                .collect()
+        } else {
+            let lengths = decode_ints(lengths_stream, count, false, v2)?;
+            split_by_lengths(data, &lengths)
+        }
+    }
+}
+
+/// Sum of list or map lengths, which come straight from the file.
+fn total_length(lengths: &[i64]) -> Result<usize, String> {
+    lengths.iter().try_fold(0usize, |total, len| {
+        usize::try_from(*len).ok().and_then(|len| total.checked_add(len))
+            .ok_or_else(|| "Corrupt list or map lengths".to_string())
+    })
+}
+
+fn split_by_lengths(data: &[u8], lengths: &[i64]) -> Result<Vec<Vec<u8>>, String> {
+    let mut pos = 0usize;
+    lengths.iter()
+        .map(|len| {
+            let end = usize::try_from(*len).ok().and_then(|len| pos.checked_add(len));
+            let bytes = end.and_then(|end| data.get(pos..end)).ok_or_else(|| "Truncated string data".to_string())?;
+            pos += bytes.len();
+            Ok(bytes.to_vec())
+        })
+        .collect()
+}
+
+/// Nanoseconds are stored with trailing decimal zeros stripped; the low three
+/// bits hold the number of zeros removed, minus one.
+fn decode_nanos(raw: u64) -> Result<u32, String> {
+    let zeros = raw & 0x07;
+    let value = raw >> 3;
+    let nanos = if zeros == 0 { Some(value) } else { value.checked_mul(10u64.pow(zeros as u32 + 1)) };
+    nanos.filter(|&nanos| nanos < 1_000_000_000)
+        .map(|nanos| nanos as u32)
+        .ok_or_else(|| format!("Invalid timestamp nanoseconds {}", raw))
+}
+
+/// Brings a decimal to the column's declared scale so every value renders
+/// with the same number of fractional digits.
+fn rescale_decimal(unscaled: i128, scale: u32, target: u32) -> OrcValue {
+    if scale < target {
+        if let Some(factor) = 10i128.checked_pow(target - scale) {
+            if let Some(value) = unscaled.checked_mul(factor) {
+                return OrcValue::Decimal { unscaled: value, scale: target };
+            }
+        }
+    }
+    OrcValue::Decimal { unscaled, scale }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lz4_output_grows_up_to_the_block_size() {
+        let data = vec![7u8; 100_000];
+        let chunk = lz4_flex::block::compress(&data);
+        assert_eq!(decompress_lz4(&chunk, 256 * 1024), Ok(data));
+        assert!(decompress_lz4(&chunk, 50_000).is_err());
+    }
+
+    #[test]
+    fn test_decode_nanos() {
+        assert_eq!(decode_nanos(5 << 3), Ok(5));
+        // Trailing zeros: 2 means three zeros
+        assert_eq!(decode_nanos((12 << 3) | 2), Ok(12_000));
+        assert!(decode_nanos(u64::MAX).is_err());
+        assert!(decode_nanos(1_000_000_000 << 3).is_err());
+    }
+}
@@ -1,80 +1,137 @@
 use wasm_bindgen::prelude::*;
+use parquet::basic::ConvertedType;
 use parquet::file::reader::{FileReader, SerializedFileReader};
+use parquet::record::reader::RowIter;
 use bytes::Bytes;
-use crate::stats::profiler::Profiler;
+use crate::parser::source::{ProfileDriver, RecordSource, SourceProgress, SourceSchema};
+use crate::stats::config::ProfilerConfig;
+use crate::stats::profiler::ProfilerResult;
 
-#[wasm_bindgen]
-pub struct ParquetProfiler {
-    profiler: Option<Profiler>,
+const BATCH_SIZE: usize = 1000;
+
+/// `RecordSource` for Parquet files. The footer is at the end of the file,
+/// so input is buffered until `finish` and rows are read after that.
+pub struct ParquetSource {
+    buffer: Vec<u8>,
     headers: Vec<String>,
+    declared_types: Vec<Option<String>>,
+    rows: Option<RowIter<'static>>,
+    batch: Vec<Vec<String>>,
+    progress: SourceProgress,
 }
 
-#[wasm_bindgen]
-impl ParquetProfiler {
-    #[wasm_bindgen(constructor)]
+impl ParquetSource {
     pub fn new() -> Self {
         Self {
-            profiler: None,
+            buffer: Vec::new(),
             headers: Vec::new(),
+            declared_types: Vec::new(),
+            rows: None,
+            batch: Vec::with_capacity(BATCH_SIZE),
+            progress: SourceProgress::default(),
         }
     }
+}
 
-    /// Parses the entire Parquet file buffer and updates the profiler.
-    pub fn parse_and_profile(&mut self, file_bytes: &[u8]) -> Result<JsValue, JsValue> {
-        // Create Bytes object which implements ChunkReader
-        let bytes_data = Bytes::copy_from_slice(file_bytes);
-        
-        let reader = SerializedFileReader::new(bytes_data).map_err(|e| JsValue::from_str(&e.to_string()))?;
-        
-        // 1. Extract Headers (Schema)
-        if self.headers.is_empty() {
-            let schema = reader.metadata().file_metadata().schema();
-            self.headers = schema.get_fields().iter().map(|f| f.name().to_string()).collect();
-            self.profiler = Some(Profiler::new(self.headers.clone()));
-        }
+impl RecordSource for ParquetSource {
+    fn push(&mut self, chunk: &[u8]) -> Result<(), String> {
+        self.progress.bytes_received += chunk.len() as u64;
+        self.buffer.extend_from_slice(chunk);
+        Ok(())
+    }
 
-        // 2. Iterate Rows
-        let iter = reader.get_row_iter(None).map_err(|e| JsValue::from_str(&e.to_string()))?;
-        
-        let mut batch: Vec<Vec<String>> = Vec::with_capacity(1000);
-        let batch_size = 1000;
-
-        for record_result in iter {
-            let record = record_result.map_err(|e| JsValue::from_str(&e.to_string()))?;
-            let mut row_values: Vec<String> = Vec::with_capacity(self.headers.len());
-
-            for i in 0..self.headers.len() {
-                // Simplified string conversion for Profiler
-                let val_str = match record.get_column_iter().nth(i) {
-                    Some((_name, field)) => field.to_string(),
-                    None => "".to_string(),
-                };
-                row_values.push(val_str);
+    fn finish(&mut self) -> Result<(), String> {
+        self.progress.finished = true;
+        let bytes_data = Bytes::from(std::mem::take(&mut self.buffer));
+        let reader = SerializedFileReader::new(bytes_data).map_err(|e| e.to_string())?;
+
+        let schema = reader.metadata().file_metadata().schema();
+        self.headers = schema.get_fields().iter().map(|f| f.name().to_string()).collect();
+        self.declared_types = schema.get_fields().iter().map(|f| {
+            if !f.is_primitive() {
+                return Some("group".to_string());
             }
-            
-            batch.push(row_values);
-
-            if batch.len() >= batch_size {
-                if let Some(ref mut p) = self.profiler {
-                    p.update_batch(&batch);
-                }
-                batch.clear();
+            match f.get_basic_info().converted_type() {
+                ConvertedType::NONE => Some(f.get_physical_type().to_string()),
+                converted => Some(converted.to_string()),
             }
+        }).collect();
+        self.rows = Some(RowIter::from_file_into(Box::new(reader)));
+        Ok(())
+    }
+
+    fn schema(&self) -> Option<SourceSchema> {
+        self.rows.as_ref().map(|_| SourceSchema {
+            declared_types: self.declared_types.clone(),
+            ..SourceSchema::untyped(self.headers.clone())
+        })
+    }
+
+    fn next_batch(&mut self) -> Result<Option<&[Vec<String>]>, String> {
+        let rows = match self.rows.as_mut() {
+            Some(rows) => rows,
+            None => return Ok(None),
+        };
+
+        self.batch.clear();
+        for record_result in rows.by_ref().take(BATCH_SIZE) {
+            let record = record_result.map_err(|e| e.to_string())?;
+            // Simplified string conversion for Profiler
+            let mut row_values: Vec<String> = record.get_column_iter()
+                .map(|(_name, field)| field.to_string())
+                .collect();
+            row_values.resize(self.headers.len(), String::new());
+            self.batch.push(row_values);
         }
 
-        // Final batch
-        if !batch.is_empty() {
-            if let Some(ref mut p) = self.profiler {
-                p.update_batch(&batch);
-            }
+        if self.batch.is_empty() {
+            return Ok(None);
         }
+        self.progress.record_batch(self.batch.len());
+        Ok(Some(&self.batch))
+    }
 
-        // 3. Finalize
-        if let Some(ref mut p) = self.profiler {
-            let stats = p.finalize();
-            serde_wasm_bindgen::to_value(&stats).map_err(|e| JsValue::from_str(&e.to_string()))
-        } else {
-            Err(JsValue::from_str("Profiler not initialized"))
+    fn progress(&self) -> SourceProgress {
+        self.progress.clone()
+    }
+
+    fn streaming(&self) -> bool {
+        false
+    }
+}
+
+#[wasm_bindgen]
+pub struct ParquetProfiler {
+    driver: ProfileDriver<ParquetSource>,
+}
+
+#[wasm_bindgen]
+impl ParquetProfiler {
+    /// `config` is a `ProfilerConfig`; omitted fields keep their defaults.
+    #[wasm_bindgen(constructor)]
+    pub fn new(config: Option<JsValue>) -> Result<ParquetProfiler, JsValue> {
+        let config = ProfilerConfig::from_js(config).map_err(|e| JsValue::from_str(&e))?;
+        Ok(Self::with_config(config))
+    }
+
+    /// Parses the entire Parquet file buffer and updates the profiler.
+    pub fn parse_and_profile(&mut self, file_bytes: &[u8]) -> Result<JsValue, JsValue> {
+        match self.parse_and_profile_internal(file_bytes) {
+            Ok(stats) => serde_wasm_bindgen::to_value(&stats).map_err(|e| JsValue::from_str(&e.to_string())),
+            Err(e) => Err(JsValue::from_str(&e)),
+        }
+    }
+}
+
+impl ParquetProfiler {
+    pub(crate) fn with_config(config: ProfilerConfig) -> Self {
+        Self {
+            driver: ProfileDriver::new(ParquetSource::new(), config),
         }
     }
+
+    pub(crate) fn parse_and_profile_internal(&mut self, file_bytes: &[u8]) -> Result<ProfilerResult, String> {
+        self.driver.push(file_bytes)?;
+        self.driver.finish()
+    }
 }
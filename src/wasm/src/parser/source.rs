@@ -0,0 +1,533 @@
+//! The `RecordSource` trait implemented by every format parser, and the
+//! `ProfileDriver` that feeds any source into a `Profiler`.
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+use crate::stats::config::ProfilerConfig;
+use crate::stats::partial::PartialProfile;
+use crate::stats::profiler::{Profiler, ProfilerResult};
+use crate::stats::types::LogicalType;
+
+/// Column layout announced by a source.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SourceSchema {
+    pub headers: Vec<String>,
+    /// Logical types declared by the format's schema, by column index
+    pub logical_types: Vec<Option<LogicalType>>,
+    /// Column types as the format's schema names them (e.g. "Int64"), for
+    /// reporting; empty when the format declares none
+    pub declared_types: Vec<Option<String>>,
+}
+
+impl SourceSchema {
+    /// A schema of plain columns, as read from a header row or record keys.
+    pub fn untyped(headers: Vec<String>) -> Self {
+        let logical_types = vec![None; headers.len()];
+        Self { headers, logical_types, declared_types: Vec::new() }
+    }
+}
+
+/// Progress counters shared by all sources.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, TS)]
+#[ts(export)]
+pub struct SourceProgress {
+    /// Bytes handed to the source so far
+    pub bytes_received: u64,
+    pub records_read: u64,
+    pub batches_read: u64,
+    /// Set once `finish` was called
+    pub finished: bool,
+}
+
+impl SourceProgress {
+    pub(crate) fn record_batch(&mut self, rows: usize) {
+        self.batches_read += 1;
+        self.records_read += rows as u64;
+    }
+}
+
+/// A parser that turns input bytes into batches of string rows.
+///
+/// Input arrives through `push` and ends with `finish`; after either call the
+/// driver drains `next_batch` until it returns `None`. Streaming formats emit
+/// rows as soon as they are complete, whole-file formats buffer their input
+/// and emit everything after `finish`. Errors are fatal to the read.
+pub trait RecordSource {
+    /// Hands the next chunk of input to the source.
+    fn push(&mut self, chunk: &[u8]) -> Result<(), String>;
+
+    /// Signals the end of input so buffered records can be released.
+    fn finish(&mut self) -> Result<(), String>;
+
+    /// The columns seen so far, once known; None until then. Later calls may
+    /// return more columns (e.g. keys first seen in a later JSON record),
+    /// never fewer.
+    fn schema(&self) -> Option<SourceSchema>;
+
+    /// The next batch of rows, aligned with `schema().headers`.
+    fn next_batch(&mut self) -> Result<Option<&[Vec<String>]>, String>;
+
+    fn progress(&self) -> SourceProgress;
+
+    /// False for formats that need their complete input, such as Parquet
+    /// with its footer at the end; `max_bytes` does not cut their input.
+    fn streaming(&self) -> bool {
+        true
+    }
+
+    /// Copies format metadata (schemas, writer statistics) into the profiler
+    /// before it is finalized.
+    fn annotate(&self, _profiler: &mut Profiler) {}
+}
+
+impl<S: RecordSource + ?Sized> RecordSource for Box<S> {
+    fn push(&mut self, chunk: &[u8]) -> Result<(), String> {
+        (**self).push(chunk)
+    }
+
+    fn finish(&mut self) -> Result<(), String> {
+        (**self).finish()
+    }
+
+    fn schema(&self) -> Option<SourceSchema> {
+        (**self).schema()
+    }
+
+    fn next_batch(&mut self) -> Result<Option<&[Vec<String>]>, String> {
+        (**self).next_batch()
+    }
+
+    fn progress(&self) -> SourceProgress {
+        (**self).progress()
+    }
+
+    fn streaming(&self) -> bool {
+        (**self).streaming()
+    }
+
+    fn annotate(&self, profiler: &mut Profiler) {
+        (**self).annotate(profiler)
+    }
+}
+
+/// A streaming text parser that returns the rows each chunk completes.
+pub trait ChunkParser {
+    /// What a parse call returns; `rows` reads its completed rows.
+    type Output;
+
+    fn parse_chunk(&mut self, chunk: &[u8]) -> Result<Self::Output, String>;
+
+    /// Parses what is left buffered at the end of input.
+    fn flush(&mut self) -> Result<Self::Output, String>;
+
+    /// The columns seen so far.
+    fn headers(&self) -> &[String];
+
+    fn rows(output: &Self::Output) -> &[Vec<String>];
+}
+
+/// `RecordSource` over a `ChunkParser`. The last parse result is kept until
+/// the wasm wrappers take it to return for the chunk.
+#[derive(Serialize, Deserialize)]
+pub struct ChunkedSource<P: ChunkParser> {
+    parser: P,
+    // Checkpoints are taken between chunks, when the result was consumed
+    #[serde(skip)]
+    last: Option<P::Output>,
+    #[serde(skip)]
+    pending: bool,
+    progress: SourceProgress,
+}
+
+impl<P: ChunkParser> ChunkedSource<P> {
+    pub fn new(parser: P) -> Self {
+        Self {
+            parser,
+            last: None,
+            pending: false,
+            progress: SourceProgress::default(),
+        }
+    }
+
+    pub fn parser(&self) -> &P {
+        &self.parser
+    }
+
+    pub fn parser_mut(&mut self) -> &mut P {
+        &mut self.parser
+    }
+
+    /// The result of the last parse, if not taken yet. None when the driver
+    /// stopped and ignored the chunk.
+    pub fn take_last_result(&mut self) -> Option<P::Output> {
+        self.pending = false;
+        self.last.take()
+    }
+
+    fn accept(&mut self, result: P::Output) {
+        self.pending = !P::rows(&result).is_empty();
+        self.last = Some(result);
+    }
+}
+
+impl<P: ChunkParser> RecordSource for ChunkedSource<P> {
+    fn push(&mut self, chunk: &[u8]) -> Result<(), String> {
+        self.progress.bytes_received += chunk.len() as u64;
+        let result = self.parser.parse_chunk(chunk)?;
+        self.accept(result);
+        Ok(())
+    }
+
+    fn finish(&mut self) -> Result<(), String> {
+        let result = self.parser.flush()?;
+        self.accept(result);
+        self.progress.finished = true;
+        Ok(())
+    }
+
+    fn schema(&self) -> Option<SourceSchema> {
+        let headers = self.parser.headers();
+        (!headers.is_empty()).then(|| SourceSchema::untyped(headers.to_vec()))
+    }
+
+    fn next_batch(&mut self) -> Result<Option<&[Vec<String>]>, String> {
+        match self.last {
+            Some(ref result) if std::mem::take(&mut self.pending) => {
+                let rows = P::rows(result);
+                self.progress.record_batch(rows.len());
+                Ok(Some(rows))
+            }
+            _ => Ok(None),
+        }
+    }
+
+    fn progress(&self) -> SourceProgress {
+        self.progress.clone()
+    }
+}
+
+/// Leading bytes of a checkpoint, followed by a format version byte and the
+/// LZ4-compressed state.
+const CHECKPOINT_MAGIC: &[u8; 4] = b"DCCP";
+const CHECKPOINT_VERSION: u8 = 2;
+/// Largest expansion an LZ4 block can encode, used to reject forged sizes
+/// before allocating.
+const LZ4_MAX_RATIO: usize = 255;
+
+/// Owns the `Profiler` for a source: creates it from the first schema, adds
+/// columns the schema gains later and profiles every batch.
+pub struct ProfileDriver<S: RecordSource> {
+    source: S,
+    config: ProfilerConfig,
+    profiler: Option<Profiler>,
+    input_size: Option<u64>,
+    // Set once `max_bytes` was pushed, also before a profiler exists
+    byte_limit_reached: bool,
+}
+
+impl<S: RecordSource> ProfileDriver<S> {
+    pub fn new(source: S, config: ProfilerConfig) -> Self {
+        Self {
+            source,
+            config,
+            profiler: None,
+            input_size: None,
+            byte_limit_reached: false,
+        }
+    }
+
+    /// Quantile `q` of a numeric column; also valid after `finish`.
+    pub fn quantile(&self, column: &str, q: f64) -> Option<f64> {
+        self.profiler.as_ref()?.quantile(column, q)
+    }
+
+    /// Size of the whole input, used to extrapolate from a partial read.
+    /// Not kept in checkpoints.
+    pub fn set_input_size(&mut self, bytes: u64) {
+        self.input_size = Some(bytes);
+    }
+
+    /// True once a sampling limit (`max_rows`, `max_bytes`, `head`) was
+    /// reached; the caller can stop reading and call `finish`.
+    pub fn is_stopped(&self) -> bool {
+        self.byte_limit_reached || self.profiler.as_ref().is_some_and(Profiler::is_stopped)
+    }
+
+    pub fn source(&self) -> &S {
+        &self.source
+    }
+
+    pub fn source_mut(&mut self) -> &mut S {
+        &mut self.source
+    }
+
+    pub fn progress(&self) -> SourceProgress {
+        self.source.progress()
+    }
+
+    /// Feeds a chunk and profiles the rows it completes. Chunks pushed after
+    /// a sampling limit was reached are ignored; the chunk that crosses
+    /// `max_bytes` is cut to the bytes left in the budget.
+    pub fn push(&mut self, chunk: &[u8]) -> Result<(), String> {
+        if self.is_stopped() {
+            return Ok(());
+        }
+        let budget = self.config.max_bytes.filter(|_| self.source.streaming())
+            .map(|max_bytes| max_bytes.saturating_sub(self.source.progress().bytes_received));
+        // Input that exactly fills the budget is not cut; the limit is only
+        // reached once a chunk has bytes beyond it
+        let cut = budget.is_some_and(|budget| budget < chunk.len() as u64 || budget == 0);
+        let chunk = match budget {
+            Some(budget) if cut => &chunk[..budget as usize],
+            _ => chunk,
+        };
+        self.source.push(chunk)?;
+        self.drain()?;
+        if cut {
+            self.byte_limit_reached = true;
+            if let Some(ref mut profiler) = self.profiler {
+                profiler.stop_at_byte_limit();
+            }
+        }
+        Ok(())
+    }
+
+    /// Ends the input and returns the profile.
+    pub fn finish(&mut self) -> Result<ProfilerResult, String> {
+        self.end_input()?;
+        let progress = self.source.progress();
+        match self.profiler {
+            Some(ref mut profiler) => {
+                self.source.annotate(profiler);
+                profiler.bytes_read = Some(progress.bytes_received);
+                profiler.rows_parsed = Some(progress.records_read);
+                profiler.input_size = self.input_size;
+                Ok(profiler.finalize())
+            }
+            None => Err("No data was processed".to_string()),
+        }
+    }
+
+    /// Ends the input and returns the unfinalized state, for merging with
+    /// the partial profiles of other workers.
+    pub fn finish_partial(&mut self) -> Result<PartialProfile, String> {
+        self.end_input()?;
+        match self.profiler {
+            Some(ref profiler) => Ok(profiler.to_partial()),
+            None => Err("No data was processed".to_string()),
+        }
+    }
+
+    fn end_input(&mut self) -> Result<(), String> {
+        // After an early stop the input is cut mid-stream: the source's
+        // trailing partial record would be malformed, so it is not flushed
+        if !self.is_stopped() {
+            self.source.finish()?;
+        }
+        self.drain()
+    }
+
+    fn drain(&mut self) -> Result<(), String> {
+        loop {
+            self.sync_schema();
+            match self.source.next_batch()? {
+                Some(rows) => {
+                    if let Some(ref mut profiler) = self.profiler {
+                        profiler.update_batch(rows);
+                    }
+                }
+                None => return Ok(()),
+            }
+        }
+    }
+
+    fn sync_schema(&mut self) {
+        let schema = match self.source.schema() {
+            Some(schema) => schema,
+            None => return,
+        };
+        let config = &self.config;
+        let profiler = self.profiler.get_or_insert_with(|| Profiler::with_config(Vec::new(), config.clone()));
+        for idx in profiler.headers().len()..schema.headers.len() {
+            profiler.add_column(schema.headers[idx].clone());
+            if let Some(Some(logical_type)) = schema.logical_types.get(idx) {
+                profiler.set_logical_type(idx, logical_type.clone());
+            }
+        }
+    }
+}
+
+impl<S: RecordSource + Serialize + DeserializeOwned> ProfileDriver<S> {
+    /// Serializes the source (including any partial record it buffers) and
+    /// the profiler state. Taken between chunks; to resume, restore it with
+    /// `resume` and push the input from `progress().bytes_received` on.
+    ///
+    /// Only streaming sources are serializable: CSV, JSON and XML through
+    /// `ChunkedSource`, and Avro containers. Parquet, ORC, Arrow, Excel and
+    /// SQLite sources read nothing before `finish`, so there is no progress
+    /// to save; Avro message streams and datasets are not checkpointed.
+    pub fn checkpoint(&self) -> Result<Vec<u8>, String> {
+        let partial = self.profiler.as_ref().map(Profiler::to_partial);
+        let state = rmp_serde::to_vec(&(&self.source, &self.config, partial))
+            .map_err(|e| format!("Failed to write checkpoint: {}", e))?;
+        let mut bytes = CHECKPOINT_MAGIC.to_vec();
+        bytes.push(CHECKPOINT_VERSION);
+        bytes.extend(lz4_flex::block::compress_prepend_size(&state));
+        Ok(bytes)
+    }
+
+    pub fn resume(checkpoint: &[u8]) -> Result<Self, String> {
+        let body = checkpoint.strip_prefix(CHECKPOINT_MAGIC.as_slice())
+            .ok_or_else(|| "Not a profiler checkpoint".to_string())?;
+        match body.split_first() {
+            Some((&CHECKPOINT_VERSION, compressed)) => {
+                let (size, block) = lz4_flex::block::uncompressed_size(compressed)
+                    .map_err(|e| format!("Corrupt checkpoint: {}", e))?;
+                if size > block.len().saturating_mul(LZ4_MAX_RATIO) {
+                    return Err("Corrupt checkpoint: invalid state size".to_string());
+                }
+                let state = lz4_flex::block::decompress(block, size)
+                    .map_err(|e| format!("Corrupt checkpoint: {}", e))?;
+                let (source, config, partial): (S, ProfilerConfig, Option<PartialProfile>) = rmp_serde::from_slice(&state)
+                    .map_err(|e| format!("Corrupt checkpoint: {}", e))?;
+                let profiler = partial.map(Profiler::from_partial).transpose()?;
+                // A profiler cut at the limit restores its own stop reason;
+                // without one, a spent budget can only mean the limit was hit
+                let byte_limit_reached = profiler.is_none()
+                    && source.streaming()
+                    && config.max_bytes.is_some_and(|max_bytes| source.progress().bytes_received >= max_bytes);
+                Ok(Self { source, config, profiler, input_size: None, byte_limit_reached })
+            }
+            Some((version, _)) => Err(format!("Unsupported checkpoint version {}", version)),
+            None => Err("Truncated checkpoint".to_string()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Emits one batch per line of comma-separated values; the first line
+    /// is the header.
+    struct LineSource {
+        headers: Vec<String>,
+        ready: Vec<Vec<Vec<String>>>,
+        current: Vec<Vec<String>>,
+        progress: SourceProgress,
+    }
+
+    impl RecordSource for LineSource {
+        fn push(&mut self, chunk: &[u8]) -> Result<(), String> {
+            self.progress.bytes_received += chunk.len() as u64;
+            let text = String::from_utf8(chunk.to_vec()).map_err(|e| e.to_string())?;
+            for line in text.lines() {
+                let fields: Vec<String> = line.split(',').map(|s| s.to_string()).collect();
+                if self.headers.is_empty() {
+                    self.headers = fields;
+                } else if fields[0] == "+" {
+                    self.headers.push(fields[1].clone());
+                } else {
+                    self.ready.push(vec![fields]);
+                }
+            }
+            Ok(())
+        }
+
+        fn finish(&mut self) -> Result<(), String> {
+            self.progress.finished = true;
+            Ok(())
+        }
+
+        fn schema(&self) -> Option<SourceSchema> {
+            (!self.headers.is_empty()).then(|| SourceSchema::untyped(self.headers.clone()))
+        }
+
+        fn next_batch(&mut self) -> Result<Option<&[Vec<String>]>, String> {
+            if self.ready.is_empty() {
+                return Ok(None);
+            }
+            self.current = self.ready.remove(0);
+            self.progress.record_batch(self.current.len());
+            Ok(Some(&self.current))
+        }
+
+        fn progress(&self) -> SourceProgress {
+            self.progress.clone()
+        }
+    }
+
+    fn line_source() -> LineSource {
+        LineSource { headers: Vec::new(), ready: Vec::new(), current: Vec::new(), progress: SourceProgress::default() }
+    }
+
+    #[test]
+    fn test_driver_profiles_batches_and_grows_schema() {
+        let mut driver = ProfileDriver::new(line_source(), ProfilerConfig::default());
+        for line in ["id,name", "1,a", "2,b", "+,score", "3,c,9.5"] {
+            driver.push(line.as_bytes()).unwrap();
+        }
+        let result = driver.finish().unwrap();
+
+        assert_eq!(result.total_rows, 3);
+        let names: Vec<&str> = result.column_profiles.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, vec!["id", "name", "score"]);
+        // Rows profiled before the column appeared count as missing
+        assert_eq!(result.column_profiles[2].base_stats.missing, 2);
+        assert_eq!(driver.progress().records_read, 3);
+        assert!(driver.progress().finished);
+    }
+
+    #[test]
+    fn test_driver_stops_at_limits() {
+        let lines = ["id,name", "1,a", "2,b", "3,c", "4,d"];
+        let config = ProfilerConfig { max_rows: Some(2), ..ProfilerConfig::default() };
+        let mut driver = ProfileDriver::new(line_source(), config);
+        driver.set_input_size(30);
+        for line in lines {
+            driver.push(line.as_bytes()).unwrap();
+        }
+        assert!(driver.is_stopped());
+        let result = driver.finish().unwrap();
+        assert_eq!(result.total_rows, 2);
+        let sampling = result.sampling.unwrap();
+        assert_eq!((sampling.rows_read, sampling.bytes_read), (2, Some(13)));
+        assert_eq!(sampling.estimated_total_rows, Some(5));
+
+        let config = ProfilerConfig { max_bytes: Some(10), ..ProfilerConfig::default() };
+        let mut driver = ProfileDriver::new(line_source(), config);
+        for line in lines {
+            driver.push(line.as_bytes()).unwrap();
+        }
+        let result = driver.finish().unwrap();
+        assert_eq!(result.total_rows, 1);
+        assert_eq!(driver.progress().bytes_received, 10);
+        assert_eq!(result.sampling.unwrap().stop_reason, Some(crate::stats::sampling::StopReason::ByteLimit));
+    }
+
+    #[test]
+    fn test_row_limit_inside_a_chunk_extrapolates_from_parsed_rows() {
+        // 1000 rows of 7 bytes; the first chunk holds the header and 100 rows
+        let mut data = "id,name\n".to_string();
+        for i in 0..1000 {
+            data.push_str(&format!("{:04},a\n", i));
+        }
+        let config = ProfilerConfig { max_rows: Some(10), ..ProfilerConfig::default() };
+        let mut driver = ProfileDriver::new(line_source(), config);
+        driver.set_input_size(data.len() as u64);
+        driver.push(&data.as_bytes()[..708]).unwrap();
+        assert!(driver.is_stopped());
+        driver.push(&data.as_bytes()[708..]).unwrap();
+
+        let sampling = driver.finish().unwrap().sampling.unwrap();
+        assert_eq!((sampling.rows_read, sampling.rows_profiled, sampling.bytes_read), (10, 10, Some(708)));
+        assert_eq!(sampling.estimated_total_rows, Some(990));
+    }
+
+    #[test]
+    fn test_driver_without_rows_or_schema_fails() {
+        let mut driver = ProfileDriver::new(line_source(), ProfilerConfig::default());
+        assert!(driver.finish().is_err());
+    }
+}
@@ -0,0 +1,323 @@
+//! Reader for the SQLite database file format: the header, table and index
+//! b-tree pages, overflow chains and the record format.
+//! See https://www.sqlite.org/fileformat2.html
+
+const HEADER_MAGIC: &[u8; 16] = b"SQLite format 3\0";
+const HEADER_SIZE: usize = 100;
+
+const INTERIOR_INDEX: u8 = 0x02;
+const INTERIOR_TABLE: u8 = 0x05;
+const LEAF_INDEX: u8 = 0x0a;
+const LEAF_TABLE: u8 = 0x0d;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TextEncoding {
+    Utf8,
+    Utf16Le,
+    Utf16Be,
+}
+
+/// A value as stored in a record, tagged with its storage class.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SqlValue {
+    Null,
+    Integer(i64),
+    Real(f64),
+    Text(String),
+    Blob(Vec<u8>),
+}
+
+impl SqlValue {
+    /// Renders the value the way the profiler expects: NULL is missing,
+    /// blobs are hex encoded.
+    pub fn to_profile_string(&self) -> String {
+        match self {
+            SqlValue::Null => String::new(),
+            SqlValue::Integer(i) => i.to_string(),
+            SqlValue::Real(f) => f.to_string(),
+            SqlValue::Text(s) => s.clone(),
+            SqlValue::Blob(bytes) => bytes.iter().map(|b| format!("{:02x}", b)).collect(),
+        }
+    }
+}
+
+/// Callback receiving each b-tree cell with the type of the page holding it.
+type CellVisitor<'v> = dyn FnMut(&[u8], u8) -> Result<(), String> + 'v;
+
+/// A database image held in memory.
+pub struct Database<'a> {
+    data: &'a [u8],
+    page_size: usize,
+    usable_size: usize,
+    page_count: u32,
+    pub encoding: TextEncoding,
+}
+
+impl<'a> Database<'a> {
+    pub fn open(data: &'a [u8]) -> Result<Self, String> {
+        if data.len() < HEADER_SIZE || &data[..16] != HEADER_MAGIC {
+            return Err("Not a SQLite database (missing 'SQLite format 3' header)".to_string());
+        }
+        let page_size = match u16::from_be_bytes([data[16], data[17]]) {
+            1 => 65536,
+            n if n >= 512 && n.is_power_of_two() => n as usize,
+            n => return Err(format!("Invalid SQLite page size {}", n)),
+        };
+        let reserved = data[20] as usize;
+        let encoding = match u32::from_be_bytes([data[56], data[57], data[58], data[59]]) {
+            0 | 1 => TextEncoding::Utf8,
+            2 => TextEncoding::Utf16Le,
+            3 => TextEncoding::Utf16Be,
+            n => return Err(format!("Unknown SQLite text encoding {}", n)),
+        };
+        Ok(Self {
+            data,
+            page_size,
+            usable_size: page_size - reserved,
+            page_count: (data.len() / page_size) as u32,
+            encoding,
+        })
+    }
+
+    fn page(&self, number: u32) -> Result<&'a [u8], String> {
+        if number == 0 || number > self.page_count {
+            return Err(format!("Page {} is outside the database ({} pages)", number, self.page_count));
+        }
+        let start = (number as usize - 1) * self.page_size;
+        Ok(&self.data[start..start + self.page_size])
+    }
+
+    /// Visits every row of a rowid table in rowid order.
+    pub fn scan_table<F>(&self, root: u32, mut visit: F) -> Result<(), String>
+    where
+        F: FnMut(i64, Vec<SqlValue>) -> Result<(), String>,
+    {
+        self.walk(root, &mut |cell, kind| {
+            if kind != LEAF_TABLE {
+                return Ok(());
+            }
+            let (payload_size, n) = read_varint(cell)?;
+            let (rowid, m) = read_varint(&cell[n..])?;
+            let payload = self.payload(&cell[n + m..], payload_size as usize, false)?;
+            visit(rowid as i64, self.decode_record(&payload)?)
+        })
+    }
+
+    /// Visits every entry of an index b-tree in key order. WITHOUT ROWID
+    /// tables are stored this way, with entries held in interior pages too.
+    pub fn scan_index<F>(&self, root: u32, mut visit: F) -> Result<(), String>
+    where
+        F: FnMut(Vec<SqlValue>) -> Result<(), String>,
+    {
+        self.walk(root, &mut |cell, kind| {
+            let cell = if kind == INTERIOR_INDEX { cell.get(4..).ok_or("Truncated SQLite cell")? } else { cell };
+            let (payload_size, n) = read_varint(cell)?;
+            let payload = self.payload(&cell[n..], payload_size as usize, true)?;
+            visit(self.decode_record(&payload)?)
+        })
+    }
+
+    /// In-order traversal calling `visit` with each cell and its page type.
+    /// Interior index cells are visited between their left subtree and the
+    /// next one, so index entries come out sorted.
+    fn walk(&self, root: u32, visit: &mut CellVisitor) -> Result<(), String> {
+        let mut visited = 0u32;
+        self.walk_page(root, visit, &mut visited)
+    }
+
+    fn walk_page(
+        &self,
+        number: u32,
+        visit: &mut CellVisitor,
+        visited: &mut u32,
+    ) -> Result<(), String> {
+        // A well-formed b-tree never visits more pages than the file has
+        *visited += 1;
+        if *visited > self.page_count {
+            return Err("Corrupt SQLite b-tree: page cycle detected".to_string());
+        }
+
+        let page = self.page(number)?;
+        let header = if number == 1 { HEADER_SIZE } else { 0 };
+        let kind = *page.get(header).ok_or_else(|| truncated_page(number))?;
+        let interior = match kind {
+            INTERIOR_INDEX | INTERIOR_TABLE => true,
+            LEAF_INDEX | LEAF_TABLE => false,
+            other => return Err(format!("Page {} has unknown b-tree page type {:#04x}", number, other)),
+        };
+        let cell_count = read_u16(page, header + 3).ok_or_else(|| truncated_page(number))? as usize;
+        let pointers = header + if interior { 12 } else { 8 };
+
+        for i in 0..cell_count {
+            let at = pointers + i * 2;
+            let offset = read_u16(page, at).ok_or_else(|| truncated_page(number))? as usize;
+            let cell = page.get(offset..).filter(|cell| !cell.is_empty())
+                .ok_or_else(|| format!("Page {} has a cell pointer past the page end", number))?;
+            if interior {
+                let child = read_u32(cell, 0).ok_or_else(|| truncated_page(number))?;
+                self.walk_page(child, visit, visited)?;
+                if kind == INTERIOR_INDEX {
+                    visit(cell, kind)?;
+                }
+            } else {
+                visit(cell, kind)?;
+            }
+        }
+
+        if interior {
+            let right = read_u32(page, header + 8).ok_or_else(|| truncated_page(number))?;
+            self.walk_page(right, visit, visited)?;
+        }
+        Ok(())
+    }
+
+    /// Collects a cell payload, following its overflow chain when the payload
+    /// does not fit on the b-tree page.
+    fn payload(&self, local: &[u8], size: usize, index: bool) -> Result<Vec<u8>, String> {
+        let usable = self.usable_size;
+        let max_local = if index { (usable - 12) * 64 / 255 - 23 } else { usable - 35 };
+        if size <= max_local {
+            return local.get(..size).map(|p| p.to_vec()).ok_or_else(|| "Truncated SQLite cell".to_string());
+        }
+
+        // Checked before `size` sizes an allocation: it is a varint from the file
+        if size > self.page_count as usize * usable {
+            return Err("Corrupt SQLite cell: payload larger than the database".to_string());
+        }
+        let min_local = (usable - 12) * 32 / 255 - 23;
+        let mut on_page = min_local + (size - min_local) % (usable - 4);
+        if on_page > max_local {
+            on_page = min_local;
+        }
+        let mut payload = Vec::with_capacity(size);
+        payload.extend_from_slice(local.get(..on_page).ok_or("Truncated SQLite cell")?);
+        let mut next = read_u32(local, on_page).ok_or("Truncated SQLite cell")?;
+
+        let mut hops = 0;
+        while payload.len() < size {
+            hops += 1;
+            if next == 0 || hops > self.page_count {
+                return Err("Corrupt SQLite overflow chain".to_string());
+            }
+            let page = self.page(next)?;
+            next = read_u32(page, 0).ok_or_else(|| truncated_page(next))?;
+            let take = (size - payload.len()).min(usable - 4);
+            payload.extend_from_slice(page.get(4..4 + take).ok_or("Truncated SQLite overflow page")?);
+        }
+        Ok(payload)
+    }
+
+    fn decode_record(&self, payload: &[u8]) -> Result<Vec<SqlValue>, String> {
+        let (header_size, mut pos) = read_varint(payload)?;
+        let header_size = header_size as usize;
+        if header_size > payload.len() {
+            return Err("Corrupt SQLite record header".to_string());
+        }
+
+        let mut body = header_size;
+        let mut values = Vec::new();
+        while pos < header_size {
+            let (serial_type, n) = read_varint(&payload[pos..])?;
+            pos += n;
+            let len = serial_type_size(serial_type);
+            let bytes = payload.get(body..body + len).ok_or("Corrupt SQLite record body")?;
+            body += len;
+            values.push(match serial_type {
+                0 => SqlValue::Null,
+                1..=6 => SqlValue::Integer(read_signed(bytes)),
+                7 => SqlValue::Real(f64::from_bits(read_signed(bytes) as u64)),
+                8 => SqlValue::Integer(0),
+                9 => SqlValue::Integer(1),
+                n if n >= 12 && n % 2 == 0 => SqlValue::Blob(bytes.to_vec()),
+                n if n >= 13 => SqlValue::Text(self.decode_text(bytes)),
+                n => return Err(format!("Reserved SQLite serial type {}", n)),
+            });
+        }
+        Ok(values)
+    }
+
+    pub fn decode_text(&self, bytes: &[u8]) -> String {
+        match self.encoding {
+            TextEncoding::Utf8 => String::from_utf8_lossy(bytes).into_owned(),
+            TextEncoding::Utf16Le => String::from_utf16_lossy(
+                &bytes.chunks_exact(2).map(|c| u16::from_le_bytes([c[0], c[1]])).collect::<Vec<_>>(),
+            ),
+            TextEncoding::Utf16Be => String::from_utf16_lossy(
+                &bytes.chunks_exact(2).map(|c| u16::from_be_bytes([c[0], c[1]])).collect::<Vec<_>>(),
+            ),
+        }
+    }
+}
+
+fn truncated_page(number: u32) -> String {
+    format!("Page {} is truncated or has a corrupt header", number)
+}
+
+/// Big-endian integers at `at`; None when they run past `bytes`.
+fn read_u16(bytes: &[u8], at: usize) -> Option<u16> {
+    bytes.get(at..at.checked_add(2)?).map(|b| u16::from_be_bytes([b[0], b[1]]))
+}
+
+fn read_u32(bytes: &[u8], at: usize) -> Option<u32> {
+    bytes.get(at..at.checked_add(4)?).map(|b| u32::from_be_bytes([b[0], b[1], b[2], b[3]]))
+}
+
+fn serial_type_size(serial_type: u64) -> usize {
+    match serial_type {
+        0 | 8 | 9 | 10 | 11 => 0,
+        1 => 1,
+        2 => 2,
+        3 => 3,
+        4 => 4,
+        5 => 6,
+        6 | 7 => 8,
+        n => ((n - 12) / 2) as usize,
+    }
+}
+
+/// Big-endian two's complement integer of 1 to 8 bytes.
+fn read_signed(bytes: &[u8]) -> i64 {
+    let mut value: i64 = if bytes.first().is_some_and(|b| b & 0x80 != 0) { -1 } else { 0 };
+    for &b in bytes {
+        value = (value << 8) | b as i64;
+    }
+    value
+}
+
+/// SQLite varint: big-endian groups of 7 bits, the ninth byte contributing
+/// all 8 bits. Returns the value and the number of bytes read.
+pub fn read_varint(bytes: &[u8]) -> Result<(u64, usize), String> {
+    let mut value = 0u64;
+    for i in 0..9 {
+        let b = *bytes.get(i).ok_or("Truncated SQLite varint")?;
+        if i == 8 {
+            return Ok(((value << 8) | b as u64, 9));
+        }
+        value = (value << 7) | (b & 0x7f) as u64;
+        if b & 0x80 == 0 {
+            return Ok((value, i + 1));
+        }
+    }
+    unreachable!()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_varint() {
+        assert_eq!(read_varint(&[0x05]).unwrap(), (5, 1));
+        assert_eq!(read_varint(&[0x81, 0x00]).unwrap(), (128, 2));
+        assert_eq!(read_varint(&[0xff; 9]).unwrap(), (u64::MAX, 9));
+        assert!(read_varint(&[0x81]).is_err());
+    }
+
+    #[test]
+    fn test_read_signed() {
+        assert_eq!(read_signed(&[0x7f]), 127);
+        assert_eq!(read_signed(&[0xff]), -1);
+        assert_eq!(read_signed(&[0xff, 0x38]), -200);
+        assert_eq!(read_signed(&[0x00, 0x01, 0x00, 0x00, 0x00, 0x00]), 1 << 32);
+    }
+}
@@ -0,0 +1,726 @@
+mod format;
+mod schema;
+
+use wasm_bindgen::prelude::*;
+use serde::Serialize;
+use ts_rs::TS;
+use crate::parser::source::{ProfileDriver, RecordSource, SourceProgress, SourceSchema};
+use crate::stats::config::ProfilerConfig;
+use crate::stats::profiler::ProfilerResult;
+use crate::stats::types::DataType;
+use self::format::{Database, SqlValue};
+use self::schema::{parse_create_table, parse_view_columns, Affinity, TableDef};
+
+/// A column as declared in the schema SQL.
+#[derive(Serialize, Debug, Clone, TS)]
+#[ts(export)]
+pub struct SqliteColumnInfo {
+    pub name: String,
+    /// Declared type exactly as written, e.g. "VARCHAR(32)"; empty if none
+    pub declared_type: String,
+    /// Type affinity SQLite derives from the declared type
+    pub affinity: String,
+    pub primary_key: bool,
+    pub not_null: bool,
+}
+
+/// A table or view from `sqlite_schema`.
+#[derive(Serialize, Debug, Clone, TS)]
+#[ts(export)]
+pub struct SqliteObjectInfo {
+    pub name: String,
+    /// "table", "view" or "virtual" (virtual tables have no stored rows)
+    pub kind: String,
+    pub sql: Option<String>,
+    pub columns: Vec<SqliteColumnInfo>,
+    pub without_rowid: bool,
+    pub strict: bool,
+    /// Stored rows; None for views and virtual tables
+    pub row_count: Option<u64>,
+}
+
+/// Values per storage class as actually found on disk.
+#[derive(Serialize, Debug, Clone, Default, TS)]
+#[ts(export)]
+pub struct SqliteStorageClassCounts {
+    pub null: u64,
+    pub integer: u64,
+    pub real: u64,
+    pub text: u64,
+    pub blob: u64,
+}
+
+/// Declared type of a column compared with what it actually holds.
+#[derive(Serialize, Debug, Clone, TS)]
+#[ts(export)]
+pub struct SqliteColumnAffinity {
+    pub column: String,
+    pub declared_type: String,
+    pub affinity: String,
+    pub inferred_type: DataType,
+    pub storage_classes: SqliteStorageClassCounts,
+    /// Values stored with a class the affinity could not coerce them to,
+    /// e.g. non-numeric text in an INTEGER column
+    pub affinity_violations: u64,
+    /// The inferred type contradicts the declared affinity
+    pub type_mismatch: bool,
+}
+
+/// Profile of one table.
+#[derive(Serialize, Debug, TS)]
+#[ts(export)]
+pub struct SqliteTableProfile {
+    pub table: SqliteObjectInfo,
+    pub affinity: Vec<SqliteColumnAffinity>,
+    pub profile: ProfilerResult,
+}
+
+/// Root page of the schema table.
+const SCHEMA_ROOT: u32 = 1;
+
+/// An entry of `sqlite_schema` describing a table or view.
+struct SchemaEntry {
+    kind: String,
+    name: String,
+    root_page: u32,
+    sql: Option<String>,
+    table: Option<TableDef>,
+}
+
+impl SchemaEntry {
+    fn is_virtual(&self) -> bool {
+        self.kind == "table" && self.root_page == 0
+    }
+
+    fn info(&self, row_count: Option<u64>) -> SqliteObjectInfo {
+        let columns = match (&self.table, &self.sql) {
+            (Some(table), _) => table.columns.iter().map(|c| SqliteColumnInfo {
+                name: c.name.clone(),
+                declared_type: c.declared_type.clone(),
+                affinity: Affinity::from_declared(&c.declared_type).as_str().to_string(),
+                primary_key: c.primary_key,
+                not_null: c.not_null,
+            }).collect(),
+            (None, Some(sql)) if self.kind == "view" => parse_view_columns(sql).into_iter().map(|name| SqliteColumnInfo {
+                name,
+                declared_type: String::new(),
+                affinity: Affinity::Blob.as_str().to_string(),
+                primary_key: false,
+                not_null: false,
+            }).collect(),
+            _ => Vec::new(),
+        };
+        SqliteObjectInfo {
+            name: self.name.clone(),
+            kind: if self.is_virtual() { "virtual".to_string() } else { self.kind.clone() },
+            sql: self.sql.clone(),
+            columns,
+            without_rowid: self.table.as_ref().is_some_and(|t| t.without_rowid),
+            strict: self.table.as_ref().is_some_and(|t| t.strict),
+            row_count,
+        }
+    }
+}
+
+/// Profiles the tables of a SQLite database file. Views are listed with
+/// their SQL but not profiled, since that would require evaluating the query.
+#[wasm_bindgen]
+pub struct SqliteProfiler {
+    encoding: String,
+    config: ProfilerConfig,
+}
+
+#[wasm_bindgen]
+impl SqliteProfiler {
+    /// `config` is a `ProfilerConfig`; omitted fields keep their defaults.
+    #[wasm_bindgen(constructor)]
+    pub fn new(config: Option<JsValue>) -> Result<SqliteProfiler, JsValue> {
+        let config = ProfilerConfig::from_js(config).map_err(|e| JsValue::from_str(&e))?;
+        Ok(Self::with_config(config))
+    }
+
+    /// Returns "UTF-8", "UTF-16le" or "UTF-16be" once a database has been opened.
+    pub fn get_encoding(&self) -> String {
+        self.encoding.clone()
+    }
+
+    /// Lists tables and views with their declared columns and row counts.
+    pub fn list_objects(&mut self, file_bytes: &[u8]) -> Result<JsValue, JsValue> {
+        match self.list_objects_internal(file_bytes) {
+            Ok(objects) => serde_wasm_bindgen::to_value(&objects).map_err(|e| JsValue::from_str(&e.to_string())),
+            Err(e) => Err(JsValue::from_str(&e)),
+        }
+    }
+
+    /// Profiles every table. Returns an array of `SqliteTableProfile`.
+    pub fn parse_and_profile(&mut self, file_bytes: &[u8]) -> Result<JsValue, JsValue> {
+        match self.parse_and_profile_internal(file_bytes, None) {
+            Ok(tables) => serde_wasm_bindgen::to_value(&tables).map_err(|e| JsValue::from_str(&e.to_string())),
+            Err(e) => Err(JsValue::from_str(&e)),
+        }
+    }
+
+    /// Profiles the named tables (an array of strings). Returns an array of `SqliteTableProfile`.
+    pub fn parse_tables(&mut self, file_bytes: &[u8], table_names: JsValue) -> Result<JsValue, JsValue> {
+        let names: Vec<String> = serde_wasm_bindgen::from_value(table_names)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+        match self.parse_and_profile_internal(file_bytes, Some(&names)) {
+            Ok(tables) => serde_wasm_bindgen::to_value(&tables).map_err(|e| JsValue::from_str(&e.to_string())),
+            Err(e) => Err(JsValue::from_str(&e)),
+        }
+    }
+}
+
+impl SqliteProfiler {
+    pub(crate) fn with_config(config: ProfilerConfig) -> Self {
+        Self {
+            encoding: String::new(),
+            config,
+        }
+    }
+
+    /// Profiles a database on disk; `only` selects tables as in `parse_tables`.
+    pub fn parse_path(&mut self, path: &std::path::Path, only: Option<&[String]>) -> Result<Vec<SqliteTableProfile>, String> {
+        let bytes = std::fs::read(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        self.parse_and_profile_internal(&bytes, only)
+    }
+
+    fn list_objects_internal(&mut self, file_bytes: &[u8]) -> Result<Vec<SqliteObjectInfo>, String> {
+        let db = self.open(file_bytes)?;
+        let mut objects = Vec::new();
+        for entry in read_schema(&db)? {
+            let row_count = if entry.kind == "table" && !entry.is_virtual() {
+                let mut rows = 0u64;
+                scan_rows(&db, &entry, |_| {
+                    rows += 1;
+                    Ok(())
+                })?;
+                Some(rows)
+            } else {
+                None
+            };
+            objects.push(entry.info(row_count));
+        }
+        Ok(objects)
+    }
+
+    pub(crate) fn parse_and_profile_internal(&mut self, file_bytes: &[u8], only: Option<&[String]>) -> Result<Vec<SqliteTableProfile>, String> {
+        let db = self.open(file_bytes)?;
+        let entries = read_schema(&db)?;
+
+        if let Some(names) = only {
+            for name in names {
+                match entries.iter().find(|e| e.name == *name) {
+                    None => return Err(format!("Table '{}' not found", name)),
+                    Some(e) if e.kind == "view" => {
+                        return Err(format!("'{}' is a view; only tables can be profiled", name));
+                    }
+                    Some(e) if e.is_virtual() => {
+                        return Err(format!("'{}' is a virtual table with no stored rows", name));
+                    }
+                    Some(_) => {}
+                }
+            }
+        }
+
+        let mut profiles = Vec::new();
+        for entry in &entries {
+            if entry.kind != "table" || entry.is_virtual() {
+                continue;
+            }
+            if only.is_some_and(|names| !names.contains(&entry.name)) {
+                continue;
+            }
+            profiles.push(profile_table(&db, entry, &self.config)?);
+        }
+        Ok(profiles)
+    }
+
+    fn open<'a>(&mut self, file_bytes: &'a [u8]) -> Result<Database<'a>, String> {
+        let db = Database::open(file_bytes)?;
+        self.encoding = match db.encoding {
+            format::TextEncoding::Utf8 => "UTF-8",
+            format::TextEncoding::Utf16Le => "UTF-16le",
+            format::TextEncoding::Utf16Be => "UTF-16be",
+        }.to_string();
+        Ok(db)
+    }
+}
+
+/// Reads the tables and views from `sqlite_schema`, skipping SQLite's own
+/// `sqlite_*` tables, indexes and triggers.
+fn read_schema(db: &Database) -> Result<Vec<SchemaEntry>, String> {
+    let mut entries = Vec::new();
+    db.scan_table(SCHEMA_ROOT, |_, values| {
+        let text = |idx: usize| match values.get(idx) {
+            Some(SqlValue::Text(s)) => Some(s.clone()),
+            _ => None,
+        };
+        let kind = text(0).unwrap_or_default();
+        let name = text(1).unwrap_or_default();
+        if (kind != "table" && kind != "view") || name.starts_with("sqlite_") {
+            return Ok(());
+        }
+        let root_page = match values.get(3) {
+            Some(SqlValue::Integer(page)) => *page as u32,
+            _ => 0,
+        };
+        let sql = text(4);
+        let table = match (&sql, kind.as_str()) {
+            (Some(sql), "table") => parse_create_table(sql),
+            _ => None,
+        };
+        entries.push(SchemaEntry { kind, name, root_page, sql, table });
+        Ok(())
+    })?;
+    Ok(entries)
+}
+
+/// Visits the stored rows of a table with values in declared column order.
+/// Tables created with `CREATE TABLE ... AS SELECT` have no parsed column
+/// list; their rows are passed through as stored.
+fn scan_rows<F>(db: &Database, entry: &SchemaEntry, mut visit: F) -> Result<(), String>
+where
+    F: FnMut(Vec<SqlValue>) -> Result<(), String>,
+{
+    let Some(table) = &entry.table else {
+        return db.scan_table(entry.root_page, |_, values| visit(values));
+    };
+
+    // Position in the record of each declared column; virtual generated
+    // columns are not stored at all
+    let stored = |idx: &usize| !table.columns[*idx].virtual_generated;
+    let record_order: Vec<usize> = if table.without_rowid {
+        let rest = (0..table.columns.len()).filter(|i| !table.primary_key.contains(i));
+        table.primary_key.iter().copied().chain(rest).filter(stored).collect()
+    } else {
+        (0..table.columns.len()).filter(stored).collect()
+    };
+
+    let arrange = |stored_values: Vec<SqlValue>| {
+        let mut row = vec![SqlValue::Null; table.columns.len()];
+        for (value, &column) in stored_values.into_iter().zip(&record_order) {
+            row[column] = value;
+        }
+        row
+    };
+
+    if table.without_rowid {
+        db.scan_index(entry.root_page, |values| visit(arrange(values)))
+    } else {
+        let alias = table.rowid_alias();
+        db.scan_table(entry.root_page, |rowid, values| {
+            let mut row = arrange(values);
+            if let Some(idx) = alias {
+                row[idx] = SqlValue::Integer(rowid);
+            }
+            visit(row)
+        })
+    }
+}
+
+const BATCH_SIZE: usize = 1000;
+
+/// `RecordSource` over the rows of one table, read from the database before
+/// profiling starts. Storage classes and affinity violations are counted as
+/// rows are emitted.
+pub struct TableSource {
+    rows: std::vec::IntoIter<Vec<SqlValue>>,
+    headers: Vec<String>,
+    declared: Vec<String>,
+    affinities: Vec<Affinity>,
+    strict: bool,
+    storage: Vec<SqliteStorageClassCounts>,
+    violations: Vec<u64>,
+    batch: Vec<Vec<String>>,
+    progress: SourceProgress,
+}
+
+impl TableSource {
+    fn new(rows: Vec<Vec<SqlValue>>, headers: Vec<String>, declared: Vec<String>, strict: bool) -> Self {
+        let affinities = declared.iter().map(|d| Affinity::from_declared(d)).collect();
+        Self {
+            rows: rows.into_iter(),
+            storage: vec![SqliteStorageClassCounts::default(); headers.len()],
+            violations: vec![0; headers.len()],
+            headers,
+            declared,
+            affinities,
+            strict,
+            batch: Vec::with_capacity(BATCH_SIZE),
+            progress: SourceProgress::default(),
+        }
+    }
+}
+
+impl RecordSource for TableSource {
+    fn push(&mut self, _chunk: &[u8]) -> Result<(), String> {
+        Err("Table rows are read from the database, not pushed".to_string())
+    }
+
+    fn finish(&mut self) -> Result<(), String> {
+        self.progress.finished = true;
+        Ok(())
+    }
+
+    fn schema(&self) -> Option<SourceSchema> {
+        Some(SourceSchema {
+            declared_types: self.declared.iter().map(|d| (!d.is_empty()).then(|| d.clone())).collect(),
+            ..SourceSchema::untyped(self.headers.clone())
+        })
+    }
+
+    fn next_batch(&mut self) -> Result<Option<&[Vec<String>]>, String> {
+        if !self.progress.finished {
+            return Ok(None);
+        }
+        self.batch.clear();
+        for row in self.rows.by_ref().take(BATCH_SIZE) {
+            let mut values = Vec::with_capacity(self.headers.len());
+            for idx in 0..self.headers.len() {
+                let value = row.get(idx).unwrap_or(&SqlValue::Null);
+                let counts = &mut self.storage[idx];
+                match value {
+                    SqlValue::Null => counts.null += 1,
+                    SqlValue::Integer(_) => counts.integer += 1,
+                    SqlValue::Real(_) => counts.real += 1,
+                    SqlValue::Text(_) => counts.text += 1,
+                    SqlValue::Blob(_) => counts.blob += 1,
+                }
+                if !self.strict && violates_affinity(self.affinities[idx], &self.declared[idx], value) {
+                    self.violations[idx] += 1;
+                }
+                values.push(value.to_profile_string());
+            }
+            self.batch.push(values);
+        }
+
+        if self.batch.is_empty() {
+            return Ok(None);
+        }
+        self.progress.record_batch(self.batch.len());
+        Ok(Some(&self.batch))
+    }
+
+    fn progress(&self) -> SourceProgress {
+        self.progress.clone()
+    }
+}
+
+fn profile_table(db: &Database, entry: &SchemaEntry, config: &ProfilerConfig) -> Result<SqliteTableProfile, String> {
+    // Collected up front so columns of an unparsed table can be named by width
+    let mut rows: Vec<Vec<SqlValue>> = Vec::new();
+    scan_rows(db, entry, |row| {
+        rows.push(row);
+        Ok(())
+    })?;
+
+    let info = entry.info(Some(rows.len() as u64));
+    let (headers, declared): (Vec<String>, Vec<String>) = if info.columns.is_empty() {
+        let width = rows.iter().map(|r| r.len()).max().unwrap_or(0);
+        ((1..=width).map(|i| format!("Column {}", i)).collect(), vec![String::new(); width])
+    } else {
+        info.columns.iter().map(|c| (c.name.clone(), c.declared_type.clone())).unzip()
+    };
+
+    let strict = entry.table.as_ref().is_some_and(|t| t.strict);
+    let mut driver = ProfileDriver::new(TableSource::new(rows, headers.clone(), declared.clone(), strict), config.clone());
+    let mut profile = driver.finish()?;
+    let TableSource { affinities, storage, violations, .. } = driver.source();
+
+    let mut affinity = Vec::with_capacity(headers.len());
+    for (idx, column) in profile.column_profiles.iter_mut().enumerate() {
+        let inferred_type = column.base_stats.inferred_type.clone();
+        let type_mismatch = !type_matches(affinities[idx], &declared[idx], &inferred_type);
+        if type_mismatch {
+            column.notes.push(format!(
+                "Declared {} ({} affinity) but values look like {:?}",
+                if declared[idx].is_empty() { "without a type" } else { &declared[idx] },
+                affinities[idx].as_str(),
+                inferred_type,
+            ));
+        }
+        if entry.table.as_ref().and_then(|t| t.columns.get(idx)).is_some_and(|c| c.virtual_generated) {
+            column.notes.push("Virtual generated column: computed on read, not stored".to_string());
+        }
+        if violations[idx] > 0 {
+            column.notes.push(format!(
+                "{} values stored with a type that violates {} affinity",
+                violations[idx],
+                affinities[idx].as_str(),
+            ));
+        }
+        affinity.push(SqliteColumnAffinity {
+            column: headers[idx].clone(),
+            declared_type: declared[idx].clone(),
+            affinity: affinities[idx].as_str().to_string(),
+            inferred_type,
+            storage_classes: storage[idx].clone(),
+            affinity_violations: violations[idx],
+            type_mismatch,
+        });
+    }
+
+    Ok(SqliteTableProfile {
+        table: info,
+        affinity,
+        profile,
+    })
+}
+
+/// Date and time columns are conventionally declared DATE/DATETIME (NUMERIC
+/// affinity) and hold ISO-8601 text, which SQLite leaves as text.
+fn is_temporal(declared: &str) -> bool {
+    let upper = declared.to_ascii_uppercase();
+    upper.contains("DATE") || upper.contains("TIME")
+}
+
+/// Whether a stored value has a storage class its column's affinity would
+/// have converted on insert, meaning the conversion was not possible.
+fn violates_affinity(affinity: Affinity, declared: &str, value: &SqlValue) -> bool {
+    match (affinity, value) {
+        (_, SqlValue::Null) | (Affinity::Blob, _) => false,
+        (Affinity::Text, SqlValue::Text(_)) => false,
+        (Affinity::Text, _) => true,
+        (_, SqlValue::Blob(_)) => true,
+        (Affinity::Integer, SqlValue::Real(_)) => true,
+        (_, SqlValue::Text(_)) => !is_temporal(declared),
+        _ => false,
+    }
+}
+
+/// Whether the inferred type is one the declared affinity is expected to hold.
+fn type_matches(affinity: Affinity, declared: &str, inferred: &DataType) -> bool {
+    match (affinity, inferred) {
+        (_, DataType::Null) | (Affinity::Blob, _) => true,
+        (Affinity::Integer, t) => *t == DataType::Integer,
+        (Affinity::Real, t) => matches!(t, DataType::Integer | DataType::Numeric),
+        (Affinity::Numeric, DataType::Date | DataType::Timestamp | DataType::Time | DataType::String) => is_temporal(declared),
+        (Affinity::Numeric, t) => matches!(t, DataType::Integer | DataType::Numeric | DataType::Boolean),
+        (Affinity::Text, t) => matches!(t, DataType::String | DataType::Date | DataType::Timestamp | DataType::Time),
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PAGE_SIZE: usize = 4096;
+
+    fn varint(mut value: u64) -> Vec<u8> {
+        let mut groups = vec![(value & 0x7f) as u8];
+        value >>= 7;
+        while value > 0 {
+            groups.push((value & 0x7f) as u8 | 0x80);
+            value >>= 7;
+        }
+        groups.reverse();
+        groups
+    }
+
+    fn record(values: &[SqlValue]) -> Vec<u8> {
+        let mut types = Vec::new();
+        let mut body = Vec::new();
+        for value in values {
+            match value {
+                SqlValue::Null => types.extend(varint(0)),
+                SqlValue::Integer(i) => {
+                    types.extend(varint(6));
+                    body.extend(i.to_be_bytes());
+                }
+                SqlValue::Real(f) => {
+                    types.extend(varint(7));
+                    body.extend(f.to_bits().to_be_bytes());
+                }
+                SqlValue::Text(s) => {
+                    types.extend(varint(s.len() as u64 * 2 + 13));
+                    body.extend(s.as_bytes());
+                }
+                SqlValue::Blob(b) => {
+                    types.extend(varint(b.len() as u64 * 2 + 12));
+                    body.extend(b);
+                }
+            }
+        }
+        let mut out = varint(types.len() as u64 + 1);
+        out.extend(types);
+        out.extend(body);
+        out
+    }
+
+    /// Writes a single leaf page holding `cells`, starting at `header` bytes in.
+    fn leaf_page(page: &mut [u8], header: usize, kind: u8, cells: &[Vec<u8>]) {
+        page[header] = kind;
+        page[header + 3..header + 5].copy_from_slice(&(cells.len() as u16).to_be_bytes());
+        let mut end = PAGE_SIZE;
+        for (i, cell) in cells.iter().enumerate() {
+            end -= cell.len();
+            page[end..end + cell.len()].copy_from_slice(cell);
+            let at = header + 8 + i * 2;
+            page[at..at + 2].copy_from_slice(&(end as u16).to_be_bytes());
+        }
+        page[header + 5..header + 7].copy_from_slice(&(end as u16).to_be_bytes());
+    }
+
+    fn text(s: &str) -> SqlValue {
+        SqlValue::Text(s.to_string())
+    }
+
+    /// Builds a UTF-8 database with one leaf page per object. Views get no page.
+    fn build_database(objects: &[(&str, &str, Vec<Vec<SqlValue>>)]) -> Vec<u8> {
+        let mut pages = vec![vec![0u8; PAGE_SIZE]];
+        let mut schema_cells = Vec::new();
+        for (i, (name, sql, rows)) in objects.iter().enumerate() {
+            let is_view = sql.starts_with("CREATE VIEW");
+            let root = if is_view { 0 } else { pages.len() as i64 + 1 };
+            let schema_row = [
+                text(if is_view { "view" } else { "table" }),
+                text(name),
+                text(name),
+                SqlValue::Integer(root),
+                text(sql),
+            ];
+            let payload = record(&schema_row);
+            let mut cell = varint(payload.len() as u64);
+            cell.extend(varint(i as u64 + 1));
+            cell.extend(payload);
+            schema_cells.push(cell);
+            if is_view {
+                continue;
+            }
+
+            let without_rowid = sql.contains("WITHOUT ROWID");
+            let cells: Vec<Vec<u8>> = rows.iter().enumerate().map(|(rowid, row)| {
+                let payload = record(row);
+                let mut cell = varint(payload.len() as u64);
+                if !without_rowid {
+                    cell.extend(varint(rowid as u64 + 1));
+                }
+                cell.extend(payload);
+                cell
+            }).collect();
+            let mut page = vec![0u8; PAGE_SIZE];
+            leaf_page(&mut page, 0, if without_rowid { 0x0a } else { 0x0d }, &cells);
+            pages.push(page);
+        }
+
+        leaf_page(&mut pages[0], 100, 0x0d, &schema_cells);
+        let header = &mut pages[0];
+        header[..16].copy_from_slice(b"SQLite format 3\0");
+        header[16..18].copy_from_slice(&(PAGE_SIZE as u16).to_be_bytes());
+        header[56..60].copy_from_slice(&1u32.to_be_bytes());
+        pages.concat()
+    }
+
+    fn sample_database() -> Vec<u8> {
+        build_database(&[
+            (
+                "users",
+                "CREATE TABLE users (id INTEGER PRIMARY KEY, name VARCHAR(40) NOT NULL, age INTEGER, \
+                 zip TEXT, twice INTEGER GENERATED ALWAYS AS (age * 2) VIRTUAL)",
+                vec![
+                    vec![SqlValue::Null, text("Ann"), SqlValue::Integer(34), text("02139")],
+                    vec![SqlValue::Null, text("Bob"), text("unknown"), text("10001")],
+                    vec![SqlValue::Null, text("Cy"), SqlValue::Integer(27), text("94105")],
+                ],
+            ),
+            ("adults", "CREATE VIEW adults AS SELECT * FROM users WHERE age >= 18", vec![]),
+        ])
+    }
+
+    #[test]
+    fn test_list_objects() {
+        let objects = SqliteProfiler::with_config(ProfilerConfig::default()).list_objects_internal(&sample_database()).unwrap();
+        assert_eq!(objects.len(), 2);
+
+        let users = &objects[0];
+        assert_eq!((users.name.as_str(), users.kind.as_str(), users.row_count), ("users", "table", Some(3)));
+        let name = &users.columns[1];
+        assert_eq!((name.declared_type.as_str(), name.affinity.as_str()), ("VARCHAR(40)", "TEXT"));
+        assert!(name.not_null && users.columns[0].primary_key);
+
+        assert_eq!((objects[1].kind.as_str(), objects[1].row_count), ("view", None));
+    }
+
+    #[test]
+    fn test_profile_flags_affinity_violations() {
+        let mut profiler = SqliteProfiler::with_config(ProfilerConfig::default());
+        let tables = profiler.parse_and_profile_internal(&sample_database(), None).unwrap();
+        assert_eq!(tables.len(), 1);
+        assert_eq!(profiler.encoding, "UTF-8");
+
+        let table = &tables[0];
+        assert_eq!(table.profile.total_rows, 3);
+        let affinity = |name: &str| table.affinity.iter().find(|a| a.column == name).unwrap();
+
+        // The rowid alias is stored as NULL and read back from the rowid
+        let id = affinity("id");
+        assert_eq!(id.storage_classes.integer, 3);
+        assert_eq!(id.inferred_type, DataType::Integer);
+
+        let age = affinity("age");
+        assert_eq!((age.storage_classes.integer, age.storage_classes.text), (2, 1));
+        assert_eq!(age.affinity_violations, 1);
+
+        // Zip codes declared TEXT hold digits only
+        let zip = affinity("zip");
+        assert_eq!(zip.affinity_violations, 0);
+        assert!(zip.type_mismatch);
+        assert!(!affinity("name").type_mismatch);
+
+        let twice = &table.profile.column_profiles[4];
+        assert_eq!(twice.base_stats.missing, 3);
+        assert!(twice.notes.iter().any(|n| n.contains("Virtual generated")));
+    }
+
+    #[test]
+    fn test_without_rowid_columns_follow_primary_key() {
+        let bytes = build_database(&[(
+            "kv",
+            "CREATE TABLE kv (value TEXT, key TEXT, PRIMARY KEY (key)) WITHOUT ROWID",
+            vec![vec![text("a"), text("first")], vec![text("b"), text("second")]],
+        )]);
+        let tables = SqliteProfiler::with_config(ProfilerConfig::default())
+            .parse_and_profile_internal(&bytes, Some(&["kv".to_string()]))
+            .unwrap();
+
+        let columns = &tables[0].profile.column_profiles;
+        let top = |idx: usize| {
+            let mut values: Vec<String> = columns[idx].categorical_stats.as_ref().unwrap()
+                .top_values.iter().map(|v| v.value.clone()).collect();
+            values.sort();
+            values
+        };
+        assert_eq!(columns[1].name, "key");
+        assert_eq!(top(1), ["a", "b"]);
+        assert_eq!(top(0), ["first", "second"]);
+    }
+
+    #[test]
+    fn test_corrupt_pages_are_errors() {
+        let profile = |bytes: &[u8]| SqliteProfiler::with_config(ProfilerConfig::default()).parse_and_profile_internal(bytes, None);
+        let users = PAGE_SIZE;
+
+        // A cell count that runs the pointer array off the page
+        let mut bytes = sample_database();
+        bytes[users + 3..users + 5].copy_from_slice(&0xffffu16.to_be_bytes());
+        assert!(profile(&bytes).is_err());
+
+        // A payload size varint far beyond the file
+        let mut bytes = sample_database();
+        let cell = u16::from_be_bytes([bytes[users + 8], bytes[users + 9]]) as usize;
+        bytes[users + cell..users + cell + 9].fill(0xff);
+        assert!(profile(&bytes).is_err());
+    }
+
+    #[test]
+    fn test_rejects_views_and_non_sqlite_input() {
+        let mut profiler = SqliteProfiler::with_config(ProfilerConfig::default());
+        let err = profiler
+            .parse_and_profile_internal(&sample_database(), Some(&["adults".to_string()]))
+            .unwrap_err();
+        assert!(err.contains("view"));
+        assert!(profiler.parse_and_profile_internal(b"not a database", None).is_err());
+    }
+}
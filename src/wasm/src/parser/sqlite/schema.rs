@@ -0,0 +1,317 @@
+//! Just enough of SQLite's CREATE TABLE / CREATE VIEW grammar to recover
+//! column names, declared types and primary keys from `sqlite_schema.sql`.
+
+/// Column type affinity, derived from the declared type by SQLite's rules
+/// (https://www.sqlite.org/datatype3.html#determination_of_column_affinity).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Affinity {
+    Integer,
+    Text,
+    Blob,
+    Real,
+    Numeric,
+}
+
+impl Affinity {
+    pub fn from_declared(declared: &str) -> Self {
+        let upper = declared.to_ascii_uppercase();
+        if upper.contains("INT") {
+            Affinity::Integer
+        } else if upper.contains("CHAR") || upper.contains("CLOB") || upper.contains("TEXT") {
+            Affinity::Text
+        } else if upper.contains("BLOB") || upper.trim().is_empty() {
+            Affinity::Blob
+        } else if upper.contains("REAL") || upper.contains("FLOA") || upper.contains("DOUB") {
+            Affinity::Real
+        } else {
+            Affinity::Numeric
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Affinity::Integer => "INTEGER",
+            Affinity::Text => "TEXT",
+            Affinity::Blob => "BLOB",
+            Affinity::Real => "REAL",
+            Affinity::Numeric => "NUMERIC",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ColumnDef {
+    pub name: String,
+    pub declared_type: String,
+    pub primary_key: bool,
+    pub not_null: bool,
+    /// VIRTUAL generated columns are computed on read and not stored in records
+    pub virtual_generated: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct TableDef {
+    pub columns: Vec<ColumnDef>,
+    /// Primary key column positions, in key order
+    pub primary_key: Vec<usize>,
+    pub without_rowid: bool,
+    pub strict: bool,
+}
+
+impl TableDef {
+    /// The column aliasing the rowid: a lone `INTEGER PRIMARY KEY` column,
+    /// stored as NULL in the record.
+    pub fn rowid_alias(&self) -> Option<usize> {
+        if self.without_rowid {
+            return None;
+        }
+        match self.primary_key.as_slice() {
+            [idx] if self.columns[*idx].declared_type.eq_ignore_ascii_case("INTEGER") => Some(*idx),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Word(String),
+    Quoted(String),
+    Punct(char),
+}
+
+fn tokenize(sql: &str) -> Vec<Token> {
+    let chars: Vec<char> = sql.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        if c.is_whitespace() {
+            i += 1;
+        } else if c == '-' && chars.get(i + 1) == Some(&'-') {
+            while i < chars.len() && chars[i] != '\n' {
+                i += 1;
+            }
+        } else if c == '/' && chars.get(i + 1) == Some(&'*') {
+            i += 2;
+            while i < chars.len() && !(chars[i] == '*' && chars.get(i + 1) == Some(&'/')) {
+                i += 1;
+            }
+            i += 2;
+        } else if c == '"' || c == '`' || c == '\'' || c == '[' {
+            let close = if c == '[' { ']' } else { c };
+            let mut text = String::new();
+            i += 1;
+            while i < chars.len() {
+                if chars[i] == close {
+                    // A doubled quote is an escaped quote
+                    if close != ']' && chars.get(i + 1) == Some(&close) {
+                        text.push(close);
+                        i += 2;
+                        continue;
+                    }
+                    break;
+                }
+                text.push(chars[i]);
+                i += 1;
+            }
+            i += 1;
+            tokens.push(Token::Quoted(text));
+        } else if c.is_alphanumeric() || c == '_' || c == '.' || c == '+' || c == '-' {
+            let start = i;
+            while i < chars.len() && (chars[i].is_alphanumeric() || "_.$+-".contains(chars[i])) {
+                i += 1;
+            }
+            tokens.push(Token::Word(chars[start..i].iter().collect()));
+        } else {
+            tokens.push(Token::Punct(c));
+            i += 1;
+        }
+    }
+    tokens
+}
+
+fn is_word(token: Option<&Token>, word: &str) -> bool {
+    matches!(token, Some(Token::Word(w)) if w.eq_ignore_ascii_case(word))
+}
+
+fn name_of(token: &Token) -> String {
+    match token {
+        Token::Word(w) | Token::Quoted(w) => w.clone(),
+        Token::Punct(c) => c.to_string(),
+    }
+}
+
+/// Splits the tokens of a parenthesized list into its top-level items.
+/// `start` is the position just after the opening parenthesis; returns the
+/// items and the position after the closing one.
+fn split_list(tokens: &[Token], start: usize) -> (Vec<&[Token]>, usize) {
+    let mut items = Vec::new();
+    let mut depth = 0;
+    let mut item_start = start;
+    let mut i = start;
+    while i < tokens.len() {
+        match tokens[i] {
+            Token::Punct('(') => depth += 1,
+            Token::Punct(')') if depth == 0 => {
+                items.push(&tokens[item_start..i]);
+                return (items, i + 1);
+            }
+            Token::Punct(')') => depth -= 1,
+            Token::Punct(',') if depth == 0 => {
+                items.push(&tokens[item_start..i]);
+                item_start = i + 1;
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+    items.push(&tokens[item_start..]);
+    (items, tokens.len())
+}
+
+const CONSTRAINT_WORDS: [&str; 11] = [
+    "CONSTRAINT", "PRIMARY", "NOT", "NULL", "UNIQUE", "CHECK", "DEFAULT", "COLLATE", "REFERENCES", "GENERATED", "AS",
+];
+
+fn parse_column(item: &[Token]) -> Option<ColumnDef> {
+    let name = name_of(item.first()?);
+    let mut i = 1;
+    let mut type_parts = Vec::new();
+    while i < item.len() && !CONSTRAINT_WORDS.iter().any(|w| is_word(item.get(i), w)) {
+        match &item[i] {
+            Token::Punct('(') => {
+                let (args, end) = split_list(item, i + 1);
+                let args: Vec<String> = args
+                    .iter()
+                    .map(|arg| arg.iter().map(name_of).collect::<Vec<_>>().join(" "))
+                    .collect();
+                let last = type_parts.pop().unwrap_or_default();
+                type_parts.push(format!("{}({})", last, args.join(",")));
+                i = end;
+            }
+            token => {
+                type_parts.push(name_of(token));
+                i += 1;
+            }
+        }
+    }
+
+    let rest = &item[i..];
+    let has = |a: &str, b: &str| rest.windows(2).any(|w| is_word(Some(&w[0]), a) && is_word(Some(&w[1]), b));
+    let generated = rest.iter().any(|t| is_word(Some(t), "AS"));
+    Some(ColumnDef {
+        name,
+        declared_type: type_parts.join(" "),
+        primary_key: has("PRIMARY", "KEY"),
+        not_null: has("NOT", "NULL"),
+        virtual_generated: generated && !rest.iter().any(|t| is_word(Some(t), "STORED")),
+    })
+}
+
+/// Parses `CREATE TABLE name (column-def, ..., table-constraint, ...) options`.
+/// Returns None for `CREATE TABLE ... AS SELECT`, whose columns are not spelled out.
+pub fn parse_create_table(sql: &str) -> Option<TableDef> {
+    let tokens = tokenize(sql);
+    let open = tokens.iter().position(|t| *t == Token::Punct('('))?;
+    if tokens[..open].iter().any(|t| is_word(Some(t), "AS")) {
+        return None;
+    }
+    let (items, end) = split_list(&tokens, open + 1);
+
+    let mut table = TableDef::default();
+    let mut table_keys: Vec<String> = Vec::new();
+    for item in items {
+        let first = item.first();
+        if ["CONSTRAINT", "PRIMARY", "UNIQUE", "CHECK", "FOREIGN"].iter().any(|w| is_word(first, w)) {
+            if let Some(pos) = item.iter().position(|t| is_word(Some(t), "PRIMARY")) {
+                if let Some(open) = item[pos..].iter().position(|t| *t == Token::Punct('(')) {
+                    let (keys, _) = split_list(item, pos + open + 1);
+                    table_keys.extend(keys.iter().filter_map(|k| k.first().map(name_of)));
+                }
+            }
+        } else if let Some(column) = parse_column(item) {
+            table.columns.push(column);
+        }
+    }
+    for key in &table_keys {
+        if let Some(idx) = table.columns.iter().position(|c| c.name.eq_ignore_ascii_case(key)) {
+            table.columns[idx].primary_key = true;
+            table.primary_key.push(idx);
+        }
+    }
+    if table_keys.is_empty() {
+        table.primary_key = (0..table.columns.len()).filter(|&i| table.columns[i].primary_key).collect();
+    }
+
+    let options = &tokens[end..];
+    table.without_rowid = options.windows(2).any(|w| is_word(Some(&w[0]), "WITHOUT") && is_word(Some(&w[1]), "ROWID"));
+    table.strict = options.iter().any(|t| is_word(Some(t), "STRICT"));
+    Some(table)
+}
+
+/// Column names listed explicitly in `CREATE VIEW name (a, b) AS ...`, if any.
+pub fn parse_view_columns(sql: &str) -> Vec<String> {
+    let tokens = tokenize(sql);
+    let Some(as_pos) = tokens.iter().position(|t| is_word(Some(t), "AS")) else {
+        return Vec::new();
+    };
+    match tokens[..as_pos].iter().position(|t| *t == Token::Punct('(')) {
+        Some(open) => split_list(&tokens, open + 1).0.iter().filter_map(|c| c.first().map(name_of)).collect(),
+        None => Vec::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_affinity_rules() {
+        assert_eq!(Affinity::from_declared("BIGINT"), Affinity::Integer);
+        assert_eq!(Affinity::from_declared("VARCHAR(255)"), Affinity::Text);
+        assert_eq!(Affinity::from_declared(""), Affinity::Blob);
+        assert_eq!(Affinity::from_declared("DOUBLE PRECISION"), Affinity::Real);
+        assert_eq!(Affinity::from_declared("DECIMAL(10,2)"), Affinity::Numeric);
+        // "FLOATING POINT" contains "INT", which wins
+        assert_eq!(Affinity::from_declared("FLOATING POINT"), Affinity::Integer);
+    }
+
+    #[test]
+    fn test_parse_create_table() {
+        let table = parse_create_table(
+            "CREATE TABLE \"order items\" (
+                id INTEGER PRIMARY KEY,
+                [sku] VARCHAR(32) NOT NULL, -- stock keeping unit
+                price DECIMAL(10, 2) DEFAULT 0,
+                total REAL GENERATED ALWAYS AS (price * 2) VIRTUAL,
+                note,
+                CONSTRAINT fk FOREIGN KEY (sku) REFERENCES products(sku)
+            )",
+        )
+        .unwrap();
+
+        let names: Vec<&str> = table.columns.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, ["id", "sku", "price", "total", "note"]);
+        assert_eq!(table.columns[1].declared_type, "VARCHAR(32)");
+        assert!(table.columns[1].not_null);
+        assert_eq!(table.columns[2].declared_type, "DECIMAL(10,2)");
+        assert!(table.columns[3].virtual_generated);
+        assert_eq!(table.columns[4].declared_type, "");
+        assert_eq!(table.rowid_alias(), Some(0));
+    }
+
+    #[test]
+    fn test_parse_without_rowid_and_views() {
+        let table = parse_create_table(
+            "CREATE TABLE kv (k TEXT, v BLOB, PRIMARY KEY (k)) WITHOUT ROWID, STRICT",
+        )
+        .unwrap();
+        assert!(table.without_rowid && table.strict);
+        assert_eq!(table.primary_key, [0]);
+        assert_eq!(table.rowid_alias(), None);
+
+        assert!(parse_create_table("CREATE TABLE t AS SELECT 1").is_none());
+        assert_eq!(parse_view_columns("CREATE VIEW v (a, b) AS SELECT x, y FROM t"), ["a", "b"]);
+        assert!(parse_view_columns("CREATE VIEW v AS SELECT (1)").is_empty());
+    }
+}
@@ -0,0 +1,267 @@
+use wasm_bindgen::prelude::*;
+use flate2::write::MultiGzDecoder;
+use serde::Serialize;
+use std::io::Write;
+use crate::parser::arrow::ArrowSource;
+use crate::parser::avro::AvroSource;
+use crate::parser::csv::{CsvParser, CsvSource};
+use crate::parser::detect::{sniff, FormatDetection};
+use crate::parser::excel::{ExcelProfiler, ExcelSheetProfile};
+use crate::parser::json::{JsonParser, JsonSource};
+use crate::parser::orc::OrcSource;
+use crate::parser::parquet::ParquetSource;
+use crate::parser::source::{ProfileDriver, RecordSource};
+use crate::parser::sqlite::{SqliteProfiler, SqliteTableProfile};
+use crate::parser::xml::{XmlParser, XmlSource};
+use crate::stats::config::ProfilerConfig;
+use crate::stats::profiler::ProfilerResult;
+
+/// Bytes collected before the format is sniffed.
+const SNIFF_BYTES: usize = 64 * 1024;
+
+/// Formats `UniversalProfiler` can be told to use instead of sniffing.
+const KNOWN_FORMATS: [&str; 13] = [
+    "csv", "tsv", "json", "jsonl", "xml", "parquet", "avro", "arrow", "orc", "xlsx", "xls", "ods", "sqlite",
+];
+
+/// Workbooks and databases hold several tables and produce one profile each.
+#[derive(Serialize, Debug)]
+#[serde(untagged)]
+pub(crate) enum UniversalResult {
+    Profile(Box<ProfilerResult>),
+    Sheets(Vec<ExcelSheetProfile>),
+    Tables(Vec<SqliteTableProfile>),
+}
+
+enum Route {
+    Sniffing,
+    Source(Box<ProfileDriver<Box<dyn RecordSource>>>),
+    // Workbooks and databases, read from the complete file
+    Whole(Vec<u8>),
+}
+
+/// Single entry point: sniffs the format from the first chunk and routes
+/// every chunk to the matching `RecordSource`. Text formats and Avro are
+/// profiled as they stream in; other binary formats are buffered until
+/// `finalize`.
+/// Gzip-compressed input is decompressed on the fly.
+#[wasm_bindgen]
+pub struct UniversalProfiler {
+    forced_format: Option<String>,
+    detection: Option<FormatDetection>,
+    pending: Vec<u8>,
+    gunzip: Option<MultiGzDecoder<Vec<u8>>>,
+    route: Route,
+    config: ProfilerConfig,
+}
+
+#[wasm_bindgen]
+impl UniversalProfiler {
+    /// `format` skips content sniffing for the given format (see
+    /// `FormatDetection.format`); compression is still detected. `config` is
+    /// a `ProfilerConfig`; omitted fields keep their defaults.
+    #[wasm_bindgen(constructor)]
+    pub fn new(format: Option<String>, config: Option<JsValue>) -> Result<UniversalProfiler, JsValue> {
+        let config = ProfilerConfig::from_js(config).map_err(|e| JsValue::from_str(&e))?;
+        Self::with_format(format, config).map_err(|e| JsValue::from_str(&e))
+    }
+
+    pub fn parse_and_profile_chunk(&mut self, chunk: &[u8]) -> Result<(), JsValue> {
+        self.process_chunk(chunk).map_err(|e| JsValue::from_str(&e))
+    }
+
+    /// Returns the `FormatDetection`, or null until enough bytes were seen.
+    pub fn get_detection(&self) -> Result<JsValue, JsValue> {
+        serde_wasm_bindgen::to_value(&self.detection).map_err(|e| JsValue::from_str(&e.to_string()))
+    }
+
+    /// Returns a `ProfilerResult`; for workbooks an array of
+    /// `ExcelSheetProfile` and for SQLite an array of `SqliteTableProfile`.
+    pub fn finalize(&mut self) -> Result<JsValue, JsValue> {
+        match self.finalize_internal() {
+            Ok(result) => serde_wasm_bindgen::to_value(&result).map_err(|e| JsValue::from_str(&e.to_string())),
+            Err(e) => Err(JsValue::from_str(&e)),
+        }
+    }
+}
+
+impl UniversalProfiler {
+    pub(crate) fn with_format(format: Option<String>, config: ProfilerConfig) -> Result<Self, String> {
+        if let Some(format) = &format {
+            if !KNOWN_FORMATS.contains(&format.as_str()) {
+                return Err(format!("Unsupported format '{}'", format));
+            }
+        }
+        Ok(Self {
+            forced_format: format,
+            detection: None,
+            pending: Vec::new(),
+            gunzip: None,
+            route: Route::Sniffing,
+            config,
+        })
+    }
+
+    pub(crate) fn process_chunk(&mut self, chunk: &[u8]) -> Result<(), String> {
+        if let Route::Sniffing = self.route {
+            self.pending.extend_from_slice(chunk);
+            if self.pending.len() < SNIFF_BYTES {
+                return Ok(());
+            }
+            return self.start();
+        }
+        self.feed_raw(chunk)
+    }
+
+    pub(crate) fn finalize_internal(&mut self) -> Result<UniversalResult, String> {
+        if let Route::Sniffing = self.route {
+            if self.pending.is_empty() {
+                return Err("No data was processed".to_string());
+            }
+            self.start()?;
+        }
+        if let Some(mut decoder) = self.gunzip.take() {
+            decoder.try_finish().map_err(|e| format!("gzip error: {}", e))?;
+            let rest = std::mem::take(decoder.get_mut());
+            self.feed(&rest)?;
+        }
+
+        let format = self.detection.as_ref().map(|d| d.format.clone()).unwrap_or_default();
+        match std::mem::replace(&mut self.route, Route::Sniffing) {
+            Route::Sniffing => Err("No data was processed".to_string()),
+            Route::Source(mut driver) => driver.finish().map(|r| UniversalResult::Profile(Box::new(r))),
+            Route::Whole(bytes) => match format.as_str() {
+                "xlsx" | "xls" | "ods" => ExcelProfiler::with_config(self.config.clone()).parse_and_profile_internal(&bytes, None).map(UniversalResult::Sheets),
+                "sqlite" => SqliteProfiler::with_config(self.config.clone()).parse_and_profile_internal(&bytes, None).map(UniversalResult::Tables),
+                other => Err(format!("No profiler for format '{}'", other)),
+            },
+        }
+    }
+
+    /// Sniffs the collected bytes, picks the route and replays them into it.
+    fn start(&mut self) -> Result<(), String> {
+        let mut detection = sniff(&self.pending);
+        if let Some(format) = &self.forced_format {
+            if *format != detection.format {
+                detection.format = format.clone();
+                detection.confidence = 1.0;
+                detection.reason = "format given by caller".to_string();
+                detection.delimiter = (format == "tsv").then(|| "\t".to_string());
+            }
+        }
+
+        if detection.compression.as_deref() == Some("gzip") {
+            self.gunzip = Some(MultiGzDecoder::new(Vec::new()));
+        }
+        self.route = match detection.format.as_str() {
+            // Several tables per file: read by their own profilers at finalize
+            "xlsx" | "xls" | "ods" | "sqlite" => Route::Whole(Vec::new()),
+            _ => Route::Source(Box::new(ProfileDriver::new(self.source_for(&detection)?, self.config.clone()))),
+        };
+        self.detection = Some(detection);
+
+        let pending = std::mem::take(&mut self.pending);
+        self.feed_raw(&pending)
+    }
+
+    fn source_for(&self, detection: &FormatDetection) -> Result<Box<dyn RecordSource>, String> {
+        let source: Box<dyn RecordSource> = match detection.format.as_str() {
+            "csv" | "tsv" => {
+                let delimiter = detection.delimiter.as_ref().and_then(|d| d.bytes().next())
+                    .unwrap_or_else(|| CsvParser::auto_detect_delimiter(&self.pending));
+                Box::new(CsvSource::new(CsvParser::new(Some(delimiter), true)))
+            }
+            "json" | "jsonl" => Box::new(JsonSource::new(JsonParser::new(None))),
+            "xml" => Box::new(XmlSource::new(XmlParser::new(None, None)?)),
+            "avro" => Box::new(AvroSource::new()),
+            "parquet" => Box::new(ParquetSource::new()),
+            "arrow" => Box::new(ArrowSource::new()),
+            "orc" => Box::new(OrcSource::new()),
+            other => {
+                return Err(format!("Could not detect a supported format ({}: {})", other, detection.reason));
+            }
+        };
+        Ok(source)
+    }
+
+    fn feed_raw(&mut self, bytes: &[u8]) -> Result<(), String> {
+        match self.gunzip.as_mut() {
+            Some(decoder) => {
+                decoder.write_all(bytes).map_err(|e| format!("gzip error: {}", e))?;
+                let decoded = std::mem::take(decoder.get_mut());
+                self.feed(&decoded)
+            }
+            None => self.feed(bytes),
+        }
+    }
+
+    fn feed(&mut self, data: &[u8]) -> Result<(), String> {
+        match &mut self.route {
+            Route::Sniffing => unreachable!("data is fed only after the route is chosen"),
+            Route::Source(driver) => driver.push(data)?,
+            Route::Whole(buffer) => buffer.extend_from_slice(data),
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::write::GzEncoder;
+    use flate2::Compression;
+
+    fn profile_chunks(profiler: &mut UniversalProfiler, data: &[u8], chunk_size: usize) -> ProfilerResult {
+        for chunk in data.chunks(chunk_size) {
+            profiler.process_chunk(chunk).unwrap();
+        }
+        match profiler.finalize_internal().unwrap() {
+            UniversalResult::Profile(result) => *result,
+            other => panic!("expected a single profile, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_routes_csv_and_jsonl() {
+        let mut profiler = UniversalProfiler::with_format(None, ProfilerConfig::default()).unwrap();
+        let result = profile_chunks(&mut profiler, b"id;name\n1;a\n2;b\n3;c\n", 5);
+        assert_eq!(profiler.detection.as_ref().unwrap().format, "csv");
+        assert_eq!(result.total_rows, 3);
+        assert_eq!(result.column_profiles[1].name, "name");
+
+        let mut profiler = UniversalProfiler::with_format(None, ProfilerConfig::default()).unwrap();
+        let result = profile_chunks(&mut profiler, b"{\"a\": 1, \"b\": \"x\"}\n{\"a\": 2, \"b\": \"y\"}\n", 7);
+        assert_eq!(profiler.detection.as_ref().unwrap().format, "jsonl");
+        assert_eq!(result.total_rows, 2);
+    }
+
+    #[test]
+    fn test_gzip_input_is_decompressed() {
+        let mut csv = b"id,value\n".to_vec();
+        for i in 0..20_000 {
+            csv.extend(format!("{},{}\n", i, i * 2).as_bytes());
+        }
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(&csv).unwrap();
+        let compressed = encoder.finish().unwrap();
+
+        let mut profiler = UniversalProfiler::with_format(None, ProfilerConfig::default()).unwrap();
+        let result = profile_chunks(&mut profiler, &compressed, 4096);
+        let detection = profiler.detection.as_ref().unwrap();
+        assert_eq!((detection.format.as_str(), detection.compression.as_deref()), ("csv", Some("gzip")));
+        assert_eq!(result.total_rows, 20_000);
+    }
+
+    #[test]
+    fn test_forced_format_and_errors() {
+        let mut profiler = UniversalProfiler::with_format(Some("tsv".to_string()), ProfilerConfig::default()).unwrap();
+        let result = profile_chunks(&mut profiler, b"a\tb\n1\t2\n", 64);
+        assert_eq!(result.column_profiles.len(), 2);
+        assert!(UniversalProfiler::with_format(Some("pdf".to_string()), ProfilerConfig::default()).is_err());
+
+        let mut profiler = UniversalProfiler::with_format(None, ProfilerConfig::default()).unwrap();
+        assert!(profiler.finalize_internal().is_err());
+        profiler.process_chunk(b"PK\x03\x04not a spreadsheet").unwrap();
+        assert!(profiler.finalize_internal().unwrap_err().contains("zip"));
+    }
+}
@@ -0,0 +1,532 @@
+use quick_xml::escape::unescape;
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+use crate::parser::json::{ArrayFieldStats, JsonParser, JsonParserConfig, PathTracker};
+use crate::parser::source::{ChunkParser, ChunkedSource};
+use crate::stats::tree::{StructureAnalysis, StructureConfig};
+
+/// Result of XML parsing, compatible with the JSON parser's result structure
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct XmlParseResult {
+    pub headers: Vec<String>,
+    pub rows: Vec<Vec<String>>,
+    pub total_rows: u32,
+    /// Absolute path of the record element, e.g. "/catalog/book", once known
+    pub record_path: Option<String>,
+    pub array_stats: HashMap<String, ArrayFieldStats>,
+}
+
+/// Bytes buffered before the record element is auto-detected.
+const DETECT_BYTES: usize = 64 * 1024;
+
+/// Which elements are records: an absolute path ("/catalog/book") or a path
+/// matched at any depth ("//book", "book", "items/item").
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+enum RecordPath {
+    Absolute(Vec<String>),
+    Suffix(Vec<String>),
+}
+
+impl RecordPath {
+    fn parse(path: &str) -> Result<Self, String> {
+        let trimmed = path.trim();
+        let (absolute, rest) = match trimmed.strip_prefix("//") {
+            Some(rest) => (false, rest),
+            None => match trimmed.strip_prefix('/') {
+                Some(rest) => (true, rest),
+                None => (false, trimmed),
+            },
+        };
+        let segments: Vec<String> = rest.split('/').map(|s| s.to_string()).collect();
+        if segments.iter().any(|s| s.is_empty()) {
+            return Err(format!("Invalid record path '{}'", path));
+        }
+        Ok(if absolute { RecordPath::Absolute(segments) } else { RecordPath::Suffix(segments) })
+    }
+
+    fn matches(&self, stack: &[String]) -> bool {
+        match self {
+            RecordPath::Absolute(segments) => stack == segments.as_slice(),
+            RecordPath::Suffix(segments) => stack.ends_with(segments),
+        }
+    }
+}
+
+/// An element inside a record, collected until its end tag.
+#[derive(Serialize, Deserialize, Default)]
+struct XmlNode {
+    name: String,
+    attributes: Vec<(String, String)>,
+    children: Vec<XmlNode>,
+    text: String,
+}
+
+impl XmlNode {
+    /// Converts an element to the JSON shape `JsonParser` flattens: attributes
+    /// become "@name" keys, repeated children become arrays, and text next to
+    /// attributes or children is kept under "#text". A leaf element is its text.
+    fn into_value(self, typed: bool) -> Value {
+        let text = self.text.trim();
+        if self.attributes.is_empty() && self.children.is_empty() {
+            return if text.is_empty() { Value::Null } else { text_value(text, typed) };
+        }
+
+        let mut map = Map::new();
+        for (name, value) in self.attributes {
+            map.insert(format!("@{}", name), text_value(&value, typed));
+        }
+        if !text.is_empty() {
+            map.insert("#text".to_string(), text_value(text, typed));
+        }
+        for child in self.children {
+            let name = child.name.clone();
+            let value = child.into_value(typed);
+            match map.get_mut(&name) {
+                Some(Value::Array(items)) => items.push(value),
+                Some(existing) => {
+                    let first = existing.take();
+                    *existing = Value::Array(vec![first, value]);
+                }
+                None => {
+                    map.insert(name, value);
+                }
+            }
+        }
+        Value::Object(map)
+    }
+}
+
+/// XML text is always a string for profiling (type inference happens in the
+/// profiler); the structure view types numbers and booleans like JSON would.
+fn text_value(text: &str, typed: bool) -> Value {
+    if typed {
+        if let Ok(i) = text.parse::<i64>() {
+            return Value::from(i);
+        }
+        if let Some(n) = text.parse::<f64>().ok().and_then(serde_json::Number::from_f64) {
+            return Value::Number(n);
+        }
+        if text == "true" || text == "false" {
+            return Value::Bool(text == "true");
+        }
+    }
+    Value::String(text.to_string())
+}
+
+fn local_name(element: &BytesStart) -> String {
+    String::from_utf8_lossy(element.local_name().as_ref()).into_owned()
+}
+
+fn read_attributes(element: &BytesStart) -> Result<Vec<(String, String)>, String> {
+    let mut attributes = Vec::new();
+    for attr in element.attributes() {
+        let attr = attr.map_err(|e| e.to_string())?;
+        let key = attr.key.as_ref();
+        if key == b"xmlns" || key.starts_with(b"xmlns:") {
+            continue;
+        }
+        let name = String::from_utf8_lossy(attr.key.local_name().as_ref()).into_owned();
+        let value = unescape(&String::from_utf8_lossy(&attr.value))
+            .map_err(|e| e.to_string())?
+            .into_owned();
+        attributes.push((name, value));
+    }
+    Ok(attributes)
+}
+
+/// Streaming XML parser that turns repeated record elements into rows.
+/// Records are converted to JSON values and flattened by a `JsonParser`, so
+/// nested elements and attributes become dotted columns exactly as in JSON.
+#[derive(Serialize, Deserialize)]
+pub struct XmlParser {
+    flattener: JsonParser,
+    record_path: Option<RecordPath>,
+    detected_path: Option<String>,
+    typed_values: bool,
+    buffer: Vec<u8>,
+    // Names of the currently open elements
+    stack: Vec<String>,
+    // Open elements of the record being read, outermost first
+    record: Vec<XmlNode>,
+    total_rows: u32,
+}
+
+impl XmlParser {
+    pub fn new(record_path: Option<&str>, config: Option<JsonParserConfig>) -> Result<Self, String> {
+        let record_path = record_path
+            .filter(|p| !p.trim().is_empty())
+            .map(RecordPath::parse)
+            .transpose()?;
+        Ok(Self {
+            flattener: JsonParser::new(config),
+            record_path,
+            detected_path: None,
+            typed_values: false,
+            buffer: Vec::new(),
+            stack: Vec::new(),
+            record: Vec::new(),
+            total_rows: 0,
+        })
+    }
+
+    /// Parse a chunk of XML data
+    pub fn parse_chunk(&mut self, chunk: &[u8]) -> Result<XmlParseResult, String> {
+        self.buffer.extend_from_slice(chunk);
+        let records = self.read_records(false)?;
+        Ok(self.flatten(records))
+    }
+
+    /// Parse whatever remains in the buffer at end of input
+    pub fn flush(&mut self) -> Result<XmlParseResult, String> {
+        let records = self.read_records(true)?;
+        if !self.record.is_empty() {
+            return Err(format!("Unexpected end of document inside <{}>", self.record[0].name));
+        }
+        Ok(self.flatten(records))
+    }
+
+    /// The record path in use: given, or detected from the first chunk
+    pub fn get_record_path(&self) -> Option<String> {
+        match &self.record_path {
+            Some(RecordPath::Absolute(segments)) => Some(format!("/{}", segments.join("/"))),
+            Some(RecordPath::Suffix(segments)) => Some(format!("//{}", segments.join("/"))),
+            None => self.detected_path.clone(),
+        }
+    }
+
+    pub fn get_array_stats(&self) -> &HashMap<String, ArrayFieldStats> {
+        self.flattener.get_array_stats()
+    }
+
+    fn flatten(&mut self, records: Vec<Value>) -> XmlParseResult {
+        let rows = records.iter().map(|record| self.flattener.flatten_value(record)).collect();
+        XmlParseResult {
+            headers: self.flattener.headers().to_vec(),
+            rows,
+            total_rows: self.total_rows,
+            record_path: self.get_record_path(),
+            array_stats: self.flattener.get_array_stats().clone(),
+        }
+    }
+
+    /// Reads all complete records from the buffer. Without `is_final`, an
+    /// event cut off by the chunk boundary is left in the buffer for the next
+    /// chunk; element state carries over between calls.
+    fn read_records(&mut self, is_final: bool) -> Result<Vec<Value>, String> {
+        if self.record_path.is_none() {
+            if !is_final && self.buffer.len() < DETECT_BYTES {
+                return Ok(Vec::new());
+            }
+            let detected = detect_record_path(&self.buffer)?;
+            self.detected_path = Some(format!("/{}", detected.join("/")));
+            self.record_path = Some(RecordPath::Absolute(detected));
+        }
+
+        let buffer = std::mem::take(&mut self.buffer);
+        let mut reader = Reader::from_reader(buffer.as_slice());
+        reader.check_end_names(false);
+
+        let mut records = Vec::new();
+        let mut consumed = 0;
+        let result = loop {
+            let event = match reader.read_event() {
+                Ok(event) => event,
+                Err(quick_xml::Error::UnexpectedEof(_)) if !is_final => break Ok(()),
+                Err(e) => break Err(format!("XML error at byte {}: {}", reader.buffer_position(), e)),
+            };
+            // Complete text is followed by the '<' of the next tag
+            let at_end = buffer.get(reader.buffer_position()) != Some(&b'<');
+            match event {
+                Event::Eof => break Ok(()),
+                // Text running into the end of the buffer may continue in the next chunk
+                Event::Text(_) if at_end && !is_final => break Ok(()),
+                Event::Start(ref e) => {
+                    if let Err(e) = self.open_element(e) {
+                        break Err(e);
+                    }
+                }
+                Event::Empty(ref e) => {
+                    if let Err(e) = self.open_element(e) {
+                        break Err(e);
+                    }
+                    self.close_element(&mut records);
+                }
+                Event::End(_) => self.close_element(&mut records),
+                Event::Text(ref e) => {
+                    if let Some(node) = self.record.last_mut() {
+                        match unescape(&String::from_utf8_lossy(e)) {
+                            Ok(text) => node.text.push_str(&text),
+                            Err(e) => break Err(e.to_string()),
+                        }
+                    }
+                }
+                Event::CData(ref e) => {
+                    if let Some(node) = self.record.last_mut() {
+                        node.text.push_str(&String::from_utf8_lossy(e));
+                    }
+                }
+                _ => {}
+            }
+            consumed = reader.buffer_position();
+        };
+
+        self.buffer = buffer[consumed..].to_vec();
+        result.map(|_| records)
+    }
+
+    fn open_element(&mut self, element: &BytesStart) -> Result<(), String> {
+        let name = local_name(element);
+        self.stack.push(name.clone());
+        let in_record = !self.record.is_empty()
+            || self.record_path.as_ref().is_some_and(|path| path.matches(&self.stack));
+        if in_record {
+            self.record.push(XmlNode {
+                name,
+                attributes: read_attributes(element)?,
+                ..Default::default()
+            });
+        }
+        Ok(())
+    }
+
+    fn close_element(&mut self, records: &mut Vec<Value>) {
+        self.stack.pop();
+        if let Some(node) = self.record.pop() {
+            match self.record.last_mut() {
+                Some(parent) => parent.children.push(node),
+                None => {
+                    records.push(node.into_value(self.typed_values));
+                    self.total_rows += 1;
+                }
+            }
+        }
+    }
+}
+
+/// Picks the shallowest element path that repeats under one parent, e.g.
+/// "/catalog/book" for a catalog of books. A document without repeated
+/// elements is a single record: its root element.
+fn detect_record_path(buffer: &[u8]) -> Result<Vec<String>, String> {
+    let mut reader = Reader::from_reader(buffer);
+    reader.check_end_names(false);
+
+    // For each open element: its name and how often each child name occurred
+    let mut stack: Vec<(String, HashMap<String, usize>)> = Vec::new();
+    let mut root: Option<String> = None;
+    let mut best: Option<Vec<String>> = None;
+
+    loop {
+        let (element, empty) = match reader.read_event() {
+            Ok(Event::Start(e)) => (local_name(&e), false),
+            Ok(Event::Empty(e)) => (local_name(&e), true),
+            Ok(Event::End(_)) => {
+                stack.pop();
+                continue;
+            }
+            Ok(Event::Eof) | Err(quick_xml::Error::UnexpectedEof(_)) => break,
+            Ok(_) => continue,
+            Err(e) => return Err(format!("XML error at byte {}: {}", reader.buffer_position(), e)),
+        };
+
+        if root.is_none() {
+            root = Some(element.clone());
+        }
+        let depth = stack.len() + 1;
+        if let Some((_, children)) = stack.last_mut() {
+            let count = children.entry(element.clone()).or_insert(0);
+            *count += 1;
+            if *count == 2 && best.as_ref().is_none_or(|b| depth < b.len()) {
+                let mut path: Vec<String> = stack.iter().map(|(name, _)| name.clone()).collect();
+                path.push(element.clone());
+                best = Some(path);
+            }
+        }
+        if !empty {
+            stack.push((element, HashMap::new()));
+        }
+    }
+
+    best.or_else(|| root.map(|r| vec![r]))
+        .ok_or_else(|| "No XML elements found".to_string())
+}
+
+/// Analyze XML structure without full profiling (for Tree Mode). Records are
+/// tracked like JSON objects, with attributes under "@name" paths.
+pub fn analyze_xml_structure(
+    data: &[u8],
+    record_path: Option<&str>,
+    config: Option<StructureConfig>,
+) -> Result<StructureAnalysis, String> {
+    let config = config.unwrap_or_default();
+    let mut parser = XmlParser::new(record_path, None)?;
+    parser.typed_values = true;
+    parser.buffer.extend_from_slice(data);
+    let records = parser.read_records(true)?;
+
+    let mut analysis = StructureAnalysis::new();
+    let mut path_tracker = PathTracker::new(config.collect_examples);
+    let mut rows_processed = 0;
+    for record in records.iter().take(config.max_sample_rows) {
+        path_tracker.track_value("$", record, 0);
+        rows_processed += 1;
+    }
+
+    analysis.tree = path_tracker.build_tree(rows_processed);
+    analysis.total_paths = path_tracker.paths.len();
+    analysis.max_depth = path_tracker.max_depth;
+    analysis.rows_sampled = rows_processed;
+    analysis.determine_mode();
+
+    Ok(analysis)
+}
+
+impl ChunkParser for XmlParser {
+    type Output = XmlParseResult;
+
+    fn parse_chunk(&mut self, chunk: &[u8]) -> Result<XmlParseResult, String> {
+        XmlParser::parse_chunk(self, chunk)
+    }
+
+    fn flush(&mut self) -> Result<XmlParseResult, String> {
+        XmlParser::flush(self)
+    }
+
+    fn headers(&self) -> &[String] {
+        self.flattener.headers()
+    }
+
+    fn rows(output: &XmlParseResult) -> &[Vec<String>] {
+        &output.rows
+    }
+}
+
+pub type XmlSource = ChunkedSource<XmlParser>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stats::tree::NodeType;
+
+    const CATALOG: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<catalog xmlns:x="urn:example">
+  <meta><generated>2024-05-01</generated></meta>
+  <book id="bk101" x:lang="en">
+    <title>XML Developer's Guide</title>
+    <author><name>Gambardella</name></author>
+    <price currency="USD">44.95</price>
+    <tag>xml</tag>
+    <tag>guide</tag>
+  </book>
+  <book id="bk102">
+    <title><![CDATA[Midnight & Rain]]></title>
+    <author><name>Ralls</name></author>
+    <price currency="EUR">5.95</price>
+  </book>
+  <book id="bk103"/>
+</catalog>"#;
+
+    #[test]
+    fn test_record_path_parsing() {
+        assert_eq!(
+            RecordPath::parse("/catalog/book").unwrap(),
+            RecordPath::Absolute(vec!["catalog".to_string(), "book".to_string()])
+        );
+        assert_eq!(RecordPath::parse("//book").unwrap(), RecordPath::Suffix(vec!["book".to_string()]));
+        assert!(RecordPath::parse("/catalog//book").is_err());
+    }
+
+    #[test]
+    fn test_detects_repeating_record_element() {
+        assert_eq!(detect_record_path(CATALOG.as_bytes()).unwrap(), vec!["catalog", "book"]);
+        assert_eq!(detect_record_path(b"<person><name>A</name></person>").unwrap(), vec!["person"]);
+    }
+
+    #[test]
+    fn test_flattens_elements_and_attributes() {
+        let mut parser = XmlParser::new(None, None).unwrap();
+        let mut rows = parser.parse_chunk(CATALOG.as_bytes()).unwrap().rows;
+        let result = parser.flush().unwrap();
+        rows.extend(result.rows);
+
+        assert_eq!(result.record_path.as_deref(), Some("/catalog/book"));
+        assert_eq!(result.total_rows, 3);
+        let col = |name: &str| result.headers.iter().position(|h| h == name).unwrap();
+        assert_eq!(rows[0][col("@id")], "bk101");
+        assert_eq!(rows[0][col("@lang")], "en");
+        assert_eq!(rows[0][col("author.name")], "Gambardella");
+        assert_eq!(rows[0][col("price.@currency")], "USD");
+        assert_eq!(rows[0][col("price.#text")], "44.95");
+        assert!(rows[0][col("tag")].starts_with("[array:2]"));
+        assert_eq!(rows[1][col("title")], "Midnight & Rain");
+        assert_eq!(rows[2][col("@id")], "bk103");
+        assert!(!result.headers.iter().any(|h| h.starts_with("meta")));
+    }
+
+    #[test]
+    fn test_chunked_input_matches_whole_document() {
+        for size in [1, 7, 64] {
+            let mut parser = XmlParser::new(Some("//book"), None).unwrap();
+            let mut rows = Vec::new();
+            for chunk in CATALOG.as_bytes().chunks(size) {
+                rows.extend(parser.parse_chunk(chunk).unwrap().rows);
+            }
+            let result = parser.flush().unwrap();
+            rows.extend(result.rows);
+
+            assert_eq!(rows.len(), 3);
+            let title = result.headers.iter().position(|h| h == "title").unwrap();
+            let lang = result.headers.iter().position(|h| h == "@lang").unwrap();
+            assert_eq!(rows[0][title], "XML Developer's Guide");
+            assert_eq!(rows[0][lang], "en");
+            assert_eq!(rows[1][title], "Midnight & Rain");
+        }
+    }
+
+    #[test]
+    fn test_checkpoint_inside_a_record() {
+        use crate::parser::source::ProfileDriver;
+        use crate::stats::config::ProfilerConfig;
+
+        let split = CATALOG.find("<author><name>Ralls").unwrap();
+        let mut driver = ProfileDriver::new(XmlSource::new(XmlParser::new(None, None).unwrap()), ProfilerConfig::default());
+        driver.push(&CATALOG.as_bytes()[..split]).unwrap();
+        let checkpoint = driver.checkpoint().unwrap();
+
+        let mut resumed = ProfileDriver::<XmlSource>::resume(&checkpoint).unwrap();
+        resumed.push(&CATALOG.as_bytes()[split..]).unwrap();
+        let result = resumed.finish().unwrap();
+
+        assert_eq!(result.total_rows, 3);
+        let title = result.column_profiles.iter().find(|c| c.name == "title").unwrap();
+        assert_eq!(title.base_stats.missing, 1);
+        assert_eq!(title.sample_values, vec!["XML Developer's Guide", "Midnight & Rain"]);
+    }
+
+    #[test]
+    fn test_malformed_document_is_an_error() {
+        let mut parser = XmlParser::new(Some("/a/b"), None).unwrap();
+        assert!(parser.parse_chunk(b"<a><b x=1></b></a>").is_err());
+
+        let mut parser = XmlParser::new(Some("/a/b"), None).unwrap();
+        parser.parse_chunk(b"<a><b><c>1</c>").unwrap();
+        assert!(parser.flush().is_err());
+    }
+
+    #[test]
+    fn test_analyze_xml_structure() {
+        let analysis = analyze_xml_structure(CATALOG.as_bytes(), None, None).unwrap();
+        assert_eq!(analysis.rows_sampled, 3);
+        assert_eq!(analysis.max_depth, 2);
+
+        let find = |path: &str| analysis.tree.children.iter().find(|n| n.path == path).unwrap();
+        assert_eq!(find("$.@id").population, 100.0);
+        assert_eq!(find("$.tag").data_type, NodeType::Array);
+        let price = find("$.price");
+        assert_eq!(price.data_type, NodeType::Object);
+        let amount = price.children.iter().find(|n| n.path == "$.price.#text").unwrap();
+        assert_eq!(amount.data_type, NodeType::Number);
+    }
+}
@@ -1,13 +1,43 @@
 use super::{QualityIssue, Severity};
+use serde::{Deserialize, Serialize};
 use std::collections::HashSet;
+use std::fmt::Write;
 
-/// Duplicate row detector using hash-based exact matching
+/// Duplicate row detector. Rows are compared exactly, so every distinct row
+/// is kept in full; checkpoints are compressed to keep that state small.
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct DuplicateDetector {
-    seen_rows: HashSet<Vec<String>>,
+    seen_rows: HashSet<String>,
     duplicate_count: u64,
     total_rows: u64,
 }
 
+/// Exact key of a row: its non-empty cells with their column names, sorted
+/// and length-prefixed. It does not depend on the column order, and a row
+/// read before a column existed matches the same row with that column empty.
+fn row_key(headers: &[String], row: &[String]) -> String {
+    let mut cells: Vec<(Option<&str>, usize, &str)> = row.iter().enumerate()
+        .filter(|(_, value)| !value.is_empty())
+        .map(|(idx, value)| (headers.get(idx).map(String::as_str), idx, value.as_str()))
+        .collect();
+    // Named cells are ordered by name, unnamed ones by position
+    cells.sort_by(|a, b| match (a.0, b.0) {
+        (Some(x), Some(y)) => (x, a.2).cmp(&(y, b.2)),
+        _ => (a.0, a.1).cmp(&(b.0, b.1)),
+    });
+
+    let mut key = String::new();
+    for (name, idx, value) in cells {
+        // Writing to a String cannot fail
+        let _ = match name {
+            Some(name) => write!(key, "n{}:{}", name.len(), name),
+            None => write!(key, "i{}:", idx),
+        };
+        let _ = write!(key, "{}:{}", value.len(), value);
+    }
+    key
+}
+
 impl DuplicateDetector {
     pub fn new() -> Self {
         Self {
@@ -17,18 +47,30 @@ impl DuplicateDetector {
         }
     }
     
-    /// Process a batch of rows
-    pub fn process_batch(&mut self, rows: &[Vec<String>]) {
+    /// Process a batch of rows whose columns are named by `headers`
+    pub fn process_batch(&mut self, headers: &[String], rows: &[Vec<String>]) {
         for row in rows {
             self.total_rows += 1;
             
-            if !self.seen_rows.insert(row.clone()) {
+            if !self.seen_rows.insert(row_key(headers, row)) {
                 // Row already exists - it's a duplicate
                 self.duplicate_count += 1;
             }
         }
     }
     
+    /// Adds the rows seen by another detector. A row seen by both counts as
+    /// a duplicate once more, as if the rows had been processed in one pass.
+    pub fn merge(&mut self, other: &DuplicateDetector) {
+        self.total_rows += other.total_rows;
+        self.duplicate_count += other.duplicate_count;
+        for row in &other.seen_rows {
+            if !self.seen_rows.insert(row.clone()) {
+                self.duplicate_count += 1;
+            }
+        }
+    }
+
     /// Get duplicate count
     pub fn duplicate_count(&self) -> u64 {
         self.duplicate_count
@@ -83,6 +125,7 @@ mod tests {
     #[test]
     fn test_duplicate_detection() {
         let mut detector = DuplicateDetector::new();
+        let headers = vec!["x".to_string(), "y".to_string(), "z".to_string()];
         
         let rows = vec![
             vec!["a".to_string(), "b".to_string(), "c".to_string()],
@@ -92,7 +135,7 @@ mod tests {
             vec!["d".to_string(), "e".to_string(), "f".to_string()], // duplicate
         ];
         
-        detector.process_batch(&rows);
+        detector.process_batch(&headers, &rows);
         
         assert_eq!(detector.total_rows(), 5);
         assert_eq!(detector.duplicate_count(), 2);
@@ -102,6 +145,7 @@ mod tests {
     #[test]
     fn test_no_duplicates() {
         let mut detector = DuplicateDetector::new();
+        let headers = vec!["x".to_string(), "y".to_string()];
         
         let rows = vec![
             vec!["a".to_string(), "b".to_string()],
@@ -109,12 +153,49 @@ mod tests {
             vec!["e".to_string(), "f".to_string()],
         ];
         
-        detector.process_batch(&rows);
+        detector.process_batch(&headers, &rows);
         
         assert_eq!(detector.duplicate_count(), 0);
         assert_eq!(detector.duplicate_percentage(), 0.0);
     }
 
+    #[test]
+    fn test_merge_counts_rows_seen_by_both() {
+        let row = |v: &str| vec![v.to_string()];
+        let headers = vec!["v".to_string()];
+        let mut first = DuplicateDetector::new();
+        first.process_batch(&headers, &[row("a"), row("b"), row("a")]);
+        let mut second = DuplicateDetector::new();
+        second.process_batch(&headers, &[row("b"), row("c")]);
+
+        first.merge(&second);
+        assert_eq!(first.total_rows(), 5);
+        assert_eq!(first.duplicate_count(), 2);
+    }
+
+    #[test]
+    fn test_row_keys_ignore_column_order_and_missing_columns() {
+        let names = |names: &[&str]| names.iter().map(|n| n.to_string()).collect::<Vec<_>>();
+        let row = |values: &[&str]| values.iter().map(|v| v.to_string()).collect::<Vec<_>>();
+        let mut detector = DuplicateDetector::new();
+        detector.process_batch(&names(&["a", "b"]), &[row(&["1", "2"])]);
+        detector.process_batch(&names(&["b", "a", "c"]), &[row(&["2", "1", ""]), row(&["1", "2", ""])]);
+        assert_eq!(detector.duplicate_count(), 1);
+    }
+
+    #[test]
+    fn test_row_keys_are_exact() {
+        let names = |names: &[&str]| names.iter().map(|n| n.to_string()).collect::<Vec<_>>();
+        let row = |values: &[&str]| values.iter().map(|v| v.to_string()).collect::<Vec<_>>();
+        let mut detector = DuplicateDetector::new();
+        // Cells that would run together without length prefixes
+        detector.process_batch(&names(&["a", "b"]), &[row(&["1:2", "3"]), row(&["1", "2:3"])]);
+        detector.process_batch(&names(&["ab"]), &[row(&["1"])]);
+        detector.process_batch(&names(&["a"]), &[row(&["b1"])]);
+        assert_eq!(detector.duplicate_count(), 0);
+        assert_eq!(detector.total_rows(), 4);
+    }
+
     #[test]
     fn test_duplicate_issues_severity() {
         // High percentage - error
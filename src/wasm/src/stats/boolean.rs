@@ -0,0 +1,83 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use ts_rs::TS;
+use crate::stats::categorical::FreqEntry;
+
+/// Distinct spellings counted; case variants of the vocabulary rarely exceed it.
+const MAX_SPELLINGS: usize = 32;
+
+#[derive(Serialize, Debug, Clone, TS)]
+#[ts(export)]
+pub struct BooleanStats {
+    pub true_count: u64,
+    pub false_count: u64,
+    pub true_ratio: f64,
+    /// Raw values seen for each side, e.g. "Y", "yes", "1"
+    pub spellings: Vec<FreqEntry>,
+    /// The column was inferred as Integer but only holds values from the
+    /// boolean vocabularies (`0` and `1` by default)
+    pub candidate_flag: bool,
+}
+
+/// Counts values found in the configured true/false vocabularies.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct BooleanAccumulator {
+    true_count: u64,
+    false_count: u64,
+    spellings: BTreeMap<String, u64>,
+}
+
+impl BooleanAccumulator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records `trimmed` if it is a boolean spelling, `value` being its
+    /// meaning under `ProfilerConfig::boolean_value`.
+    pub fn update(&mut self, trimmed: &str, value: bool) {
+        if value { self.true_count += 1 } else { self.false_count += 1 }
+        if let Some(count) = self.spellings.get_mut(trimmed) {
+            *count += 1;
+        } else if self.spellings.len() < MAX_SPELLINGS {
+            self.spellings.insert(trimmed.to_string(), 1);
+        }
+    }
+
+    /// Values that were boolean spellings.
+    pub fn count(&self) -> u64 {
+        self.true_count + self.false_count
+    }
+
+    pub fn merge(&mut self, other: &BooleanAccumulator) {
+        self.true_count += other.true_count;
+        self.false_count += other.false_count;
+        for (spelling, &count) in &other.spellings {
+            if let Some(existing) = self.spellings.get_mut(spelling) {
+                *existing += count;
+            } else if self.spellings.len() < MAX_SPELLINGS {
+                self.spellings.insert(spelling.clone(), count);
+            }
+        }
+    }
+
+    pub fn finalize(&self, candidate_flag: bool) -> Option<BooleanStats> {
+        let total = self.count();
+        if total == 0 {
+            return None;
+        }
+        let mut spellings: Vec<FreqEntry> = self.spellings.iter().map(|(value, &count)| FreqEntry {
+            value: value.clone(),
+            count,
+            percentage: (count as f64 / total as f64) * 100.0,
+        }).collect();
+        spellings.sort_by_key(|entry| std::cmp::Reverse(entry.count));
+
+        Some(BooleanStats {
+            true_count: self.true_count,
+            false_count: self.false_count,
+            true_ratio: self.true_count as f64 / total as f64,
+            spellings,
+            candidate_flag,
+        })
+    }
+}
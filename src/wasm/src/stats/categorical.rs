@@ -1,4 +1,4 @@
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use ts_rs::TS;
 
@@ -17,19 +17,21 @@ pub struct CategoricalStats {
     pub unique_count: u64,
 }
 
-#[derive(Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct CategoricalAccumulator {
     counts: HashMap<String, u64>,
     total_count: u64,
     max_unique: usize,
+    top_n: usize,
 }
 
 impl CategoricalAccumulator {
-    pub fn new(max_unique: usize) -> Self {
+    pub fn new(max_unique: usize, top_n: usize) -> Self {
         Self {
             counts: HashMap::new(),
             total_count: 0,
             max_unique,
+            top_n,
         }
     }
 
@@ -42,6 +44,24 @@ impl CategoricalAccumulator {
         }
     }
 
+    /// Adds the counts of `other`. Values new to this accumulator are kept
+    /// while there is room, as in `update`.
+    pub fn merge(&mut self, other: &CategoricalAccumulator) {
+        self.total_count += other.total_count;
+        for (value, &count) in &other.counts {
+            if let Some(existing) = self.counts.get_mut(value) {
+                *existing += count;
+            } else if self.counts.len() < self.max_unique {
+                self.counts.insert(value.clone(), count);
+            }
+        }
+    }
+
+    /// Whether every value was counted, i.e. `max_unique` was never exceeded.
+    pub fn is_exact(&self) -> bool {
+        self.counts.values().sum::<u64>() == self.total_count
+    }
+
     pub fn finalize(&self) -> CategoricalStats {
         let mut entries: Vec<FreqEntry> = self.counts.iter().map(|(val, &count)| {
             FreqEntry {
@@ -51,10 +71,11 @@ impl CategoricalAccumulator {
             }
         }).collect();
 
-        entries.sort_by(|a, b| b.count.cmp(&a.count));
+        // Ties broken by value so the report does not depend on hash order
+        entries.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.value.cmp(&b.value)));
         
         CategoricalStats {
-            top_values: entries.into_iter().take(10).collect(),
+            top_values: entries.into_iter().take(self.top_n).collect(),
             unique_count: self.counts.len() as u64,
         }
     }
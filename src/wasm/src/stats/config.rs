@@ -0,0 +1,211 @@
+use chrono::{DateTime, NaiveDate, NaiveDateTime};
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+use wasm_bindgen::JsValue;
+use crate::stats::outliers::OutlierMethod;
+use crate::stats::sampling::SamplingMode;
+
+/// Tunables for column profiling. Every profiler constructor accepts one
+/// (any field may be omitted from JS to keep its default), and the
+/// `ProfilerResult` echoes the configuration that produced it.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, TS)]
+#[serde(default)]
+#[ts(export)]
+pub struct ProfilerConfig {
+    /// Values counted as missing, compared after trimming and ignoring ASCII case
+    pub null_tokens: Vec<String>,
+    /// Distinct non-null values kept as `sample_values` for display
+    pub max_sample_values: usize,
+    /// Non-null values kept per column for PII pattern detection
+    pub max_pii_samples: usize,
+    /// Reservoir size for the numeric sample behind histograms and the MAD
+    pub histogram_reservoir_size: usize,
+    /// Accuracy of the KLL sketch behind the median and percentiles; the
+    /// rank error is about 1.3% at the default 200 and shrinks roughly as 1/k
+    pub quantile_sketch_k: usize,
+    /// Distinct values counted exactly by the categorical accumulator
+    pub max_categorical_keys: usize,
+    /// Entries reported in `categorical_stats.top_values`
+    pub top_values: usize,
+    /// HyperLogLog++ precision for distinct estimates (4 to 18)
+    pub hll_precision: u8,
+    /// Cap on each of `missing_rows` and `outlier_rows`, and the extreme
+    /// values kept on each side of a column to find its outliers
+    pub max_anomaly_rows: usize,
+    /// How outlier fences are computed; `outlier_stats` reports the result
+    pub outlier_method: OutlierMethod,
+    /// Values read as `true`, compared after trimming and ignoring ASCII case
+    pub true_tokens: Vec<String>,
+    /// Values read as `false`, compared like `true_tokens`
+    pub false_tokens: Vec<String>,
+    /// Which rows are profiled; `sampling` in the result reports the coverage
+    pub sampling: SamplingMode,
+    /// Stop reading after this many rows
+    pub max_rows: Option<usize>,
+    /// Stop reading after this many bytes. Input is cut at the limit and the
+    /// record it splits is dropped, so the read ends on a record boundary.
+    /// Applies to streaming formats; whole-file formats need their complete
+    /// input.
+    pub max_bytes: Option<u64>,
+    /// Seeds all randomness (sampling, reservoirs, sketch hashing), so equal
+    /// input and configuration give identical results
+    pub seed: u64,
+    /// Instant that `temporal_stats.future_count` is measured against, as an
+    /// ISO 8601 date or timestamp. Without it `future_count` is not computed,
+    /// as the clock would make results differ between runs.
+    pub reference_time: Option<String>,
+    /// Count distinct values and compute quantiles from every value rather
+    /// than sketches, while a column stays within `exact_memory_limit`
+    pub exact_mode: bool,
+    /// Bytes per column kept for `exact_mode` before falling back to sketches
+    pub exact_memory_limit: usize,
+}
+
+impl Default for ProfilerConfig {
+    fn default() -> Self {
+        Self {
+            null_tokens: vec![String::new(), "null".to_string(), "n/a".to_string()],
+            max_sample_values: 5,
+            max_pii_samples: 100,
+            histogram_reservoir_size: 1000,
+            quantile_sketch_k: 200,
+            max_categorical_keys: 1000,
+            top_values: 10,
+            hll_precision: 12,
+            max_anomaly_rows: 1000,
+            outlier_method: OutlierMethod::default(),
+            true_tokens: ["true", "t", "yes", "y", "on", "1"].iter().map(|t| t.to_string()).collect(),
+            false_tokens: ["false", "f", "no", "n", "off", "0"].iter().map(|t| t.to_string()).collect(),
+            sampling: SamplingMode::Full,
+            max_rows: None,
+            max_bytes: None,
+            seed: 0,
+            reference_time: None,
+            exact_mode: false,
+            exact_memory_limit: 16 * 1024 * 1024,
+        }
+    }
+}
+
+impl ProfilerConfig {
+    /// Reads a config passed from JS; `undefined` or `null` means defaults.
+    pub fn from_js(value: Option<JsValue>) -> Result<Self, String> {
+        let config: Self = match value {
+            Some(value) => serde_wasm_bindgen::from_value(value)
+                .map_err(|e| format!("Invalid profiler config: {}", e))?,
+            None => Self::default(),
+        };
+        config.validate()?;
+        Ok(config)
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        if !(4..=18).contains(&self.hll_precision) {
+            return Err(format!("hll_precision must be between 4 and 18, got {}", self.hll_precision));
+        }
+        if self.histogram_reservoir_size == 0 {
+            return Err("histogram_reservoir_size must be at least 1".to_string());
+        }
+        if self.quantile_sketch_k < 8 {
+            return Err(format!("quantile_sketch_k must be at least 8, got {}", self.quantile_sketch_k));
+        }
+        self.outlier_method.validate()?;
+        match self.sampling {
+            SamplingMode::Head { rows: 0 } => return Err("sampling.rows must be at least 1".to_string()),
+            SamplingMode::Systematic { every: 0 } => return Err("sampling.every must be at least 1".to_string()),
+            SamplingMode::Bernoulli { rate, .. } if !(rate > 0.0 && rate <= 1.0) => {
+                return Err(format!("sampling.rate must be in (0, 1], got {}", rate));
+            }
+            _ => {}
+        }
+        if self.max_rows == Some(0) || self.max_bytes == Some(0) {
+            return Err("max_rows and max_bytes must be at least 1".to_string());
+        }
+        if let Some(ref time) = self.reference_time {
+            if self.reference_time().is_none() {
+                return Err(format!("reference_time must be an ISO 8601 date or timestamp, got '{}'", time));
+            }
+        }
+        if let Some(token) = self.true_tokens.iter().find(|t| self.false_tokens.iter().any(|f| f.trim().eq_ignore_ascii_case(t.trim()))) {
+            return Err(format!("'{}' is in both true_tokens and false_tokens", token));
+        }
+        Ok(())
+    }
+
+    pub fn reference_time(&self) -> Option<NaiveDateTime> {
+        let time = self.reference_time.as_deref()?.trim();
+        DateTime::parse_from_rfc3339(time).map(|t| t.naive_utc())
+            .or_else(|_| time.parse::<NaiveDateTime>())
+            .or_else(|_| time.parse::<NaiveDate>().map(|d| d.and_time(Default::default())))
+            .ok()
+    }
+
+    /// Whether rows may be skipped or reading may stop early.
+    pub fn is_sampled(&self) -> bool {
+        self.sampling != SamplingMode::Full || self.max_rows.is_some() || self.max_bytes.is_some()
+    }
+
+    pub fn is_null_token(&self, trimmed: &str) -> bool {
+        self.null_tokens.iter().any(|token| token.trim().eq_ignore_ascii_case(trimmed))
+    }
+
+    /// The boolean a value spells under `true_tokens`/`false_tokens`, if any.
+    pub fn boolean_value(&self, trimmed: &str) -> Option<bool> {
+        let matches = |tokens: &[String]| tokens.iter().any(|token| token.trim().eq_ignore_ascii_case(trimmed));
+        if matches(&self.true_tokens) {
+            Some(true)
+        } else if matches(&self.false_tokens) {
+            Some(false)
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_partial_config_keeps_defaults() {
+        let config: ProfilerConfig = serde_json::from_str(r#"{"top_values": 3, "null_tokens": ["-"]}"#).unwrap();
+        assert_eq!(config.top_values, 3);
+        assert_eq!(config.hll_precision, 12);
+        assert!(config.is_null_token("-"));
+        assert!(!config.is_null_token("NULL"));
+        assert!(ProfilerConfig::default().is_null_token("N/A"));
+    }
+
+    #[test]
+    fn test_validate_rejects_out_of_range_values() {
+        assert!(ProfilerConfig::default().validate().is_ok());
+        let config = ProfilerConfig { hll_precision: 20, ..ProfilerConfig::default() };
+        assert!(config.validate().is_err());
+        let config = ProfilerConfig { outlier_method: OutlierMethod::ZScore { threshold: 0.0 }, ..ProfilerConfig::default() };
+        assert!(config.validate().is_err());
+        let config = ProfilerConfig { false_tokens: vec!["Yes".to_string()], ..ProfilerConfig::default() };
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_profiler_applies_config() {
+        use crate::stats::profiler::Profiler;
+
+        let config = ProfilerConfig {
+            null_tokens: vec!["-".to_string()],
+            top_values: 2,
+            max_sample_values: 1,
+            ..ProfilerConfig::default()
+        };
+        let mut profiler = Profiler::with_config(vec!["code".to_string()], config.clone());
+        let rows: Vec<Vec<String>> = ["a", "b", "c", "-", "null"].iter().map(|v| vec![v.to_string()]).collect();
+        profiler.update_batch(&rows);
+        let result = profiler.finalize();
+
+        let column = &result.column_profiles[0];
+        assert_eq!(column.base_stats.missing, 1);
+        assert_eq!(column.sample_values.len(), 1);
+        assert_eq!(column.categorical_stats.as_ref().unwrap().top_values.len(), 2);
+        assert_eq!(result.config, config);
+    }
+}
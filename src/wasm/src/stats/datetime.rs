@@ -0,0 +1,445 @@
+//! Date, time and timestamp detection over a library of candidate formats.
+
+use chrono::{DateTime, Datelike, NaiveDate, NaiveDateTime, NaiveTime};
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+use crate::stats::temporal::TemporalAccumulator;
+use crate::stats::types::DataType;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Kind {
+    Date,
+    Time,
+    Timestamp,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Parser {
+    Rfc3339,
+    Rfc2822,
+    Zoned,
+    Timestamp,
+    Date,
+    Time,
+    EpochSeconds,
+    EpochMillis,
+}
+
+impl Parser {
+    fn kind(self) -> Kind {
+        match self {
+            Parser::Date => Kind::Date,
+            Parser::Time => Kind::Time,
+            _ => Kind::Timestamp,
+        }
+    }
+
+    fn has_timezone(self) -> bool {
+        matches!(self, Parser::Rfc3339 | Parser::Rfc2822 | Parser::Zoned)
+    }
+}
+
+/// A candidate format: a chrono pattern, or a name for the formats chrono
+/// parses by other means.
+struct Candidate {
+    format: &'static str,
+    parser: Parser,
+}
+
+impl Candidate {
+    fn is_month_first(&self) -> bool {
+        self.format.starts_with("%m")
+    }
+
+    fn is_day_first(&self) -> bool {
+        self.format.starts_with("%d")
+    }
+
+    /// Epoch forms, which any integer identifier of the right width in
+    /// range passes for.
+    fn is_epoch(&self) -> bool {
+        matches!(self.parser, Parser::EpochSeconds | Parser::EpochMillis)
+    }
+}
+
+const fn candidate(format: &'static str, parser: Parser) -> Candidate {
+    Candidate { format, parser }
+}
+
+/// Formats tried for every value. Where a value matches several, the first
+/// one decides whether it is a date, time or timestamp. Month-first formats
+/// precede their day-first twins, so ambiguous columns report month-first.
+const CANDIDATES: &[Candidate] = &[
+    candidate("%Y-%m-%dT%H:%M:%S%.f%:z", Parser::Rfc3339),
+    candidate("%a, %d %b %Y %H:%M:%S %z", Parser::Rfc2822),
+    candidate("%Y-%m-%dT%H:%M:%S%.f%z", Parser::Zoned),
+    candidate("%Y-%m-%d %H:%M:%S%.f %z", Parser::Zoned),
+    candidate("%Y-%m-%dT%H:%M:%S%.f", Parser::Timestamp),
+    candidate("%Y-%m-%d %H:%M:%S%.f", Parser::Timestamp),
+    candidate("%Y-%m-%dT%H:%M", Parser::Timestamp),
+    candidate("%Y-%m-%d %H:%M", Parser::Timestamp),
+    candidate("%Y/%m/%d %H:%M:%S", Parser::Timestamp),
+    candidate("%m/%d/%Y %H:%M:%S", Parser::Timestamp),
+    candidate("%d/%m/%Y %H:%M:%S", Parser::Timestamp),
+    candidate("%m/%d/%Y %H:%M", Parser::Timestamp),
+    candidate("%d/%m/%Y %H:%M", Parser::Timestamp),
+    candidate("%m/%d/%Y %I:%M %p", Parser::Timestamp),
+    candidate("%d.%m.%Y %H:%M:%S", Parser::Timestamp),
+    candidate("%Y%m%dT%H%M%S", Parser::Timestamp),
+    candidate("%Y%m%d%H%M%S", Parser::Timestamp),
+    candidate("%Y-%m-%d", Parser::Date),
+    candidate("%Y/%m/%d", Parser::Date),
+    candidate("%m/%d/%Y", Parser::Date),
+    candidate("%d/%m/%Y", Parser::Date),
+    candidate("%m-%d-%Y", Parser::Date),
+    candidate("%d-%m-%Y", Parser::Date),
+    candidate("%d.%m.%Y", Parser::Date),
+    candidate("%Y%m%d", Parser::Date),
+    candidate("%b %d, %Y", Parser::Date),
+    candidate("%B %d, %Y", Parser::Date),
+    candidate("%d %b %Y", Parser::Date),
+    candidate("%d %B %Y", Parser::Date),
+    candidate("%d-%b-%Y", Parser::Date),
+    candidate("%a, %d %b %Y", Parser::Date),
+    candidate("%H:%M:%S%.f", Parser::Time),
+    candidate("%H:%M", Parser::Time),
+    candidate("%I:%M:%S %p", Parser::Time),
+    candidate("%I:%M %p", Parser::Time),
+    candidate("epoch_seconds", Parser::EpochSeconds),
+    candidate("epoch_millis", Parser::EpochMillis),
+];
+
+/// Every candidate, as a bit set over `CANDIDATES`.
+const ALL_CANDIDATES: u64 = u64::MAX >> (64 - CANDIDATES.len());
+const _: () = assert!(CANDIDATES.len() <= 64);
+
+/// Epoch values are only accepted between 2000-01-01 and 2100-01-01, as
+/// wider ranges would claim most 10 and 13 digit identifiers.
+const EPOCH_SECONDS_RANGE: std::ops::Range<i64> = 946_684_800..4_102_444_800;
+/// Compact all-digit forms like `20261016` must fall in this year range, so
+/// only identifiers that read as plausible dates throughout pass for them.
+const COMPACT_YEARS: std::ops::RangeInclusive<i32> = 1900..=2100;
+
+/// Parses `value` with a candidate format. Instants with an offset are
+/// converted to UTC; times of day are placed on 1970-01-01.
+fn parse(candidate: &Candidate, value: &str) -> Option<NaiveDateTime> {
+    let format = candidate.format;
+    let parsed = match candidate.parser {
+        Parser::Rfc3339 => return DateTime::parse_from_rfc3339(value).ok().map(|t| t.naive_utc()),
+        Parser::Rfc2822 => return DateTime::parse_from_rfc2822(value).ok().map(|t| t.naive_utc()),
+        Parser::Zoned => return DateTime::parse_from_str(value, format).ok().map(|t| t.naive_utc()),
+        Parser::Time => return NaiveTime::parse_from_str(value, format).ok().map(|t| NaiveDate::default().and_time(t)),
+        Parser::EpochSeconds | Parser::EpochMillis => return parse_epoch(candidate.parser, value),
+        Parser::Timestamp => NaiveDateTime::parse_from_str(value, format).ok()?,
+        Parser::Date => NaiveDate::parse_from_str(value, format).ok()?.and_time(NaiveTime::MIN),
+    };
+    let years = if value.bytes().all(|b| b.is_ascii_digit() || b == b'T') { COMPACT_YEARS } else { 1000..=9999 };
+    years.contains(&parsed.year()).then_some(parsed)
+}
+
+fn parse_epoch(parser: Parser, value: &str) -> Option<NaiveDateTime> {
+    let (digits, per_second) = if let Parser::EpochMillis = parser { (13, 1000) } else { (10, 1) };
+    let number = value.parse::<i64>().ok().filter(|_| value.len() == digits)?;
+    let seconds = number.div_euclid(per_second);
+    if !EPOCH_SECONDS_RANGE.contains(&seconds) {
+        return None;
+    }
+    let nanos = (number.rem_euclid(per_second) * (1_000_000_000 / per_second)) as u32;
+    DateTime::from_timestamp(seconds, nanos).map(|t| t.naive_utc())
+}
+
+/// Cheap test that rules out most non-temporal values before any parsing.
+fn may_be_temporal(value: &str) -> bool {
+    let len = value.len();
+    if !(4..=40).contains(&len) || !value.bytes().any(|b| b.is_ascii_digit()) {
+        return false;
+    }
+    if value.bytes().all(|b| b.is_ascii_digit()) {
+        // Compact dates, compact timestamps and epoch seconds or millis
+        return matches!(len, 8 | 10 | 13 | 14);
+    }
+    value.bytes().next().is_some_and(|b| b.is_ascii_alphanumeric())
+}
+
+/// Whether a column name suggests an instant, which is required before an
+/// integer column is read as epoch timestamps.
+pub fn name_suggests_time(name: &str) -> bool {
+    let lower = name.to_lowercase();
+    lower.contains("time") || lower.contains("date") || lower.contains("epoch")
+        || lower.split(|c: char| !c.is_ascii_alphanumeric())
+            .any(|token| matches!(token, "ts" | "at" | "dt" | "day" | "created" | "updated" | "modified"))
+}
+
+/// How the candidates in a bit set read one value.
+struct Reading {
+    matched: u64,
+    // Kind and instant of the first match, and of the first match that is
+    // not month-first
+    parser: Parser,
+    instant: NaiveDateTime,
+    day_first: Option<NaiveDateTime>,
+    zoned: bool,
+}
+
+/// Parses `value` with the candidates in `candidates`; None if none matches.
+fn read(value: &str, candidates: u64) -> Option<Reading> {
+    let mut reading: Option<Reading> = None;
+    for (idx, candidate) in CANDIDATES.iter().enumerate() {
+        if candidates & (1 << idx) == 0 {
+            continue;
+        }
+        let Some(instant) = parse(candidate, value) else {
+            continue;
+        };
+        let reading = reading.get_or_insert(Reading {
+            matched: 0,
+            parser: candidate.parser,
+            instant,
+            day_first: None,
+            zoned: false,
+        });
+        reading.matched |= 1 << idx;
+        if !candidate.is_month_first() {
+            reading.day_first.get_or_insert(instant);
+        }
+        reading.zoned |= candidate.parser.has_timezone();
+    }
+    reading
+}
+
+/// The format detected for a date, time or timestamp column.
+#[derive(Serialize, Debug, Clone, PartialEq, TS)]
+#[ts(export)]
+pub struct DatetimeFormat {
+    /// chrono (strftime) pattern matching the most values, or
+    /// "epoch_seconds" / "epoch_millis"
+    pub format: String,
+    /// Values matching `format`
+    pub matches: u64,
+    /// Another format matching just as many values, e.g. "%d/%m/%Y" next to
+    /// "%m/%d/%Y" when no day exceeds 12
+    pub ambiguous_with: Option<String>,
+    /// Values carrying a UTC offset or zone designator
+    pub with_timezone: u64,
+}
+
+/// Counts, per candidate format, the values it parses, and accumulates the
+/// `TemporalStats` of the values as the winning format reads them.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct DatetimeAccumulator {
+    matches: Vec<u64>,
+    dates: u64,
+    times: u64,
+    timestamps: u64,
+    with_timezone: u64,
+    // Bit set of the candidates that matched every value since the last
+    // value none of them matched; only these are tried first
+    live: u64,
+    // Set once a value matched no format: the column cannot be temporal
+    rejected: bool,
+    // Values read month-first where ambiguous, and day-first; the winning
+    // format picks one once the column is finalized
+    month_first: TemporalAccumulator,
+    day_first: TemporalAccumulator,
+}
+
+impl DatetimeAccumulator {
+    /// `reference_time` is `ProfilerConfig::reference_time`.
+    pub fn new(reference_time: Option<NaiveDateTime>) -> Self {
+        Self {
+            matches: vec![0; CANDIDATES.len()],
+            dates: 0,
+            times: 0,
+            timestamps: 0,
+            with_timezone: 0,
+            live: ALL_CANDIDATES,
+            rejected: false,
+            month_first: TemporalAccumulator::new(reference_time),
+            day_first: TemporalAccumulator::new(reference_time),
+        }
+    }
+
+    /// Records a non-null value.
+    pub fn update(&mut self, value: &str) {
+        if self.rejected {
+            return;
+        }
+        if !may_be_temporal(value) {
+            self.rejected = true;
+            return;
+        }
+
+        // A column keeps to a few formats, so the others are dropped once they
+        // fail; all are tried again for a value none of the live ones reads
+        let reading = match read(value, self.live) {
+            Some(reading) => Some(reading),
+            None if self.live == ALL_CANDIDATES => None,
+            None => read(value, ALL_CANDIDATES),
+        };
+        let Some(Reading { matched, parser, instant, day_first, zoned }) = reading else {
+            self.rejected = true;
+            return;
+        };
+        for (idx, count) in self.matches.iter_mut().enumerate() {
+            if matched & (1 << idx) != 0 {
+                *count += 1;
+            }
+        }
+        self.live = matched;
+        self.month_first.update(instant);
+        self.day_first.update(day_first.unwrap_or(instant));
+        match parser.kind() {
+            Kind::Date => self.dates += 1,
+            Kind::Time => self.times += 1,
+            Kind::Timestamp => self.timestamps += 1,
+        }
+        if zoned {
+            self.with_timezone += 1;
+        }
+    }
+
+    pub fn merge(&mut self, other: &DatetimeAccumulator) {
+        for (mine, theirs) in self.matches.iter_mut().zip(&other.matches) {
+            *mine += theirs;
+        }
+        self.dates += other.dates;
+        self.times += other.times;
+        self.timestamps += other.timestamps;
+        self.with_timezone += other.with_timezone;
+        self.live &= other.live;
+        self.rejected |= other.rejected;
+        self.month_first.merge(&other.month_first);
+        self.day_first.merge(&other.day_first);
+    }
+
+    /// The values as read by the winning format: day-first when it is a
+    /// day-first format such as `%d/%m/%Y`, else month-first.
+    pub fn temporal(&self) -> &TemporalAccumulator {
+        match self.best() {
+            Some((best, _)) if CANDIDATES[best].is_day_first() => &self.day_first,
+            _ => &self.month_first,
+        }
+    }
+
+    /// The temporal type of a column with `total_valid` non-null values, if
+    /// every one of them was a date, time or timestamp. Dates mixed with
+    /// timestamps make a timestamp column.
+    pub fn data_type(&self, total_valid: u64, column_name: &str) -> Option<DataType> {
+        if self.rejected || total_valid == 0 || self.dates + self.times + self.timestamps < total_valid {
+            return None;
+        }
+        let (best, _) = self.best()?;
+        if CANDIDATES[best].is_epoch() && !name_suggests_time(column_name) {
+            return None;
+        }
+        if self.times == total_valid {
+            Some(DataType::Time)
+        } else if self.dates == total_valid {
+            Some(DataType::Date)
+        } else if self.times == 0 {
+            Some(DataType::Timestamp)
+        } else {
+            None
+        }
+    }
+
+    pub fn format(&self) -> Option<DatetimeFormat> {
+        let (best, ambiguous_with) = self.best()?;
+        Some(DatetimeFormat {
+            format: CANDIDATES[best].format.to_string(),
+            matches: self.matches[best],
+            ambiguous_with: ambiguous_with.map(|idx| CANDIDATES[idx].format.to_string()),
+            with_timezone: self.with_timezone,
+        })
+    }
+
+    /// Index of the format matching the most values (the earliest on ties),
+    /// and of the next format of the same kind matching as many.
+    fn best(&self) -> Option<(usize, Option<usize>)> {
+        let best = (0..CANDIDATES.len())
+            .filter(|&idx| self.matches[idx] > 0)
+            .max_by_key(|&idx| (self.matches[idx], std::cmp::Reverse(idx)))?;
+        let tie = (best + 1..CANDIDATES.len()).find(|&idx| {
+            self.matches[idx] == self.matches[best] && CANDIDATES[idx].parser.kind() == CANDIDATES[best].parser.kind()
+        });
+        Some((best, tie))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn infer(name: &str, values: &[&str]) -> (Option<DataType>, Option<DatetimeFormat>) {
+        let mut acc = DatetimeAccumulator::new(None);
+        for value in values {
+            acc.update(value);
+        }
+        (acc.data_type(values.len() as u64, name), acc.format())
+    }
+
+    #[test]
+    fn test_rejects_look_alikes() {
+        assert_eq!(infer("phone", &["555-123-4567", "555-987-6543"]).0, None);
+        assert_eq!(infer("sku", &["AB-2024-0001", "AB-2024-0002"]).0, None);
+        assert_eq!(infer("range", &["10-20", "1/2"]).0, None);
+        assert_eq!(infer("order_id", &["1697452800", "1697452801"]).0, None);
+    }
+
+    #[test]
+    fn test_detects_formats() {
+        let (kind, format) = infer("day", &["20261016", "20250101"]);
+        assert_eq!((kind, format.unwrap().format.as_str()), (Some(DataType::Date), "%Y%m%d"));
+        // Compact dates are told from identifiers by their values alone
+        assert_eq!(infer("period", &["20010203", "20011105"]).0, Some(DataType::Date));
+        assert_eq!(infer("birth", &["19870412"]).0, Some(DataType::Date));
+        assert_eq!(infer("sku", &["20010203", "20011340"]).0, None);
+        assert_eq!(infer("sku", &["10010203"]).0, None);
+
+        let (kind, format) = infer("day", &["Oct 16, 2026", "Jan 2, 2025"]);
+        assert_eq!((kind, format.unwrap().format.as_str()), (Some(DataType::Date), "%b %d, %Y"));
+
+        let (kind, format) = infer("at", &["2026-10-16T10:15:00Z", "2026-10-16T11:00:00.5+02:00"]);
+        assert_eq!(kind, Some(DataType::Timestamp));
+        assert_eq!(format.unwrap().with_timezone, 2);
+
+        let (kind, format) = infer("sent", &["Fri, 16 Oct 2026 10:15:00 +0200"]);
+        assert_eq!((kind, format.unwrap().format.as_str()), (Some(DataType::Timestamp), "%a, %d %b %Y %H:%M:%S %z"));
+
+        assert_eq!(infer("opens", &["09:30", "17:45:10"]).0, Some(DataType::Time));
+        assert_eq!(infer("created_at", &["1697452800", "1697452801"]).0, Some(DataType::Timestamp));
+        assert_eq!(infer("created_ms", &["1697452800123"]).0, Some(DataType::Timestamp));
+        assert_eq!(infer("order_no", &["1697452800123"]).0, None);
+        // Dates mixed with timestamps read as timestamps
+        assert_eq!(infer("when", &["2026-10-16", "2026-10-16 10:15:00"]).0, Some(DataType::Timestamp));
+    }
+
+    #[test]
+    fn test_failed_candidates_are_dropped() {
+        let mut acc = DatetimeAccumulator::new(None);
+        acc.update("01/02/2026");
+        let both = acc.live;
+        assert!(both.count_ones() >= 2);
+        acc.update("13/04/2026");
+        assert_eq!(acc.live.count_ones(), 1);
+        assert!(acc.live & both != 0);
+
+        // A value no live candidate reads brings the others back
+        acc.update("2026-10-16");
+        acc.update("14/04/2026");
+        assert_eq!(acc.data_type(4, "d"), Some(DataType::Date));
+        assert_eq!(acc.format().unwrap().format, "%d/%m/%Y");
+        assert_eq!(acc.format().unwrap().matches, 3);
+    }
+
+    #[test]
+    fn test_day_month_ambiguity() {
+        let format = infer("d", &["01/02/2026", "03/04/2026"]).1.unwrap();
+        assert_eq!(format.format, "%m/%d/%Y");
+        assert_eq!(format.ambiguous_with.as_deref(), Some("%d/%m/%Y"));
+
+        let format = infer("d", &["01/02/2026", "13/04/2026"]).1.unwrap();
+        assert_eq!(format.format, "%d/%m/%Y");
+        assert_eq!(format.ambiguous_with, None);
+    }
+}
@@ -0,0 +1,173 @@
+//! Exact distinct sets and numeric values for `exact_mode`, kept until a
+//! column's memory budget is spent, and the accuracy report of each column.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use ts_rs::TS;
+
+/// Estimated bytes per distinct value beyond its text: the `String` header
+/// and the hash table slot.
+const ENTRY_OVERHEAD: usize = 48;
+/// Bytes per kept numeric value and its row index.
+const NUMBER_SIZE: usize = std::mem::size_of::<(f64, usize)>();
+
+/// Whether a statistic is exact and, if not, how far off it may be.
+#[derive(Serialize, Debug, Clone, PartialEq, TS)]
+#[ts(export)]
+pub struct Accuracy {
+    pub exact: bool,
+    /// For approximate statistics: the relative standard error of a count,
+    /// or the 95% bound on the rank error of a quantile or histogram
+    pub error_bound: Option<f64>,
+}
+
+impl Accuracy {
+    pub fn exact() -> Self {
+        Self { exact: true, error_bound: None }
+    }
+
+    pub fn approximate(error_bound: Option<f64>) -> Self {
+        Self { exact: false, error_bound }
+    }
+
+    /// Relative standard error of a HyperLogLog count with `2^precision` registers.
+    pub fn hll(precision: u8) -> Self {
+        Self::approximate(Some(1.04 / ((1u64 << precision) as f64).sqrt()))
+    }
+
+    /// Bound on the CDF error of `samples` uniform draws that holds with 95%
+    /// probability (Dvoretzky–Kiefer–Wolfowitz).
+    pub fn sampled(samples: usize) -> Self {
+        Self::approximate(Some(((2.0f64 / 0.05).ln() / (2.0 * samples.max(1) as f64)).sqrt()))
+    }
+}
+
+/// Accuracy of each estimated statistic of a column.
+#[derive(Serialize, Debug, Clone, TS)]
+#[ts(export)]
+pub struct ColumnAccuracy {
+    pub distinct_estimate: Accuracy,
+    /// `categorical_stats.top_values`; approximate once `max_categorical_keys` overflowed
+    pub top_values: Accuracy,
+    /// `numeric_stats` median and percentiles
+    pub quantiles: Option<Accuracy>,
+    pub histogram: Option<Accuracy>,
+    /// `outlier_stats` counts and `outlier_rows`, given the fences
+    pub outliers: Option<Accuracy>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ExactAccumulator {
+    // Both are dropped together once `bytes` exceeds `limit`
+    distinct: Option<HashSet<String>>,
+    numbers: Option<Vec<(f64, usize)>>,
+    bytes: usize,
+    limit: usize,
+}
+
+impl ExactAccumulator {
+    /// A disabled accumulator keeps nothing and reports no exact values.
+    pub fn new(enabled: bool, limit: usize) -> Self {
+        Self {
+            distinct: enabled.then(HashSet::new),
+            numbers: enabled.then(Vec::new),
+            bytes: 0,
+            limit,
+        }
+    }
+
+    pub fn update(&mut self, trimmed: &str) {
+        if let Some(ref mut distinct) = self.distinct {
+            if !distinct.contains(trimmed) {
+                self.bytes += trimmed.len() + ENTRY_OVERHEAD;
+                distinct.insert(trimmed.to_string());
+                self.enforce_limit();
+            }
+        }
+    }
+
+    pub fn update_number(&mut self, val: f64, row_index: usize) {
+        if let Some(ref mut numbers) = self.numbers {
+            numbers.push((val, row_index));
+            self.bytes += NUMBER_SIZE;
+            self.enforce_limit();
+        }
+    }
+
+    /// Adds the values of `other`, whose row indices are shifted by `row_offset`.
+    /// If either side already degraded, so does the result.
+    pub fn merge(&mut self, other: &ExactAccumulator, row_offset: usize) {
+        match (&mut self.distinct, &other.distinct) {
+            (Some(ours), Some(theirs)) => {
+                for value in theirs {
+                    if ours.insert(value.clone()) {
+                        self.bytes += value.len() + ENTRY_OVERHEAD;
+                    }
+                }
+            }
+            _ => self.degrade(),
+        }
+        match (&mut self.numbers, &other.numbers) {
+            (Some(ours), Some(theirs)) => {
+                ours.extend(theirs.iter().map(|&(val, row)| (val, row + row_offset)));
+                self.bytes += theirs.len() * NUMBER_SIZE;
+            }
+            _ => self.degrade(),
+        }
+        self.enforce_limit();
+    }
+
+    pub fn distinct_count(&self) -> Option<u64> {
+        self.distinct.as_ref().map(|distinct| distinct.len() as u64)
+    }
+
+    /// Every numeric value with its row, if all were kept.
+    pub fn take_numbers(&mut self) -> Option<Vec<(f64, usize)>> {
+        self.numbers.take()
+    }
+
+    fn enforce_limit(&mut self) {
+        if self.bytes > self.limit {
+            self.degrade();
+        }
+    }
+
+    fn degrade(&mut self) {
+        self.distinct = None;
+        self.numbers = None;
+        self.bytes = 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_degrades_past_the_memory_limit() {
+        let mut acc = ExactAccumulator::new(true, 1_000);
+        for i in 0..10 {
+            acc.update(&format!("v{}", i % 5));
+            acc.update_number(i as f64, i + 1);
+        }
+        assert_eq!(acc.distinct_count(), Some(5));
+
+        let mut other = ExactAccumulator::new(true, 1_000);
+        other.update_number(1.5, 1);
+        acc.merge(&other, 10);
+        assert_eq!(acc.clone().take_numbers().unwrap().last(), Some(&(1.5, 11)));
+
+        for i in 0..20 {
+            acc.update(&format!("w{}", i));
+        }
+        assert_eq!(acc.distinct_count(), None);
+        assert!(acc.take_numbers().is_none());
+        assert!(ExactAccumulator::new(false, 1_000).distinct_count().is_none());
+    }
+
+    #[test]
+    fn test_error_bounds() {
+        assert!((Accuracy::hll(12).error_bound.unwrap() - 0.01625).abs() < 1e-9);
+        assert!((Accuracy::sampled(1000).error_bound.unwrap() - 0.0429).abs() < 1e-3);
+    }
+}
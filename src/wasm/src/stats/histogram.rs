@@ -1,5 +1,6 @@
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use ts_rs::TS;
+use crate::stats::rng::SplitMix64;
 
 #[derive(Serialize, Debug, Clone, TS)]
 #[ts(export)]
@@ -52,19 +53,21 @@ impl Histogram {
     }
 }
 
-#[derive(Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct HistogramAccumulator {
     pub samples: Vec<(f64, usize)>,
     max_samples: usize,
     count: u64,
+    rng: SplitMix64,
 }
 
 impl HistogramAccumulator {
-    pub fn new(max_samples: usize) -> Self {
+    pub fn new(max_samples: usize, seed: u64) -> Self {
         Self {
             samples: Vec::with_capacity(max_samples),
             max_samples,
             count: 0,
+            rng: SplitMix64::new(seed),
         }
     }
 
@@ -73,14 +76,55 @@ impl HistogramAccumulator {
         if self.samples.len() < self.max_samples {
             self.samples.push((val, row_index));
         } else {
-            // Simple deterministic LCG for reservoir sampling in WASM/CLI
-            let j = (self.count * 1103515245 + 12345) as usize % self.count as usize;
+            let j = self.rng.below(self.count) as usize;
             if j < self.max_samples {
                 self.samples[j] = (val, row_index);
             }
         }
     }
 
+    /// Combines two reservoirs into one that is a uniform sample of both
+    /// populations: each slot is drawn from a side with probability
+    /// proportional to the values that side still represents. Row indices of
+    /// `other` are shifted by `row_offset`.
+    pub fn merge(&mut self, other: &HistogramAccumulator, row_offset: usize) {
+        let incoming = other.samples.iter().map(|&(val, row)| (val, row + row_offset));
+        if self.samples.len() + other.samples.len() <= self.max_samples {
+            self.samples.extend(incoming);
+            self.count += other.count;
+            return;
+        }
+
+        let mut ours = std::mem::take(&mut self.samples);
+        let mut theirs: Vec<(f64, usize)> = incoming.collect();
+        let (mut our_weight, mut their_weight) = (self.count, other.count);
+
+        while self.samples.len() < self.max_samples && !(ours.is_empty() && theirs.is_empty()) {
+            let take_ours = if theirs.is_empty() {
+                true
+            } else if ours.is_empty() {
+                false
+            } else {
+                self.rng.below(our_weight + their_weight) < our_weight
+            };
+            let (side, weight) = if take_ours { (&mut ours, &mut our_weight) } else { (&mut theirs, &mut their_weight) };
+            let picked = side.swap_remove(self.rng.below(side.len() as u64) as usize);
+            self.samples.push(picked);
+            *weight = weight.saturating_sub(1).max(1);
+        }
+        self.count += other.count;
+    }
+
+    /// Whether the reservoir still holds every value seen.
+    pub fn is_complete(&self) -> bool {
+        self.samples.len() as u64 == self.count
+    }
+
+    /// Sample size, for error bounds.
+    pub fn len(&self) -> usize {
+        self.samples.len()
+    }
+
     pub fn finalize(&self, min: f64, max: f64) -> Histogram {
         let num_bins = if self.count > 0 {
             let n = self.count as f64;
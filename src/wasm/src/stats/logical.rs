@@ -0,0 +1,161 @@
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use ts_rs::TS;
+
+/// Earliest and latest value of a date or timestamp column.
+/// Values are ISO 8601 strings in a single fixed format, so they order lexically.
+#[derive(Serialize, Deserialize, Debug, Clone, TS)]
+#[ts(export)]
+pub struct DateRange {
+    pub min: String,
+    pub max: String,
+}
+
+impl DateRange {
+    pub fn new(value: &str) -> Self {
+        Self {
+            min: value.to_string(),
+            max: value.to_string(),
+        }
+    }
+
+    pub fn update(&mut self, value: &str) {
+        if value < self.min.as_str() {
+            self.min = value.to_string();
+        }
+        if value > self.max.as_str() {
+            self.max = value.to_string();
+        }
+    }
+
+    pub fn merge(&mut self, other: &DateRange) {
+        self.update(&other.min);
+        self.update(&other.max);
+    }
+}
+
+/// Exact statistics for a fixed-scale decimal column. `min` and `max` keep the
+/// full declared precision instead of going through f64.
+#[derive(Serialize, Deserialize, Debug, Clone, TS)]
+#[ts(export)]
+pub struct DecimalStats {
+    pub precision: usize,
+    pub scale: usize,
+    pub min: Option<String>,
+    pub max: Option<String>,
+    /// Widest integer part seen, to compare against `precision - scale` headroom
+    pub max_integer_digits: usize,
+}
+
+impl DecimalStats {
+    pub fn new(precision: usize, scale: usize) -> Self {
+        Self {
+            precision,
+            scale,
+            min: None,
+            max: None,
+            max_integer_digits: 0,
+        }
+    }
+
+    pub fn update(&mut self, value: &str) {
+        if self.min.as_deref().is_none_or(|min| cmp_decimal(value, min) == Ordering::Less) {
+            self.min = Some(value.to_string());
+        }
+        if self.max.as_deref().is_none_or(|max| cmp_decimal(value, max) == Ordering::Greater) {
+            self.max = Some(value.to_string());
+        }
+
+        let int_part = value.trim_start_matches('-').split('.').next().unwrap_or("");
+        let int_digits = int_part.trim_start_matches('0').len();
+        self.max_integer_digits = self.max_integer_digits.max(int_digits);
+    }
+
+    pub fn merge(&mut self, other: &DecimalStats) {
+        for value in other.min.iter().chain(other.max.iter()) {
+            self.update(value);
+        }
+    }
+}
+
+/// Formats an unscaled integer (as produced by `BigInt::to_string`) with `scale`
+/// fractional digits, e.g. ("-12345", 2) -> "-123.45" and ("5", 3) -> "0.005".
+pub fn format_scaled_decimal(unscaled: &str, scale: usize) -> String {
+    let (sign, digits) = match unscaled.strip_prefix('-') {
+        Some(rest) => ("-", rest),
+        None => ("", unscaled),
+    };
+    if scale == 0 {
+        return format!("{}{}", sign, digits);
+    }
+    let padded = format!("{:0>width$}", digits, width = scale + 1);
+    let (int_part, frac_part) = padded.split_at(padded.len() - scale);
+    format!("{}{}.{}", sign, int_part, frac_part)
+}
+
+/// Compares two plain decimal strings numerically without going through f64.
+fn cmp_decimal(a: &str, b: &str) -> Ordering {
+    let (a_neg, a_abs) = split_sign(a);
+    let (b_neg, b_abs) = split_sign(b);
+    match (a_neg, b_neg) {
+        (false, true) => Ordering::Greater,
+        (true, false) => Ordering::Less,
+        (false, false) => cmp_abs(a_abs, b_abs),
+        (true, true) => cmp_abs(b_abs, a_abs),
+    }
+}
+
+fn split_sign(s: &str) -> (bool, &str) {
+    match s.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, s),
+    }
+}
+
+fn cmp_abs(a: &str, b: &str) -> Ordering {
+    let (a_int, a_frac) = a.split_once('.').unwrap_or((a, ""));
+    let (b_int, b_frac) = b.split_once('.').unwrap_or((b, ""));
+    let a_int = a_int.trim_start_matches('0');
+    let b_int = b_int.trim_start_matches('0');
+
+    a_int.len().cmp(&b_int.len())
+        .then_with(|| a_int.cmp(b_int))
+        .then_with(|| {
+            let width = a_frac.len().max(b_frac.len());
+            format!("{:0<width$}", a_frac, width = width).cmp(&format!("{:0<width$}", b_frac, width = width))
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_scaled_decimal() {
+        assert_eq!(format_scaled_decimal("12345", 2), "123.45");
+        assert_eq!(format_scaled_decimal("-12345", 2), "-123.45");
+        assert_eq!(format_scaled_decimal("5", 3), "0.005");
+        assert_eq!(format_scaled_decimal("-5", 3), "-0.005");
+        assert_eq!(format_scaled_decimal("42", 0), "42");
+    }
+
+    #[test]
+    fn test_decimal_stats_exact_ordering() {
+        let mut stats = DecimalStats::new(38, 2);
+        for v in ["10.50", "-3.25", "99999999999999999999.99", "9.99", "-0.01"] {
+            stats.update(v);
+        }
+        assert_eq!(stats.min.as_deref(), Some("-3.25"));
+        assert_eq!(stats.max.as_deref(), Some("99999999999999999999.99"));
+        assert_eq!(stats.max_integer_digits, 20);
+    }
+
+    #[test]
+    fn test_date_range() {
+        let mut range = DateRange::new("2026-10-16");
+        range.update("2025-01-01");
+        range.update("2026-12-31");
+        assert_eq!(range.min, "2025-01-01");
+        assert_eq!(range.max, "2026-12-31");
+    }
+}
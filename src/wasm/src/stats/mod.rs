@@ -1,4 +1,4 @@
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use ts_rs::TS;
 pub mod types;
 pub mod numeric;
@@ -7,47 +7,132 @@ pub mod histogram;
 pub mod categorical;
 pub mod correlation;
 pub mod tree;
+pub mod logical;
+pub mod config;
+pub mod datetime;
+pub mod temporal;
+pub mod strings;
+pub mod shapes;
+pub mod boolean;
+pub mod sampling;
+pub mod rng;
+pub mod exact;
+pub mod quantiles;
+pub mod outliers;
+pub mod partial;
 
 use hyperloglogplus::{HyperLogLog, HyperLogLogPlus};
-use std::collections::hash_map::RandomState;
-use crate::stats::types::{DataType, BaseStats};
+use std::hash::BuildHasher;
+use std::sync::Arc;
+use crate::stats::types::{DataType, BaseStats, LogicalType};
 use crate::stats::numeric::NumericStats;
 use crate::stats::histogram::{Histogram, HistogramAccumulator};
 use crate::stats::categorical::{CategoricalStats, CategoricalAccumulator};
+use crate::stats::logical::{DateRange, DecimalStats};
+use crate::stats::config::ProfilerConfig;
+use crate::stats::datetime::{DatetimeAccumulator, DatetimeFormat};
+use crate::stats::temporal::TemporalStats;
+use crate::stats::strings::{StringAccumulator, StringStats};
+use crate::stats::shapes::{ShapeAccumulator, ShapeStats};
+use crate::stats::boolean::{BooleanAccumulator, BooleanStats};
+use crate::stats::rng::SeededHasher;
+use crate::stats::exact::{Accuracy, ColumnAccuracy, ExactAccumulator};
+use crate::stats::quantiles::KllSketch;
+use crate::stats::outliers::{OutlierMethod, OutlierStats, TailAccumulator, MIN_VALUES};
 
-#[derive(Serialize, Debug, TS)]
+/// Hasher for the distinct-count sketch. Unlike `RandomState` it is keyed
+/// by the configured seed, so sketches built in different workers hash
+/// values identically and can be merged.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default)]
+pub struct SketchHasher {
+    seed: u64,
+}
+
+impl BuildHasher for SketchHasher {
+    type Hasher = SeededHasher;
+
+    fn build_hasher(&self) -> SeededHasher {
+        SeededHasher::new(self.seed)
+    }
+}
+
+#[derive(Serialize, Debug, Clone, TS)]
 #[ts(export)]
 pub struct ColumnProfile {
     pub name: String,
     pub base_stats: BaseStats,
     pub numeric_stats: Option<NumericStats>,
     pub categorical_stats: Option<CategoricalStats>,
+    pub shape_stats: Option<ShapeStats>,
+    pub boolean_stats: Option<BooleanStats>,
     pub histogram: Option<Histogram>,
+    pub outlier_stats: Option<OutlierStats>,
     pub min_length: Option<usize>,
     pub max_length: Option<usize>,
     pub notes: Vec<String>,
     pub quality_metrics: Option<crate::quality::ColumnQualityMetrics>,
 
+    // Declared by the source schema; overrides the inferred type when present
+    pub logical_type: Option<LogicalType>,
+    pub date_range: Option<DateRange>,
+    pub decimal_stats: Option<DecimalStats>,
+    /// Detected format of a date, time or timestamp column
+    pub datetime_format: Option<DatetimeFormat>,
+    pub temporal_stats: Option<TemporalStats>,
+    /// Which of the statistics above are exact
+    pub accuracy: Option<ColumnAccuracy>,
+    pub string_stats: Option<StringStats>,
+
     #[serde(skip)]
     #[ts(skip)]
-    hll: HyperLogLogPlus<String, RandomState>,
+    config: Arc<ProfilerConfig>,
+
+    #[serde(skip)]
+    #[ts(skip)]
+    hll: HyperLogLogPlus<String, SketchHasher>,
+
+    #[serde(skip)]
+    #[ts(skip)]
+    exact: ExactAccumulator,
 
     #[serde(skip)]
     #[ts(skip)]
     hist_acc: Option<HistogramAccumulator>,
 
+    #[serde(skip)]
+    #[ts(skip)]
+    quantile_sketch: Option<KllSketch>,
+
+    #[serde(skip)]
+    #[ts(skip)]
+    tails: Option<TailAccumulator>,
+
     #[serde(skip)]
     #[ts(skip)]
     cat_acc: CategoricalAccumulator,
 
+    #[serde(skip)]
+    #[ts(skip)]
+    shape_acc: ShapeAccumulator,
+
+    #[serde(skip)]
+    #[ts(skip)]
+    bool_acc: BooleanAccumulator,
+
+    #[serde(skip)]
+    #[ts(skip)]
+    datetime: DatetimeAccumulator,
+
+    #[serde(skip)]
+    #[ts(skip)]
+    string_acc: StringAccumulator,
+
     // Type inference counters
     integer_count: u64,
     numeric_count: u64,
-    boolean_count: u64,
-    date_count: u64,
     total_valid: u64,
 
-    // Sample values for display (up to 5 unique non-null values)
+    // Sample values for display (up to `max_sample_values` unique non-null values)
     pub sample_values: Vec<String>,
 
     // Sample values for PII detection (separate to avoid confusion)
@@ -61,39 +146,19 @@ pub struct ColumnProfile {
     pub outlier_rows: Vec<usize>,
 }
 
-impl Clone for ColumnProfile {
-    fn clone(&self) -> Self {
-        Self {
-            name: self.name.clone(),
-            base_stats: self.base_stats.clone(),
-            numeric_stats: self.numeric_stats.clone(),
-            categorical_stats: self.categorical_stats.clone(),
-            histogram: self.histogram.clone(),
-            min_length: self.min_length.clone(),
-            max_length: self.max_length.clone(),
-            notes: self.notes.clone(),
-            quality_metrics: self.quality_metrics.clone(),
-            hll: HyperLogLogPlus::new(12, RandomState::new()).unwrap(),
-            hist_acc: None,
-            cat_acc: CategoricalAccumulator::new(1000),
-            integer_count: self.integer_count,
-            numeric_count: self.numeric_count,
-            boolean_count: self.boolean_count,
-            date_count: self.date_count,
-            total_valid: self.total_valid,
-            sample_values: self.sample_values.clone(),
-            pii_samples: Vec::new(),
-            missing_rows: self.missing_rows.clone(),
-            pii_rows: self.pii_rows.clone(),
-            outlier_rows: self.outlier_rows.clone(),
-        }
-    }
-}
-
 impl ColumnProfile {
     pub fn new(name: String) -> Self {
-        let hll = HyperLogLogPlus::new(12, RandomState::new()).unwrap();
-        
+        Self::with_config(name, Arc::new(ProfilerConfig::default()))
+    }
+
+    /// The config must have passed `ProfilerConfig::validate`.
+    pub fn with_config(name: String, config: Arc<ProfilerConfig>) -> Self {
+        let hll = HyperLogLogPlus::new(config.hll_precision, SketchHasher { seed: config.seed }).unwrap();
+        let cat_acc = CategoricalAccumulator::new(config.max_categorical_keys, config.top_values);
+        let exact = ExactAccumulator::new(config.exact_mode, config.exact_memory_limit);
+        let shape_acc = ShapeAccumulator::new(config.max_categorical_keys, config.top_values, config.max_anomaly_rows);
+        let datetime = DatetimeAccumulator::new(config.reference_time());
+
         Self {
             name,
             base_stats: BaseStats {
@@ -104,18 +169,34 @@ impl ColumnProfile {
             },
             numeric_stats: None,
             categorical_stats: None,
+            shape_stats: None,
+            boolean_stats: None,
             histogram: None,
+            outlier_stats: None,
             min_length: None,
             max_length: None,
             notes: Vec::new(),
             quality_metrics: None,
+            logical_type: None,
+            date_range: None,
+            decimal_stats: None,
+            datetime_format: None,
+            temporal_stats: None,
+            accuracy: None,
+            string_stats: None,
+            config,
             hll,
+            exact,
             hist_acc: None,
-            cat_acc: CategoricalAccumulator::new(1000),
+            quantile_sketch: None,
+            tails: None,
+            cat_acc,
+            shape_acc,
+            bool_acc: BooleanAccumulator::new(),
+            datetime,
+            string_acc: StringAccumulator::new(),
             integer_count: 0,
             numeric_count: 0,
-            boolean_count: 0,
-            date_count: 0,
             total_valid: 0,
             sample_values: Vec::new(),
             pii_samples: Vec::new(),
@@ -125,13 +206,31 @@ impl ColumnProfile {
         }
     }
 
+    /// Declares the column's logical type from the source schema.
+    pub fn set_logical_type(&mut self, logical_type: LogicalType) {
+        if let LogicalType::Decimal { precision, scale } = logical_type {
+            self.decimal_stats = Some(DecimalStats::new(precision, scale));
+        }
+        self.logical_type = Some(logical_type);
+    }
+
+    /// Counts `count` values as missing at once, for rows profiled before
+    /// the column existed. `rows` are the row numbers of the first of them;
+    /// only `max_anomaly_rows` are kept.
+    pub fn update_missing(&mut self, count: u64, rows: &[usize]) {
+        self.base_stats.count += count;
+        self.base_stats.missing += count;
+        let room = self.config.max_anomaly_rows.saturating_sub(self.missing_rows.len());
+        self.missing_rows.extend(rows.iter().take(room));
+    }
+
     pub fn update(&mut self, value: &str, row_index: usize) {
         self.base_stats.count += 1;
         
         let trimmed = value.trim();
-        if trimmed.is_empty() || trimmed.to_lowercase() == "null" || trimmed.to_lowercase() == "n/a" {
+        if self.config.is_null_token(trimmed) {
             self.base_stats.missing += 1;
-            if self.missing_rows.len() < 1000 {
+            if self.missing_rows.len() < self.config.max_anomaly_rows {
                 self.missing_rows.push(row_index);
             }
             return;
@@ -139,70 +238,81 @@ impl ColumnProfile {
 
         self.total_valid += 1;
         self.hll.insert(&trimmed.to_string());
+        self.exact.update(trimmed);
         self.cat_acc.update(trimmed);
+        self.shape_acc.update(trimmed, row_index);
         
-        // Store sample values for display (max 5 unique non-null values)
-        if self.sample_values.len() < 5 && !self.sample_values.contains(&trimmed.to_string()) {
+        // Store sample values for display (unique non-null values)
+        if self.sample_values.len() < self.config.max_sample_values && !self.sample_values.contains(&trimmed.to_string()) {
             self.sample_values.push(trimmed.to_string());
         }
         
-        // Store sample values for PII detection (max 100)
+        // Store sample values for PII detection (up to `max_pii_samples`)
         // Also track row index if this looks like PII (simplified check here, refined in finalize)
         // Note: Real PII detection happens in finalize() using the samples. 
         // To strictly map rows to PII types, we would need to run detection per row which is slow.
         // For now, we collect samples. If we want to highlight rows with "Potential PII", 
         // we might need to assume all rows matching the pattern are PII.
         // Let's store potential PII indices if we collect the sample.
-        if self.pii_samples.len() < 100 {
+        if self.pii_samples.len() < self.config.max_pii_samples {
             self.pii_samples.push(trimmed.to_string());
             // We blindly add the index here corresponding to the sample. 
             // In reality, we'd filter these later.
-            if self.pii_rows.len() < 100 {
+            if self.pii_rows.len() < self.config.max_pii_samples {
                  self.pii_rows.push(row_index);
             }
         }
 
+        // Whitespace padding is counted, so this takes the untrimmed value
+        self.string_acc.update(value);
+
         let len = trimmed.len();
-        if self.min_length.map_or(true, |min| len < min) { self.min_length = Some(len); }
-        if self.max_length.map_or(true, |max| len > max) { self.max_length = Some(len); }
+        if self.min_length.is_none_or(|min| len < min) { self.min_length = Some(len); }
+        if self.max_length.is_none_or(|max| len > max) { self.max_length = Some(len); }
 
+        if self.logical_type.is_some() {
+            self.update_logical(trimmed);
+        }
         self.infer_and_update(trimmed, row_index);
     }
 
+    fn update_logical(&mut self, trimmed: &str) {
+        if let Some(ref mut stats) = self.decimal_stats {
+            stats.update(trimmed);
+        }
+        if self.logical_type.as_ref().is_some_and(|lt| lt.is_date_like()) {
+            match self.date_range {
+                Some(ref mut range) => range.update(trimmed),
+                None => self.date_range = Some(DateRange::new(trimmed)),
+            }
+        }
+    }
+
     fn infer_and_update(&mut self, trimmed: &str, row_index: usize) {
+        // Compact dates and epoch values are also integers, so the temporal
+        // check runs for every value and the type is decided in `finalize`
+        self.datetime.update(trimmed);
+        // Also before the numeric checks, for `0`/`1` flags
+        if let Some(value) = self.config.boolean_value(trimmed) {
+            self.bool_acc.update(trimmed, value);
+        }
+
         if let Ok(_) = trimmed.parse::<i64>() {
             self.integer_count += 1;
             self.numeric_count += 1;
             self.update_numeric(trimmed.parse::<f64>().unwrap(), row_index);
-            return;
-        }
-
-        if let Ok(val) = trimmed.parse::<f64>() {
+        } else if let Ok(val) = trimmed.parse::<f64>() {
             self.numeric_count += 1;
             self.update_numeric(val, row_index);
-            return;
-        }
-
-        let lower = trimmed.to_lowercase();
-        if lower == "true" || lower == "false" || lower == "t" || lower == "f" {
-            self.boolean_count += 1;
-            return;
-        }
-
-        if self.is_date(trimmed) {
-            self.date_count += 1;
-            return;
         }
     }
 
-    fn is_date(&self, s: &str) -> bool {
-        (s.contains('-') || s.contains('/')) && s.len() >= 8 && s.chars().any(|c| c.is_numeric())
-    }
-
     fn update_numeric(&mut self, val: f64, row_index: usize) {
         if self.numeric_stats.is_none() {
             self.numeric_stats = Some(NumericStats::new());
-            self.hist_acc = Some(HistogramAccumulator::new(1000));
+            self.hist_acc = Some(HistogramAccumulator::new(self.config.histogram_reservoir_size, self.config.seed));
+            self.quantile_sketch = Some(KllSketch::new(self.config.quantile_sketch_k, self.config.seed));
+            self.tails = Some(TailAccumulator::new(self.config.max_anomaly_rows));
         }
         if let Some(ref mut stats) = self.numeric_stats {
             stats.update(val);
@@ -210,43 +320,154 @@ impl ColumnProfile {
         if let Some(ref mut acc) = self.hist_acc {
             acc.update(val, row_index);
         }
+        if let Some(ref mut sketch) = self.quantile_sketch {
+            sketch.update(val);
+        }
+        if let Some(ref mut tails) = self.tails {
+            tails.update(val, row_index);
+        }
+        self.exact.update_number(val, row_index);
+    }
+
+    /// Adds the values profiled by `other`, whose rows follow this column's
+    /// rows: its row indices are shifted by `row_offset`. Both columns must
+    /// use the same config and neither may be finalized yet.
+    pub fn merge(&mut self, other: &ColumnProfile, row_offset: usize) -> Result<(), String> {
+        self.hll.merge(&other.hll).map_err(|e| format!("Cannot merge column '{}': {:?}", self.name, e))?;
+        self.cat_acc.merge(&other.cat_acc);
+        self.exact.merge(&other.exact, row_offset);
+        self.shape_acc.merge(&other.shape_acc, row_offset);
+
+        self.base_stats.count += other.base_stats.count;
+        self.base_stats.missing += other.base_stats.missing;
+        self.integer_count += other.integer_count;
+        self.numeric_count += other.numeric_count;
+        self.bool_acc.merge(&other.bool_acc);
+        self.datetime.merge(&other.datetime);
+        self.string_acc.merge(&other.string_acc);
+        self.total_valid += other.total_valid;
+
+        if let Some(len) = other.min_length {
+            if self.min_length.is_none_or(|min| len < min) { self.min_length = Some(len); }
+        }
+        if let Some(len) = other.max_length {
+            if self.max_length.is_none_or(|max| len > max) { self.max_length = Some(len); }
+        }
+
+        if self.logical_type.is_none() {
+            if let Some(ref logical_type) = other.logical_type {
+                self.set_logical_type(logical_type.clone());
+            }
+        }
+        match (&mut self.date_range, &other.date_range) {
+            (Some(range), Some(theirs)) => range.merge(theirs),
+            (None, Some(theirs)) => self.date_range = Some(theirs.clone()),
+            _ => {}
+        }
+        if let (Some(stats), Some(theirs)) = (&mut self.decimal_stats, &other.decimal_stats) {
+            stats.merge(theirs);
+        }
+
+        match (&mut self.numeric_stats, &other.numeric_stats) {
+            (Some(stats), Some(theirs)) => stats.merge(theirs),
+            (None, Some(theirs)) => self.numeric_stats = Some(theirs.clone()),
+            _ => {}
+        }
+        match (&mut self.hist_acc, &other.hist_acc) {
+            (Some(acc), Some(theirs)) => acc.merge(theirs, row_offset),
+            (None, Some(theirs)) => {
+                let mut acc = HistogramAccumulator::new(self.config.histogram_reservoir_size, self.config.seed);
+                acc.merge(theirs, row_offset);
+                self.hist_acc = Some(acc);
+            }
+            _ => {}
+        }
+        match (&mut self.quantile_sketch, &other.quantile_sketch) {
+            (Some(sketch), Some(theirs)) => sketch.merge(theirs),
+            (None, Some(theirs)) => self.quantile_sketch = Some(theirs.clone()),
+            _ => {}
+        }
+        match (&mut self.tails, &other.tails) {
+            (Some(tails), Some(theirs)) => tails.merge(theirs, row_offset),
+            (None, Some(theirs)) => {
+                let mut tails = TailAccumulator::new(self.config.max_anomaly_rows);
+                tails.merge(theirs, row_offset);
+                self.tails = Some(tails);
+            }
+            _ => {}
+        }
+
+        for value in &other.sample_values {
+            if self.sample_values.len() < self.config.max_sample_values && !self.sample_values.contains(value) {
+                self.sample_values.push(value.clone());
+            }
+        }
+        let room = self.config.max_pii_samples.saturating_sub(self.pii_samples.len());
+        self.pii_samples.extend(other.pii_samples.iter().take(room).cloned());
+        let room = self.config.max_pii_samples.saturating_sub(self.pii_rows.len());
+        self.pii_rows.extend(other.pii_rows.iter().take(room).map(|row| row + row_offset));
+        let room = self.config.max_anomaly_rows.saturating_sub(self.missing_rows.len());
+        self.missing_rows.extend(other.missing_rows.iter().take(room).map(|row| row + row_offset));
+        Ok(())
     }
 
     pub fn finalize(&mut self) {
-        self.base_stats.distinct_estimate = self.hll.count().round() as u64;
+        let mut accuracy = ColumnAccuracy {
+            distinct_estimate: Accuracy::hll(self.config.hll_precision),
+            top_values: if self.cat_acc.is_exact() { Accuracy::exact() } else { Accuracy::approximate(None) },
+            quantiles: None,
+            histogram: None,
+            outliers: None,
+        };
+        match self.exact.distinct_count() {
+            Some(count) => {
+                self.base_stats.distinct_estimate = count;
+                accuracy.distinct_estimate = Accuracy::exact();
+            }
+            None => self.base_stats.distinct_estimate = self.hll.count().round() as u64,
+        }
         self.categorical_stats = Some(self.cat_acc.finalize());
+        self.shape_stats = self.shape_acc.finalize();
         
+        if let Some(numbers) = self.exact.take_numbers() {
+            // With every value kept, the reservoir is replaced by all of them
+            if let Some(ref mut acc) = self.hist_acc {
+                acc.samples = numbers;
+            }
+        }
+        if let Some(mut stats) = self.numeric_stats.take() {
+            stats.finalize();
+            stats.set_quantiles(|q| self.quantile(q));
+            self.numeric_stats = Some(stats);
+            let exact = self.hist_acc.as_ref().is_some_and(HistogramAccumulator::is_complete);
+            accuracy.quantiles = Some(match self.quantile_sketch {
+                Some(ref sketch) if !exact => Accuracy::approximate(Some(KllSketch::rank_error(sketch.k()))),
+                _ => Accuracy::exact(),
+            });
+        }
+
         if let Some(ref mut stats) = self.numeric_stats {
             if let Some(ref mut acc) = self.hist_acc {
-                stats.finalize(&mut acc.samples);
+                accuracy.histogram = Some(if acc.is_complete() { Accuracy::exact() } else { Accuracy::sampled(acc.len()) });
                 self.histogram = Some(acc.finalize(stats.min, stats.max));
-                
-                // Identify outliers from samples
-                if stats.count > 10 && stats.std_dev > 0.0 {
-                    let threshold = 3.0 * stats.std_dev;
-                    for (val, idx) in &acc.samples {
-                        if (val - stats.mean).abs() > threshold {
-                            if self.outlier_rows.len() < 1000 {
-                                self.outlier_rows.push(*idx);
-                            }
-                        }
-                    }
-                }
-            } else {
-                stats.finalize(&mut []);
             }
         }
+        accuracy.outliers = self.find_outliers();
+        self.accuracy = Some(accuracy);
 
         if self.total_valid == 0 {
             self.base_stats.inferred_type = DataType::Null;
+        } else if let Some(ref logical_type) = self.logical_type {
+            self.base_stats.inferred_type = logical_type.data_type();
+        } else if let Some(data_type) = self.datetime.data_type(self.total_valid, &self.name) {
+            self.base_stats.inferred_type = data_type;
+            self.datetime_format = self.datetime.format();
         } else if self.integer_count == self.total_valid {
             self.base_stats.inferred_type = DataType::Integer;
         } else if self.numeric_count == self.total_valid {
             self.base_stats.inferred_type = DataType::Numeric;
-        } else if self.boolean_count == self.total_valid {
+        } else if self.bool_acc.count() == self.total_valid {
             self.base_stats.inferred_type = DataType::Boolean;
-        } else if self.date_count == self.total_valid {
-            self.base_stats.inferred_type = DataType::Date;
         } else {
             self.base_stats.inferred_type = DataType::String;
             
@@ -255,10 +476,95 @@ impl ColumnProfile {
             }
         }
         
+        if matches!(self.base_stats.inferred_type, DataType::Date | DataType::Timestamp) {
+            self.temporal_stats = self.datetime.temporal().finalize();
+        }
+        if self.base_stats.inferred_type == DataType::String {
+            self.string_stats = self.string_acc.finalize();
+        }
+        if self.base_stats.inferred_type == DataType::Boolean {
+            self.boolean_stats = self.bool_acc.finalize(false);
+        } else if self.base_stats.inferred_type == DataType::Integer && self.bool_acc.count() == self.total_valid {
+            self.boolean_stats = self.bool_acc.finalize(true);
+            self.notes.push("Candidate flag: only boolean values such as 0 and 1".to_string());
+        }
+
         // Calculate quality metrics
         self.calculate_quality_metrics();
     }
     
+    /// Fills `outlier_stats` and `outlier_rows` and returns their accuracy.
+    /// Outliers are taken from every value while the reservoir holds them
+    /// all, else from the tails; a side with more outliers than its tail
+    /// holds has its count estimated by the quantile sketch.
+    fn find_outliers(&mut self) -> Option<Accuracy> {
+        let (stats, acc) = (self.numeric_stats.as_ref()?, self.hist_acc.as_ref()?);
+        if stats.count <= MIN_VALUES {
+            return None;
+        }
+        let method = &self.config.outlier_method;
+        let sample: Vec<f64> = acc.samples.iter().map(|s| s.0).filter(|v| v.is_finite()).collect();
+        let (lower, upper) = method.fences(stats, |q| self.quantile(q), &sample)?;
+
+        let (low, high, low_count, high_count, exact) = if acc.is_complete() {
+            let low: Vec<(f64, usize)> = acc.samples.iter().filter(|s| s.0 < lower).copied().collect();
+            let high: Vec<(f64, usize)> = acc.samples.iter().filter(|s| s.0 > upper).copied().collect();
+            let (low_count, high_count) = (low.len() as u64, high.len() as u64);
+            (low, high, low_count, high_count, true)
+        } else {
+            let tails = self.tails.as_ref()?;
+            let sketch = self.quantile_sketch.as_ref()?;
+            let (low, low_complete) = tails.below(lower);
+            let (high, high_complete) = tails.above(upper);
+            let low_count = if low_complete { low.len() as u64 } else { sketch.count_below(lower).max(low.len() as u64) };
+            let high_count = if high_complete { high.len() as u64 } else { sketch.count_above(upper).max(high.len() as u64) };
+            // Z-score fences come from exact moments; the others from the sketch or reservoir
+            let exact_fences = matches!(method, OutlierMethod::ZScore { .. });
+            (low, high, low_count, high_count, exact_fences && low_complete && high_complete)
+        };
+
+        let mut rows: Vec<usize> = low.iter().chain(&high).map(|&(_, row)| row).collect();
+        rows.sort_unstable();
+        rows.truncate(self.config.max_anomaly_rows);
+        let count = low_count + high_count;
+        let accuracy = if exact {
+            Accuracy::exact()
+        } else {
+            Accuracy::approximate(self.quantile_sketch.as_ref().map(|sketch| KllSketch::rank_error(sketch.k())))
+        };
+        self.outlier_stats = Some(OutlierStats {
+            method: method.clone(),
+            lower_fence: lower,
+            upper_fence: upper,
+            count,
+            low_count,
+            high_count,
+            ratio: count as f64 / stats.count as f64,
+        });
+        self.outlier_rows = rows;
+        Some(accuracy)
+    }
+
+    /// Value at quantile `q` in [0, 1] of the column's numbers: exact while
+    /// every value is kept (small columns, `exact_mode`), else from the KLL
+    /// sketch. Also answers queries after `finalize`.
+    pub fn quantile(&self, q: f64) -> Option<f64> {
+        if !(0.0..=1.0).contains(&q) {
+            return None;
+        }
+        match self.hist_acc {
+            Some(ref acc) if acc.is_complete() => {
+                let mut values: Vec<f64> = acc.samples.iter().map(|s| s.0).filter(|v| v.is_finite()).collect();
+                if values.is_empty() {
+                    return None;
+                }
+                values.sort_by(f64::total_cmp);
+                Some(NumericStats::quantile_of_sorted(&values, q))
+            }
+            _ => self.quantile_sketch.as_ref()?.quantile(q),
+        }
+    }
+
     fn calculate_quality_metrics(&mut self) {
         use crate::quality::completeness;
         use crate::quality::uniqueness;
@@ -362,6 +668,16 @@ impl ColumnProfile {
 mod tests {
     use super::*;
 
+    /// Profiles `values` as rows 1, 2, ... of a column named `name`.
+    fn profile_of(name: &str, values: &[&str]) -> ColumnProfile {
+        let mut profile = ColumnProfile::new(name.to_string());
+        for (i, value) in values.iter().enumerate() {
+            profile.update(value, i + 1);
+        }
+        profile.finalize();
+        profile
+    }
+
     #[test]
     fn test_advanced_stats() {
         let mut profile = ColumnProfile::new("values".to_string());
@@ -378,4 +694,159 @@ mod tests {
         let hist = profile.histogram.as_ref().unwrap();
         assert!(!hist.bins.is_empty());
     }
+
+    #[test]
+    fn test_temporal_columns() {
+        let day = profile_of("day", &["20261016", "20261017", ""]);
+        assert_eq!(day.base_stats.inferred_type, DataType::Date);
+        assert_eq!(day.datetime_format.as_ref().unwrap().format, "%Y%m%d");
+        let temporal = day.temporal_stats.as_ref().unwrap();
+        assert_eq!((temporal.min.as_str(), temporal.span_seconds), ("2026-10-16T00:00:00", 86_400));
+
+        // Ambiguous values are read with the day-first format the column settles on
+        let day_first = profile_of("d", &["01/02/2026", "13/04/2026", "05/06/2026"]);
+        assert_eq!(day_first.datetime_format.as_ref().unwrap().format, "%d/%m/%Y");
+        let temporal = day_first.temporal_stats.as_ref().unwrap();
+        assert_eq!((temporal.min.as_str(), temporal.max.as_str()), ("2026-02-01T00:00:00", "2026-06-05T00:00:00"));
+        assert_eq!(temporal.by_month[1..6], [1, 0, 1, 0, 1]);
+
+        let phone = profile_of("phone", &["555-123-4567", "555-765-4321"]);
+        assert_eq!(phone.base_stats.inferred_type, DataType::String);
+        assert!(phone.datetime_format.is_none());
+
+        let ids = profile_of("id", &["10000001", "10000002"]);
+        assert_eq!(ids.base_stats.inferred_type, DataType::Integer);
+    }
+
+    #[test]
+    fn test_boolean_columns() {
+        let answers = profile_of("flag", &["Yes", "no", "Y", "yes", "N/A"]);
+        assert_eq!(answers.base_stats.inferred_type, DataType::Boolean);
+        let stats = answers.boolean_stats.as_ref().unwrap();
+        assert_eq!((stats.true_count, stats.false_count, stats.true_ratio), (3, 1, 0.75));
+        assert_eq!(stats.spellings.len(), 4);
+        assert!(!stats.candidate_flag);
+
+        let flags = profile_of("flag", &["0", "1", "1", "0"]);
+        assert_eq!(flags.base_stats.inferred_type, DataType::Integer);
+        assert!(flags.boolean_stats.as_ref().unwrap().candidate_flag);
+
+        let counts = profile_of("flag", &["0", "1", "2"]);
+        assert!(counts.boolean_stats.is_none());
+    }
+
+    #[test]
+    fn test_exact_mode_and_fallback() {
+        let profile_with = |config: ProfilerConfig| {
+            let mut profile = ColumnProfile::with_config("n".to_string(), Arc::new(config));
+            for i in 0..5000 {
+                profile.update(&(i % 3001).to_string(), i + 1);
+            }
+            profile.finalize();
+            profile
+        };
+
+        let exact = profile_with(ProfilerConfig { exact_mode: true, ..ProfilerConfig::default() });
+        assert_eq!(exact.base_stats.distinct_estimate, 3001);
+        assert_eq!(exact.numeric_stats.as_ref().unwrap().median, 1249.5);
+        let accuracy = exact.accuracy.as_ref().unwrap();
+        assert!(accuracy.distinct_estimate.exact && accuracy.quantiles.as_ref().unwrap().exact);
+
+        let degraded = profile_with(ProfilerConfig { exact_mode: true, exact_memory_limit: 10_000, ..ProfilerConfig::default() });
+        let accuracy = degraded.accuracy.as_ref().unwrap();
+        assert!(!accuracy.distinct_estimate.exact && accuracy.distinct_estimate.error_bound.is_some());
+        assert!(!accuracy.quantiles.as_ref().unwrap().exact);
+        assert!(!accuracy.top_values.exact);
+    }
+
+    #[test]
+    fn test_percentiles_come_from_the_sketch() {
+        let mut profile = ColumnProfile::new("latency".to_string());
+        for i in 0..50_000 {
+            profile.update(&((i * 7_919) % 50_000).to_string(), i + 1);
+        }
+        profile.finalize();
+
+        let stats = profile.numeric_stats.as_ref().unwrap();
+        let tolerance = KllSketch::rank_error(200) * 50_000.0;
+        assert!((stats.p99 - 49_499.0).abs() < tolerance);
+        assert!((profile.quantile(0.999).unwrap() - 49_949.0).abs() < tolerance);
+        assert_eq!(profile.quantile(2.0), None);
+        let quantiles = profile.accuracy.as_ref().unwrap().quantiles.as_ref().unwrap();
+        assert!(!quantiles.exact && quantiles.error_bound.unwrap() < 0.02);
+    }
+
+    #[test]
+    fn test_outliers_cover_rows_outside_the_reservoir() {
+        let profile_with = |config: ProfilerConfig| {
+            let config = Arc::new(config);
+            let (mut first, mut second) = (ColumnProfile::with_config("v".to_string(), config.clone()), ColumnProfile::with_config("v".to_string(), config));
+            for row in 1..=20_000 {
+                let value = match row {
+                    5_000 => 10_000,
+                    12_345 => -5_000,
+                    19_999 => 9_000,
+                    _ => (row % 100) as i64,
+                };
+                if row <= 10_000 { first.update(&value.to_string(), row) } else { second.update(&value.to_string(), row - 10_000) }
+            }
+            first.merge(&second, 10_000).unwrap();
+            first.finalize();
+            first
+        };
+
+        let profile = profile_with(ProfilerConfig::default());
+        let outliers = profile.outlier_stats.as_ref().unwrap();
+        assert_eq!(profile.outlier_rows, vec![5_000, 12_345, 19_999]);
+        assert_eq!((outliers.low_count, outliers.high_count), (1, 2));
+        assert!(profile.accuracy.as_ref().unwrap().outliers.as_ref().unwrap().exact);
+
+        // Two rows kept per side: the count on the full high side is estimated
+        let config = ProfilerConfig { outlier_method: OutlierMethod::Iqr { multiplier: 1.5 }, max_anomaly_rows: 2, ..ProfilerConfig::default() };
+        let profile = profile_with(config);
+        let outliers = profile.outlier_stats.as_ref().unwrap();
+        assert_eq!(profile.outlier_rows, vec![5_000, 12_345]);
+        assert_eq!(outliers.low_count, 1);
+        assert!(outliers.high_count >= 2);
+        assert!(!profile.accuracy.as_ref().unwrap().outliers.as_ref().unwrap().exact);
+    }
+
+    #[test]
+    fn test_merge_matches_single_pass() {
+        let values: Vec<String> = (1..=200).map(|i| if i % 10 == 0 { String::new() } else { (i * i % 97).to_string() }).collect();
+
+        let mut whole = ColumnProfile::new("v".to_string());
+        for (i, value) in values.iter().enumerate() {
+            whole.update(value, i + 1);
+        }
+
+        let mut first = ColumnProfile::new("v".to_string());
+        let mut second = ColumnProfile::new("v".to_string());
+        for (i, value) in values[..120].iter().enumerate() {
+            first.update(value, i + 1);
+        }
+        for (i, value) in values[120..].iter().enumerate() {
+            second.update(value, i + 1);
+        }
+        first.merge(&second, 120).unwrap();
+
+        whole.finalize();
+        first.finalize();
+        assert_eq!(first.base_stats.count, whole.base_stats.count);
+        assert_eq!(first.base_stats.missing, whole.base_stats.missing);
+        assert_eq!(first.base_stats.distinct_estimate, whole.base_stats.distinct_estimate);
+        assert_eq!(first.missing_rows, whole.missing_rows);
+
+        let (merged, single) = (first.numeric_stats.unwrap(), whole.numeric_stats.unwrap());
+        assert!((merged.mean - single.mean).abs() < 1e-9);
+        assert!((merged.variance - single.variance).abs() < 1e-6);
+        assert!((merged.skewness - single.skewness).abs() < 1e-9);
+        assert!((merged.kurtosis - single.kurtosis).abs() < 1e-9);
+        assert_eq!((merged.min, merged.max), (single.min, single.max));
+
+        let top = |stats: &CategoricalStats| stats.top_values.iter().map(|e| (e.value.clone(), e.count)).collect::<Vec<_>>();
+        let (merged_cat, single_cat) = (first.categorical_stats.unwrap(), whole.categorical_stats.unwrap());
+        assert_eq!(merged_cat.unique_count, single_cat.unique_count);
+        assert_eq!(top(&merged_cat)[0].1, top(&single_cat)[0].1);
+    }
 }
@@ -1,4 +1,4 @@
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use ts_rs::TS;
 
 #[derive(Serialize, Debug, TS)]
@@ -32,6 +32,19 @@ pub struct NumericStats {
     m4: f64,
 }
 
+/// The running moments behind `NumericStats`, as carried in a partial profile.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct NumericState {
+    pub min: f64,
+    pub max: f64,
+    pub sum: f64,
+    pub count: u64,
+    pub mean: f64,
+    pub m2: f64,
+    pub m3: f64,
+    pub m4: f64,
+}
+
 impl NumericStats {
     pub fn new() -> Self {
         Self {
@@ -78,7 +91,66 @@ impl NumericStats {
         self.m2 += term1;
     }
 
-    pub fn finalize(&mut self, samples: &mut [(f64, usize)]) {
+    pub fn state(&self) -> NumericState {
+        NumericState {
+            min: self.min,
+            max: self.max,
+            sum: self.sum,
+            count: self.count,
+            mean: self.mean,
+            m2: self.m2,
+            m3: self.m3,
+            m4: self.m4,
+        }
+    }
+
+    pub fn from_state(state: &NumericState) -> Self {
+        let mut stats = Self::new();
+        stats.min = state.min;
+        stats.max = state.max;
+        stats.sum = state.sum;
+        stats.count = state.count;
+        stats.mean = state.mean;
+        stats.m2 = state.m2;
+        stats.m3 = state.m3;
+        stats.m4 = state.m4;
+        stats
+    }
+
+    /// Combines the moments of two disjoint sets of values (Pébay, 2008).
+    pub fn merge(&mut self, other: &NumericStats) {
+        if other.count == 0 { return; }
+        if self.count == 0 {
+            *self = other.clone();
+            return;
+        }
+
+        let na = self.count as f64;
+        let nb = other.count as f64;
+        let n = na + nb;
+        let delta = other.mean - self.mean;
+        let delta2 = delta * delta;
+
+        let m2 = self.m2 + other.m2 + delta2 * na * nb / n;
+        let m3 = self.m3 + other.m3
+            + delta2 * delta * na * nb * (na - nb) / (n * n)
+            + 3.0 * delta * (na * other.m2 - nb * self.m2) / n;
+        let m4 = self.m4 + other.m4
+            + delta2 * delta2 * na * nb * (na * na - na * nb + nb * nb) / (n * n * n)
+            + 6.0 * delta2 * (na * na * other.m2 + nb * nb * self.m2) / (n * n)
+            + 4.0 * delta * (na * other.m3 - nb * self.m3) / n;
+
+        self.mean += delta * nb / n;
+        self.m2 = m2;
+        self.m3 = m3;
+        self.m4 = m4;
+        self.count += other.count;
+        self.sum += other.sum;
+        self.min = self.min.min(other.min);
+        self.max = self.max.max(other.max);
+    }
+
+    pub fn finalize(&mut self) {
         if self.count > 1 {
             let n = self.count as f64;
             self.variance = self.m2 / (n - 1.0);
@@ -90,20 +162,21 @@ impl NumericStats {
             }
         }
 
-        if !samples.is_empty() {
-            samples.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap_or(std::cmp::Ordering::Equal));
-            let values: Vec<f64> = samples.iter().map(|s| s.0).collect();
-            
-            self.median = self.get_quantile(&values, 0.5);
-            self.p25 = self.get_quantile(&values, 0.25);
-            self.p75 = self.get_quantile(&values, 0.75);
-            self.p90 = self.get_quantile(&values, 0.9);
-            self.p95 = self.get_quantile(&values, 0.95);
-            self.p99 = self.get_quantile(&values, 0.99);
-        }
     }
 
-    fn get_quantile(&self, sorted_samples: &[f64], q: f64) -> f64 {
+    /// Fills the median and percentiles from a quantile function.
+    pub fn set_quantiles(&mut self, quantile: impl Fn(f64) -> Option<f64>) {
+        let at = |q: f64| quantile(q).unwrap_or(0.0);
+        self.median = at(0.5);
+        self.p25 = at(0.25);
+        self.p75 = at(0.75);
+        self.p90 = at(0.9);
+        self.p95 = at(0.95);
+        self.p99 = at(0.99);
+    }
+
+    /// Quantile `q` of sorted values, interpolating between neighbours.
+    pub fn quantile_of_sorted(sorted_samples: &[f64], q: f64) -> f64 {
         let n = sorted_samples.len();
         if n == 0 { return 0.0; }
         let pos = q * (n - 1) as f64;
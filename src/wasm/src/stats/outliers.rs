@@ -0,0 +1,239 @@
+//! Outlier fences and the tails that find every row beyond them. Fences
+//! depend on statistics only known at the end, so instead of a second pass
+//! each column keeps its most extreme values on both sides: every outlier
+//! is among them unless a side has more outliers than it holds.
+
+use serde::{Deserialize, Serialize};
+use std::cmp::{Ordering, Reverse};
+use std::collections::BinaryHeap;
+use ts_rs::TS;
+use crate::stats::numeric::NumericStats;
+
+/// Columns with this many numbers or fewer get no outlier analysis.
+pub const MIN_VALUES: u64 = 10;
+
+/// How the fences beyond which values are outliers are computed.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, TS)]
+#[serde(tag = "method", rename_all = "snake_case")]
+#[ts(export)]
+pub enum OutlierMethod {
+    /// Further than `threshold` standard deviations from the mean
+    ZScore { threshold: f64 },
+    /// Modified z-score `0.6745 * |x - median| / MAD` above `threshold`
+    /// (Iglewicz and Hoaglin suggest 3.5). The MAD comes from the numeric
+    /// reservoir when it does not hold every value.
+    ModifiedZScore { threshold: f64 },
+    /// Tukey's fences: more than `multiplier` IQRs outside the quartiles
+    Iqr { multiplier: f64 },
+    /// Below the `lower` or above the `upper` quantile, both in [0, 1]
+    Percentile { lower: f64, upper: f64 },
+}
+
+impl Default for OutlierMethod {
+    fn default() -> Self {
+        OutlierMethod::ZScore { threshold: 3.0 }
+    }
+}
+
+impl OutlierMethod {
+    pub fn validate(&self) -> Result<(), String> {
+        match *self {
+            OutlierMethod::ZScore { threshold } | OutlierMethod::ModifiedZScore { threshold } => {
+                if !(threshold.is_finite() && threshold > 0.0) {
+                    return Err(format!("outlier_method.threshold must be positive, got {}", threshold));
+                }
+            }
+            OutlierMethod::Iqr { multiplier } => {
+                if !(multiplier.is_finite() && multiplier >= 0.0) {
+                    return Err(format!("outlier_method.multiplier must not be negative, got {}", multiplier));
+                }
+            }
+            OutlierMethod::Percentile { lower, upper } => {
+                if !(0.0 <= lower && lower < upper && upper <= 1.0) {
+                    return Err(format!("outlier_method.lower and upper must satisfy 0 <= lower < upper <= 1, got {} and {}", lower, upper));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// The `(lower, upper)` fences, from finalized `stats`, the column's
+    /// `quantile` function, and a sample of its values for the MAD. None
+    /// when the spread is zero for a method that divides by it.
+    pub fn fences(&self, stats: &NumericStats, quantile: impl Fn(f64) -> Option<f64>, sample: &[f64]) -> Option<(f64, f64)> {
+        match *self {
+            OutlierMethod::ZScore { threshold } => {
+                if stats.std_dev <= 0.0 {
+                    return None;
+                }
+                Some((stats.mean - threshold * stats.std_dev, stats.mean + threshold * stats.std_dev))
+            }
+            OutlierMethod::ModifiedZScore { threshold } => {
+                let median = quantile(0.5)?;
+                let mut deviations: Vec<f64> = sample.iter().map(|v| (v - median).abs()).collect();
+                if deviations.is_empty() {
+                    return None;
+                }
+                deviations.sort_by(f64::total_cmp);
+                let mad = NumericStats::quantile_of_sorted(&deviations, 0.5);
+                if mad <= 0.0 {
+                    return None;
+                }
+                let reach = threshold * mad / 0.6745;
+                Some((median - reach, median + reach))
+            }
+            OutlierMethod::Iqr { multiplier } => {
+                let (q1, q3) = (quantile(0.25)?, quantile(0.75)?);
+                let reach = multiplier * (q3 - q1);
+                Some((q1 - reach, q3 + reach))
+            }
+            OutlierMethod::Percentile { lower, upper } => Some((quantile(lower)?, quantile(upper)?)),
+        }
+    }
+}
+
+#[derive(Serialize, Debug, Clone, TS)]
+#[ts(export)]
+pub struct OutlierStats {
+    pub method: OutlierMethod,
+    pub lower_fence: f64,
+    pub upper_fence: f64,
+    /// Values below `lower_fence` or above `upper_fence`; estimated from the
+    /// quantile sketch when a side had more outliers than `max_anomaly_rows`
+    pub count: u64,
+    pub low_count: u64,
+    pub high_count: u64,
+    /// `count` over the column's numeric values
+    pub ratio: f64,
+}
+
+/// A value and its row, ordered by value and then row.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+pub struct TailEntry(pub f64, pub usize);
+
+impl PartialEq for TailEntry {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for TailEntry {}
+
+impl PartialOrd for TailEntry {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for TailEntry {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.total_cmp(&other.0).then(self.1.cmp(&other.1))
+    }
+}
+
+/// The `capacity` smallest and largest values of a column with their rows.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TailAccumulator {
+    capacity: usize,
+    // Max-heap of the smallest values: the top is the first to give way
+    low: BinaryHeap<TailEntry>,
+    high: BinaryHeap<Reverse<TailEntry>>,
+}
+
+impl TailAccumulator {
+    pub fn new(capacity: usize) -> Self {
+        Self { capacity, low: BinaryHeap::new(), high: BinaryHeap::new() }
+    }
+
+    pub fn update(&mut self, val: f64, row_index: usize) {
+        if !val.is_finite() {
+            return;
+        }
+        self.update_low(TailEntry(val, row_index));
+        self.update_high(TailEntry(val, row_index));
+    }
+
+    /// Adds the tails of `other`, whose row indices are shifted by `row_offset`.
+    pub fn merge(&mut self, other: &TailAccumulator, row_offset: usize) {
+        for &TailEntry(val, row) in &other.low {
+            self.update_low(TailEntry(val, row + row_offset));
+        }
+        for &Reverse(TailEntry(val, row)) in &other.high {
+            self.update_high(TailEntry(val, row + row_offset));
+        }
+    }
+
+    fn update_low(&mut self, entry: TailEntry) {
+        if self.low.len() < self.capacity {
+            self.low.push(entry);
+        } else if self.low.peek().is_some_and(|top| entry.0 < top.0) {
+            self.low.pop();
+            self.low.push(entry);
+        }
+    }
+
+    fn update_high(&mut self, entry: TailEntry) {
+        if self.high.len() < self.capacity {
+            self.high.push(Reverse(entry));
+        } else if self.high.peek().is_some_and(|top| entry.0 > top.0.0) {
+            self.high.pop();
+            self.high.push(Reverse(entry));
+        }
+    }
+
+    /// Values below `fence` with their rows, and whether that is all of them:
+    /// true unless the whole low tail lies below the fence.
+    pub fn below(&self, fence: f64) -> (Vec<(f64, usize)>, bool) {
+        let found: Vec<(f64, usize)> = self.low.iter().filter(|e| e.0 < fence).map(|e| (e.0, e.1)).collect();
+        let complete = self.low.len() < self.capacity || found.len() < self.low.len();
+        (found, complete)
+    }
+
+    /// Values above `fence` with their rows, like `below`.
+    pub fn above(&self, fence: f64) -> (Vec<(f64, usize)>, bool) {
+        let found: Vec<(f64, usize)> = self.high.iter().filter(|e| e.0.0 > fence).map(|e| (e.0.0, e.0.1)).collect();
+        let complete = self.high.len() < self.capacity || found.len() < self.high.len();
+        (found, complete)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tails_hold_the_extremes() {
+        let mut first = TailAccumulator::new(3);
+        let mut second = TailAccumulator::new(3);
+        for i in 0..100 {
+            let val = ((i * 37) % 100) as f64;
+            if i < 50 { first.update(val, i + 1) } else { second.update(val, i - 49) }
+        }
+        first.merge(&second, 50);
+
+        let (mut low, complete) = first.below(2.0);
+        low.sort_by(|a, b| a.0.total_cmp(&b.0));
+        assert_eq!(low, vec![(0.0, 1), (1.0, 74)]);
+        assert!(complete);
+        let (high, complete) = first.above(10.0);
+        assert_eq!(high.len(), 3);
+        assert!(!complete);
+    }
+
+    #[test]
+    fn test_fences() {
+        let mut stats = NumericStats::new();
+        for val in 1..=9 {
+            stats.update(val as f64);
+        }
+        stats.finalize();
+        let sample: Vec<f64> = (1..=9).map(|v| v as f64).collect();
+        let quantile = |q: f64| Some(NumericStats::quantile_of_sorted(&sample, q));
+
+        assert_eq!(OutlierMethod::Iqr { multiplier: 1.5 }.fences(&stats, quantile, &sample), Some((-3.0, 13.0)));
+        assert_eq!(OutlierMethod::Percentile { lower: 0.0, upper: 0.5 }.fences(&stats, quantile, &sample), Some((1.0, 5.0)));
+        let (low, high) = OutlierMethod::ModifiedZScore { threshold: 0.6745 }.fences(&stats, quantile, &sample).unwrap();
+        assert!((low - 3.0).abs() < 1e-9 && (high - 7.0).abs() < 1e-9);
+        assert!(OutlierMethod::Percentile { lower: 0.5, upper: 0.5 }.validate().is_err());
+    }
+}
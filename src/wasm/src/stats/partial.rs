@@ -0,0 +1,128 @@
+//! Serializable state of an unfinalized `Profiler`, so that a file split into
+//! ranges can be profiled by several workers and the results merged.
+
+use hyperloglogplus::HyperLogLogPlus;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use crate::quality::duplicates::DuplicateDetector;
+use crate::stats::boolean::BooleanAccumulator;
+use crate::stats::categorical::CategoricalAccumulator;
+use crate::stats::config::ProfilerConfig;
+use crate::stats::datetime::DatetimeAccumulator;
+use crate::stats::exact::ExactAccumulator;
+use crate::stats::histogram::HistogramAccumulator;
+use crate::stats::logical::{DateRange, DecimalStats};
+use crate::stats::numeric::{NumericState, NumericStats};
+use crate::stats::outliers::TailAccumulator;
+use crate::stats::quantiles::KllSketch;
+use crate::stats::sampling::Sampler;
+use crate::stats::shapes::ShapeAccumulator;
+use crate::stats::strings::StringAccumulator;
+use crate::stats::types::LogicalType;
+use crate::stats::{ColumnProfile, SketchHasher};
+
+/// Everything a `Profiler` has accumulated before `finalize`. The layout is
+/// internal: JS should pass it between workers unchanged.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PartialProfile {
+    pub config: ProfilerConfig,
+    pub total_rows: u64,
+    pub first_rows: Vec<usize>,
+    pub columns: Vec<ColumnState>,
+    pub duplicates: DuplicateDetector,
+    pub sampler: Sampler,
+}
+
+/// The accumulators of one `ColumnProfile`.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ColumnState {
+    pub name: String,
+    pub count: u64,
+    pub missing: u64,
+    pub integer_count: u64,
+    pub numeric_count: u64,
+    pub total_valid: u64,
+    pub min_length: Option<usize>,
+    pub max_length: Option<usize>,
+    pub logical_type: Option<LogicalType>,
+    pub date_range: Option<DateRange>,
+    pub decimal_stats: Option<DecimalStats>,
+    pub hll: HyperLogLogPlus<String, SketchHasher>,
+    pub exact: ExactAccumulator,
+    pub numeric: Option<NumericState>,
+    pub reservoir: Option<HistogramAccumulator>,
+    pub quantiles: Option<KllSketch>,
+    pub tails: Option<TailAccumulator>,
+    pub categorical: CategoricalAccumulator,
+    pub shapes: ShapeAccumulator,
+    pub boolean: BooleanAccumulator,
+    pub datetime: DatetimeAccumulator,
+    pub strings: StringAccumulator,
+    pub sample_values: Vec<String>,
+    pub pii_samples: Vec<String>,
+    pub pii_rows: Vec<usize>,
+    pub missing_rows: Vec<usize>,
+}
+
+impl ColumnState {
+    pub fn capture(profile: &ColumnProfile) -> Self {
+        Self {
+            name: profile.name.clone(),
+            count: profile.base_stats.count,
+            missing: profile.base_stats.missing,
+            integer_count: profile.integer_count,
+            numeric_count: profile.numeric_count,
+            total_valid: profile.total_valid,
+            min_length: profile.min_length,
+            max_length: profile.max_length,
+            logical_type: profile.logical_type.clone(),
+            date_range: profile.date_range.clone(),
+            decimal_stats: profile.decimal_stats.clone(),
+            hll: profile.hll.clone(),
+            exact: profile.exact.clone(),
+            numeric: profile.numeric_stats.as_ref().map(NumericStats::state),
+            reservoir: profile.hist_acc.clone(),
+            quantiles: profile.quantile_sketch.clone(),
+            tails: profile.tails.clone(),
+            categorical: profile.cat_acc.clone(),
+            shapes: profile.shape_acc.clone(),
+            boolean: profile.bool_acc.clone(),
+            datetime: profile.datetime.clone(),
+            strings: profile.string_acc.clone(),
+            sample_values: profile.sample_values.clone(),
+            pii_samples: profile.pii_samples.clone(),
+            pii_rows: profile.pii_rows.clone(),
+            missing_rows: profile.missing_rows.clone(),
+        }
+    }
+
+    pub fn restore(self, config: Arc<ProfilerConfig>) -> ColumnProfile {
+        let mut profile = ColumnProfile::with_config(self.name, config);
+        profile.base_stats.count = self.count;
+        profile.base_stats.missing = self.missing;
+        profile.integer_count = self.integer_count;
+        profile.numeric_count = self.numeric_count;
+        profile.total_valid = self.total_valid;
+        profile.min_length = self.min_length;
+        profile.max_length = self.max_length;
+        profile.logical_type = self.logical_type;
+        profile.date_range = self.date_range;
+        profile.decimal_stats = self.decimal_stats;
+        profile.hll = self.hll;
+        profile.exact = self.exact;
+        profile.numeric_stats = self.numeric.as_ref().map(NumericStats::from_state);
+        profile.hist_acc = self.reservoir;
+        profile.quantile_sketch = self.quantiles;
+        profile.tails = self.tails;
+        profile.cat_acc = self.categorical;
+        profile.shape_acc = self.shapes;
+        profile.bool_acc = self.boolean;
+        profile.datetime = self.datetime;
+        profile.string_acc = self.strings;
+        profile.sample_values = self.sample_values;
+        profile.pii_samples = self.pii_samples;
+        profile.pii_rows = self.pii_rows;
+        profile.missing_rows = self.missing_rows;
+        profile
+    }
+}
@@ -1,6 +1,10 @@
 use serde::Serialize;
+use std::sync::Arc;
 use ts_rs::TS;
 use crate::stats::ColumnProfile;
+use crate::stats::config::ProfilerConfig;
+use crate::stats::partial::{ColumnState, PartialProfile};
+use crate::stats::sampling::{Sampler, SamplingSummary, StopReason};
 
 #[derive(Serialize, Debug, TS)]
 #[ts(export)]
@@ -9,43 +13,205 @@ pub struct ProfilerResult {
     pub total_rows: u64,
     pub duplicate_issues: Vec<crate::quality::QualityIssue>,
     pub avro_schema: Option<String>,
+    pub avro_union_stats: Option<Vec<crate::parser::avro::AvroUnionStats>>,
+    pub avro_schema_ids: Option<Vec<crate::parser::avro_messages::AvroSchemaIdCount>>,
+    pub orc_metadata: Option<crate::parser::orc::OrcMetadata>,
+    pub dataset: Option<crate::parser::dataset::DatasetMetadata>,
+    /// The configuration this profile was produced with
+    pub config: ProfilerConfig,
+    /// Coverage of a sampled or limited profile; None for a full read
+    pub sampling: Option<SamplingSummary>,
 }
 
 pub struct Profiler {
     column_profiles: Vec<ColumnProfile>,
     total_rows: u64,
+    /// Row numbers of the first `max_anomaly_rows` profiled rows, listed
+    /// as missing in columns added later
+    first_rows: Vec<usize>,
     headers: Vec<String>,
     duplicate_detector: crate::quality::duplicates::DuplicateDetector,
+    config: Arc<ProfilerConfig>,
+    sampler: Sampler,
+    /// Input bytes read, the rows the source parsed from them (including
+    /// rows past a row limit) and the whole input's size, set by the driver
+    pub bytes_read: Option<u64>,
+    pub rows_parsed: Option<u64>,
+    pub input_size: Option<u64>,
     pub avro_schema: Option<String>,
+    pub avro_union_stats: Option<Vec<crate::parser::avro::AvroUnionStats>>,
+    pub avro_schema_ids: Option<Vec<crate::parser::avro_messages::AvroSchemaIdCount>>,
+    pub orc_metadata: Option<crate::parser::orc::OrcMetadata>,
+    pub dataset: Option<crate::parser::dataset::DatasetMetadata>,
 }
 
 impl Profiler {
-    pub fn new(headers: Vec<String>) -> Self {
+    /// The config must have passed `ProfilerConfig::validate`.
+    pub fn with_config(headers: Vec<String>, config: ProfilerConfig) -> Self {
+        let config = Arc::new(config);
         let column_profiles = headers.iter()
-            .map(|name| ColumnProfile::new(name.clone()))
+            .map(|name| ColumnProfile::with_config(name.clone(), config.clone()))
             .collect();
             
         Self {
             column_profiles,
             total_rows: 0,
+            first_rows: Vec::new(),
             headers,
             duplicate_detector: crate::quality::duplicates::DuplicateDetector::new(),
+            sampler: Sampler::new(&config),
+            config,
+            bytes_read: None,
+            rows_parsed: None,
+            input_size: None,
             avro_schema: None,
+            avro_union_stats: None,
+            avro_schema_ids: None,
+            orc_metadata: None,
+            dataset: None,
         }
     }
 
+    pub fn headers(&self) -> &[String] {
+        &self.headers
+    }
+
+    /// Declares a column's logical type from the source schema.
+    pub fn set_logical_type(&mut self, column: usize, logical_type: crate::stats::types::LogicalType) {
+        if let Some(profile) = self.column_profiles.get_mut(column) {
+            profile.set_logical_type(logical_type);
+        }
+    }
+
+    /// Appends a column first seen after rows were already profiled; those
+    /// earlier rows count as missing values for it. Returns the column index.
+    pub fn add_column(&mut self, name: String) -> usize {
+        let mut profile = ColumnProfile::with_config(name.clone(), self.config.clone());
+        profile.update_missing(self.total_rows, &self.first_rows);
+        self.column_profiles.push(profile);
+        self.headers.push(name);
+        self.column_profiles.len() - 1
+    }
+
     pub fn update_batch(&mut self, rows: &[Vec<String>]) {
-        // Process duplicates
-        self.duplicate_detector.process_batch(rows);
-        
+        if self.sampler.is_passthrough() {
+            self.duplicate_detector.process_batch(&self.headers, rows);
+            for row in rows {
+                let row_number = self.sampler.admit().unwrap_or_default();
+                self.profile_row(row, row_number);
+            }
+            return;
+        }
+
+        let mut sampled = Vec::new();
         for row in rows {
-            self.total_rows += 1;
-            for (i, value) in row.iter().enumerate() {
-                if i < self.column_profiles.len() {
-                    self.column_profiles[i].update(value, self.total_rows as usize);
+            match self.sampler.admit() {
+                Some(row_number) => {
+                    self.profile_row(row, row_number);
+                    sampled.push(row.clone());
                 }
+                None if self.sampler.is_stopped() => break,
+                None => {}
             }
         }
+        self.duplicate_detector.process_batch(&self.headers, &sampled);
+    }
+
+    /// `row_number` is the 1-based position among all rows read, which
+    /// differs from `total_rows` when sampling.
+    fn profile_row(&mut self, row: &[String], row_number: usize) {
+        self.total_rows += 1;
+        if self.first_rows.len() < self.config.max_anomaly_rows {
+            self.first_rows.push(row_number);
+        }
+        // Rows written before a column was discovered are shorter than
+        // the header; the absent trailing values count as missing
+        for (i, profile) in self.column_profiles.iter_mut().enumerate() {
+            let value = row.get(i).map(String::as_str).unwrap_or("");
+            profile.update(value, row_number);
+        }
+    }
+
+    /// Quantile `q` of a numeric column, see `ColumnProfile::quantile`.
+    pub fn quantile(&self, column: &str, q: f64) -> Option<f64> {
+        let index = self.headers.iter().position(|h| h == column)?;
+        self.column_profiles[index].quantile(q)
+    }
+
+    /// True once a sampling limit was reached; further rows are ignored.
+    pub fn is_stopped(&self) -> bool {
+        self.sampler.is_stopped()
+    }
+
+    /// Stops profiling because the byte limit was reached.
+    pub fn stop_at_byte_limit(&mut self) {
+        self.sampler.stop(StopReason::ByteLimit);
+    }
+
+    /// Captures the accumulated state without finalizing. Format metadata
+    /// (Avro schemas, ORC footers, dataset info) is not included.
+    pub fn to_partial(&self) -> PartialProfile {
+        PartialProfile {
+            config: (*self.config).clone(),
+            total_rows: self.total_rows,
+            first_rows: self.first_rows.clone(),
+            columns: self.column_profiles.iter().map(ColumnState::capture).collect(),
+            duplicates: self.duplicate_detector.clone(),
+            sampler: self.sampler.clone(),
+        }
+    }
+
+    pub fn from_partial(partial: PartialProfile) -> Result<Self, String> {
+        partial.config.validate()?;
+        let config = Arc::new(partial.config);
+        let headers = partial.columns.iter().map(|c| c.name.clone()).collect();
+        let column_profiles = partial.columns.into_iter().map(|c| c.restore(config.clone())).collect();
+        let mut profiler = Self::with_config(Vec::new(), ProfilerConfig::clone(&config));
+        profiler.column_profiles = column_profiles;
+        profiler.headers = headers;
+        profiler.config = config;
+        profiler.total_rows = partial.total_rows;
+        profiler.first_rows = partial.first_rows;
+        profiler.duplicate_detector = partial.duplicates;
+        profiler.sampler = partial.sampler;
+        Ok(profiler)
+    }
+
+    /// Adds the rows profiled by `other` as if they followed this profiler's
+    /// rows. Columns are matched by name; a column only one side has counts
+    /// the other side's rows as missing. Both must use the same config.
+    pub fn merge(&mut self, other: &Profiler) -> Result<(), String> {
+        if self.config != other.config {
+            return Err("Cannot merge profiles produced with different configurations".to_string());
+        }
+        let row_offset = self.sampler.rows_read() as usize;
+
+        let mut mapping = Vec::with_capacity(other.headers.len());
+        for name in &other.headers {
+            let column = match self.headers.iter().position(|h| h == name) {
+                Some(column) => column,
+                None => self.add_column(name.clone()),
+            };
+            mapping.push(column);
+        }
+
+        for (theirs, &column) in other.column_profiles.iter().zip(&mapping) {
+            self.column_profiles[column].merge(theirs, row_offset)?;
+        }
+        let other_rows: Vec<usize> = other.first_rows.iter().map(|row| row + row_offset).collect();
+        for (column, profile) in self.column_profiles.iter_mut().enumerate() {
+            if !mapping.contains(&column) {
+                profile.update_missing(other.total_rows, &other_rows);
+            }
+        }
+        let room = self.config.max_anomaly_rows.saturating_sub(self.first_rows.len());
+        self.first_rows.extend(other_rows.into_iter().take(room));
+
+        // Fingerprints key cells by column name, so they need no remapping
+        self.duplicate_detector.merge(&other.duplicate_detector);
+        self.total_rows += other.total_rows;
+        self.sampler.merge(&other.sampler);
+        Ok(())
     }
 
     pub fn finalize(&mut self) -> ProfilerResult {
@@ -64,6 +230,101 @@ impl Profiler {
             total_rows: self.total_rows,
             duplicate_issues,
             avro_schema: self.avro_schema.clone(),
+            avro_union_stats: self.avro_union_stats.clone(),
+            avro_schema_ids: self.avro_schema_ids.clone(),
+            orc_metadata: self.orc_metadata.clone(),
+            dataset: self.dataset.clone(),
+            config: (*self.config).clone(),
+            sampling: self.config.is_sampled()
+                .then(|| self.sampler.summary(self.total_rows, self.bytes_read, self.rows_parsed, self.input_size)),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rows(values: &[&str]) -> Vec<Vec<String>> {
+        values.iter().map(|line| line.split(',').map(|v| v.to_string()).collect()).collect()
+    }
+
+    #[test]
+    fn test_partial_round_trip_and_merge() {
+        let headers = vec!["id".to_string(), "city".to_string()];
+        let mut first = Profiler::with_config(headers.clone(), ProfilerConfig::default());
+        first.update_batch(&rows(&["1,Oslo", "2,", "3,Rome"]));
+        let mut second = Profiler::with_config(vec!["city".to_string(), "id".to_string(), "score".to_string()], ProfilerConfig::default());
+        second.update_batch(&rows(&["Oslo,4,1.5", ",5,2.5", "Rome,3,"]));
+
+        let json = serde_json::to_string(&second.to_partial()).unwrap();
+        let restored = Profiler::from_partial(serde_json::from_str(&json).unwrap()).unwrap();
+        first.merge(&restored).unwrap();
+        let result = first.finalize();
+
+        assert_eq!(result.total_rows, 6);
+        let names: Vec<&str> = result.column_profiles.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, vec!["id", "city", "score"]);
+        let city = &result.column_profiles[1];
+        assert_eq!(city.base_stats.missing, 2);
+        assert_eq!(city.missing_rows, vec![2, 5]);
+        assert_eq!(city.base_stats.distinct_estimate, 2);
+        // The first three rows predate the score column
+        assert_eq!(result.column_profiles[2].base_stats.missing, 4);
+        // "3,Rome" appears in both halves once the columns are realigned
+        assert_eq!(result.duplicate_issues.len(), 1);
+    }
+
+    #[test]
+    fn test_added_columns_list_sampled_rows_as_missing() {
+        let config = ProfilerConfig {
+            sampling: crate::stats::sampling::SamplingMode::Systematic { every: 2 },
+            null_tokens: vec!["-".to_string()],
+            ..ProfilerConfig::default()
+        };
+        let mut first = Profiler::with_config(vec!["id".to_string()], config.clone());
+        first.update_batch(&rows(&["1", "2", "3", "4", "5"]));
+        let score = first.add_column("score".to_string());
+        first.update_batch(&rows(&["6,1.5", "7,2.5"]));
+
+        let mut second = Profiler::with_config(vec!["score".to_string()], config);
+        second.update_batch(&rows(&["1", "2", "3"]));
+        first.merge(&second).unwrap();
+        let result = first.finalize();
+
+        // Rows 1, 3 and 5 were sampled before the score column existed
+        let score = &result.column_profiles[score];
+        assert_eq!(score.base_stats.missing, 3);
+        assert_eq!(score.missing_rows, vec![1, 3, 5]);
+        // The second profiler's rows 1 and 3 follow the first's 7 rows
+        assert_eq!(result.column_profiles[0].missing_rows, vec![8, 10]);
+    }
+
+    #[test]
+    fn test_merge_rejects_different_configs() {
+        let mut first = Profiler::with_config(vec!["a".to_string()], ProfilerConfig::default());
+        let config = ProfilerConfig { top_values: 3, ..ProfilerConfig::default() };
+        let second = Profiler::with_config(vec!["a".to_string()], config);
+        assert!(first.merge(&second).is_err());
+    }
+
+    #[test]
+    fn test_identical_runs_give_identical_results() {
+        let config = ProfilerConfig {
+            histogram_reservoir_size: 50,
+            sampling: crate::stats::sampling::SamplingMode::Bernoulli { rate: 0.5, seed: None },
+            seed: 99,
+            ..ProfilerConfig::default()
+        };
+        let data: Vec<String> = (0..2000).map(|i| format!("{},{},2025-{:02}-{:02},code{}", i, i * 7 % 301, i % 12 + 1, i % 28 + 1, i % 40)).collect();
+        let run = || {
+            let mut profiler = Profiler::with_config(vec!["id".into(), "n".into(), "day".into(), "code".into()], config.clone());
+            profiler.update_batch(&rows(&data.iter().map(String::as_str).collect::<Vec<_>>()));
+            serde_json::to_string(&profiler.finalize()).unwrap()
+        };
+        // Without `reference_time` nothing depends on the clock
+        let result = run();
+        assert!(result.contains(r#""future_count":null"#));
+        assert_eq!(result, run());
+    }
+}
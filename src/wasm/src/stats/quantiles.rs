@@ -0,0 +1,186 @@
+//! KLL quantile sketch (Karnin, Lang and Liberty, 2016): a stack of
+//! compactors where an item at level `h` stands for `2^h` values. Its
+//! size only grows with the log of the values seen, and two sketches
+//! merge into one with the same accuracy.
+
+use serde::{Deserialize, Serialize};
+use crate::stats::rng::SplitMix64;
+
+/// Smallest capacity of any compactor.
+const MIN_CAPACITY: usize = 2;
+/// Capacity ratio between adjacent levels.
+const DECAY: f64 = 2.0 / 3.0;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct KllSketch {
+    k: usize,
+    n: u64,
+    compactors: Vec<Vec<f64>>,
+    rng: SplitMix64,
+}
+
+impl KllSketch {
+    /// `k` is the top compactor's capacity; larger is more accurate.
+    pub fn new(k: usize, seed: u64) -> Self {
+        Self { k, n: 0, compactors: vec![Vec::new()], rng: SplitMix64::new(seed) }
+    }
+
+    /// Approximate normalized rank error of quantiles for a given `k`, as
+    /// fitted for the DataSketches KLL implementation (99% confidence).
+    pub fn rank_error(k: usize) -> f64 {
+        2.296 / (k as f64).powf(0.9723)
+    }
+
+    pub fn k(&self) -> usize {
+        self.k
+    }
+
+    /// Adds a value; NaN and infinities are ignored, as in `NumericStats`.
+    pub fn update(&mut self, val: f64) {
+        if !val.is_finite() {
+            return;
+        }
+        self.n += 1;
+        self.compactors[0].push(val);
+        if self.size() >= self.max_size() {
+            self.compress();
+        }
+    }
+
+    pub fn merge(&mut self, other: &KllSketch) {
+        while self.compactors.len() < other.compactors.len() {
+            self.compactors.push(Vec::new());
+        }
+        for (ours, theirs) in self.compactors.iter_mut().zip(&other.compactors) {
+            ours.extend_from_slice(theirs);
+        }
+        self.n += other.n;
+        while self.size() >= self.max_size() {
+            self.compress();
+        }
+    }
+
+    /// The value at quantile `q` in [0, 1], interpolated between retained
+    /// items like `NumericStats::quantile_of_sorted`; exact while no
+    /// compaction happened. None when empty or `q` is out of range.
+    pub fn quantile(&self, q: f64) -> Option<f64> {
+        if self.n == 0 || !(0.0..=1.0).contains(&q) {
+            return None;
+        }
+        let mut items: Vec<(f64, u64)> = self.weighted_items().collect();
+        items.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+        // Each item sits at the middle of the ranks it stands for
+        let target = q * (self.n - 1) as f64;
+        let mut before = 0u64;
+        let mut previous: Option<(f64, f64)> = None;
+        for (val, weight) in items {
+            let rank = before as f64 + (weight - 1) as f64 / 2.0;
+            if rank >= target {
+                return Some(match previous {
+                    Some((prev_rank, prev_val)) if rank > prev_rank => {
+                        prev_val + (val - prev_val) * (target - prev_rank) / (rank - prev_rank)
+                    }
+                    _ => val,
+                });
+            }
+            previous = Some((rank, val));
+            before += weight;
+        }
+        previous.map(|(_, val)| val)
+    }
+
+    /// Estimated number of values below `val`.
+    pub fn count_below(&self, val: f64) -> u64 {
+        self.weighted_items().filter(|&(item, _)| item < val).map(|(_, weight)| weight).sum()
+    }
+
+    /// Estimated number of values above `val`.
+    pub fn count_above(&self, val: f64) -> u64 {
+        self.weighted_items().filter(|&(item, _)| item > val).map(|(_, weight)| weight).sum()
+    }
+
+    fn weighted_items(&self) -> impl Iterator<Item = (f64, u64)> + '_ {
+        self.compactors.iter().enumerate()
+            .flat_map(|(level, items)| items.iter().map(move |&val| (val, 1u64 << level)))
+    }
+
+    fn size(&self) -> usize {
+        self.compactors.iter().map(Vec::len).sum()
+    }
+
+    fn capacity(&self, level: usize) -> usize {
+        let depth = self.compactors.len() - level - 1;
+        ((self.k as f64 * DECAY.powi(depth as i32)).ceil() as usize).max(MIN_CAPACITY)
+    }
+
+    fn max_size(&self) -> usize {
+        (0..self.compactors.len()).map(|level| self.capacity(level)).sum()
+    }
+
+    /// Halves the lowest full compactor: sorted, every other item from a
+    /// random offset moves up a level with twice the weight.
+    fn compress(&mut self) {
+        for level in 0..self.compactors.len() {
+            if self.compactors[level].len() < self.capacity(level) {
+                continue;
+            }
+            if level + 1 == self.compactors.len() {
+                self.compactors.push(Vec::new());
+            }
+            let mut items = std::mem::take(&mut self.compactors[level]);
+            items.sort_by(|a, b| a.total_cmp(b));
+            // An odd item out stays behind
+            if items.len() % 2 == 1 {
+                self.compactors[level].push(items.pop().unwrap());
+            }
+            let offset = (self.rng.next_u64() & 1) as usize;
+            let promoted = items.into_iter().skip(offset).step_by(2);
+            self.compactors[level + 1].extend(promoted);
+            if self.size() < self.max_size() {
+                break;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_quantiles_within_rank_error() {
+        let n = 200_000;
+        let mut first = KllSketch::new(200, 1);
+        let mut second = KllSketch::new(200, 2);
+        // Values in scrambled order, so the sketch sees no sorted runs
+        for i in 0..n {
+            let val = ((i as u64 * 7_919) % n as u64) as f64;
+            if i % 2 == 0 { first.update(val) } else { second.update(val) }
+        }
+        first.merge(&second);
+        assert_eq!(first.n, n as u64);
+        assert!(first.size() < 2_000);
+        let below = first.count_below(10_000.0) as f64;
+        assert!((below - 10_000.0).abs() < KllSketch::rank_error(200) * n as f64);
+
+        let tolerance = KllSketch::rank_error(200) * n as f64;
+        for q in [0.01, 0.25, 0.5, 0.9, 0.99] {
+            let got = first.quantile(q).unwrap();
+            assert!((got - q * (n - 1) as f64).abs() < tolerance, "q={} got={}", q, got);
+        }
+    }
+
+    #[test]
+    fn test_small_inputs_are_exact() {
+        let mut sketch = KllSketch::new(200, 0);
+        for val in [5.0, 1.0, 4.0, 2.0, f64::NAN, 3.0] {
+            sketch.update(val);
+        }
+        assert_eq!(sketch.quantile(0.5), Some(3.0));
+        assert_eq!(sketch.quantile(0.25), Some(2.0));
+        assert_eq!(sketch.quantile(0.9), Some(4.6));
+        assert_eq!(sketch.quantile(1.5), None);
+        assert_eq!(KllSketch::new(200, 0).quantile(0.5), None);
+    }
+}
@@ -0,0 +1,200 @@
+//! Row sampling and early-stop limits, applied by `Profiler::update_batch`.
+
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+use crate::stats::config::ProfilerConfig;
+use crate::stats::rng::SplitMix64;
+
+/// Which of the rows read are profiled.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, TS)]
+#[serde(tag = "mode", rename_all = "snake_case")]
+#[ts(export)]
+pub enum SamplingMode {
+    Full,
+    /// The first `rows` rows, then stop reading
+    Head { rows: usize },
+    /// Each row independently with probability `rate`. `seed` defaults to
+    /// the config's `seed`.
+    Bernoulli { rate: f64, seed: Option<u64> },
+    /// Every `every`-th row, starting with the first
+    Systematic { every: usize },
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, TS)]
+#[serde(rename_all = "snake_case")]
+#[ts(export)]
+pub enum StopReason {
+    /// `max_rows` or the `head` row count was reached
+    RowLimit,
+    /// `max_bytes` was reached
+    ByteLimit,
+}
+
+/// How much of the input a sampled or limited profile covers.
+#[derive(Serialize, Debug, Clone, TS)]
+#[ts(export)]
+pub struct SamplingSummary {
+    pub mode: SamplingMode,
+    /// Rows the source produced before reading stopped
+    pub rows_read: u64,
+    pub rows_profiled: u64,
+    pub bytes_read: Option<u64>,
+    /// None when the whole input was read
+    pub stop_reason: Option<StopReason>,
+    /// Share of the input read: 1 when complete, else bytes read over the
+    /// input size when JS provided it
+    pub fraction_read: Option<f64>,
+    /// Rows in the whole input: exact when complete, else extrapolated from
+    /// the rows parsed per byte read
+    pub estimated_total_rows: Option<u64>,
+    /// Multiply profiled counts by this to estimate counts for the whole input
+    pub scale_factor: Option<f64>,
+}
+
+/// Decides row by row whether to profile, and when to stop reading.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Sampler {
+    mode: SamplingMode,
+    row_limit: Option<usize>,
+    rows_read: u64,
+    rng: SplitMix64,
+    stop_reason: Option<StopReason>,
+}
+
+impl Sampler {
+    pub fn new(config: &ProfilerConfig) -> Self {
+        let head = match config.sampling {
+            SamplingMode::Head { rows } => Some(rows),
+            _ => None,
+        };
+        let row_limit = match (head, config.max_rows) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        };
+        let seed = match config.sampling {
+            SamplingMode::Bernoulli { seed: Some(seed), .. } => seed,
+            _ => config.seed,
+        };
+        Self { mode: config.sampling.clone(), row_limit, rows_read: 0, rng: SplitMix64::new(seed), stop_reason: None }
+    }
+
+    /// Takes the next row read. Returns its 1-based row number if it
+    /// should be profiled.
+    pub fn admit(&mut self) -> Option<usize> {
+        if self.stop_reason.is_some() {
+            return None;
+        }
+        if self.row_limit.is_some_and(|limit| self.rows_read >= limit as u64) {
+            self.stop(StopReason::RowLimit);
+            return None;
+        }
+        self.rows_read += 1;
+        let keep = match self.mode {
+            SamplingMode::Full | SamplingMode::Head { .. } => true,
+            SamplingMode::Bernoulli { rate, .. } => self.rng.next_unit() < rate,
+            SamplingMode::Systematic { every } => (self.rows_read - 1).is_multiple_of(every as u64),
+        };
+        keep.then_some(self.rows_read as usize)
+    }
+
+    /// Whether every row would be profiled, so batches need not be filtered.
+    pub fn is_passthrough(&self) -> bool {
+        self.mode == SamplingMode::Full && self.row_limit.is_none() && self.stop_reason.is_none()
+    }
+
+    pub fn stop(&mut self, reason: StopReason) {
+        self.stop_reason.get_or_insert(reason);
+    }
+
+    /// True once a limit was hit; the rest of the input can be skipped.
+    pub fn is_stopped(&self) -> bool {
+        self.stop_reason.is_some() || self.row_limit.is_some_and(|limit| self.rows_read >= limit as u64)
+    }
+
+    pub fn rows_read(&self) -> u64 {
+        self.rows_read
+    }
+
+    /// Adds the rows read by another worker's sampler.
+    pub fn merge(&mut self, other: &Sampler) {
+        self.rows_read += other.rows_read;
+        if let Some(reason) = other.stop_reason {
+            self.stop(reason);
+        }
+    }
+
+    /// `rows_parsed` are the rows the source produced from `bytes_read`. It
+    /// can exceed `rows_read`, as the chunk that crosses a row limit is
+    /// parsed whole; extrapolating `rows_read` over those bytes would
+    /// underestimate the total.
+    pub fn summary(&self, rows_profiled: u64, bytes_read: Option<u64>, rows_parsed: Option<u64>, input_size: Option<u64>) -> SamplingSummary {
+        let stop_reason = match self.stop_reason {
+            // Reaching the row limit is treated as stopping early, even if
+            // the input happened to end there
+            None if self.is_stopped() => Some(StopReason::RowLimit),
+            reason => reason,
+        };
+        let (fraction_read, estimated_total_rows) = match (stop_reason, bytes_read, input_size) {
+            (None, _, _) => (Some(1.0), Some(self.rows_read)),
+            (Some(_), Some(read), Some(size)) if read > 0 => {
+                let fraction = (read as f64 / size as f64).min(1.0);
+                let rows = rows_parsed.unwrap_or(0).max(self.rows_read);
+                (Some(fraction), Some((rows as f64 / fraction).round() as u64))
+            }
+            _ => (None, None),
+        };
+        let scale_factor = match estimated_total_rows {
+            Some(total) if rows_profiled > 0 => Some(total as f64 / rows_profiled as f64),
+            _ => None,
+        };
+        SamplingSummary {
+            mode: self.mode.clone(),
+            rows_read: self.rows_read,
+            rows_profiled,
+            bytes_read,
+            stop_reason,
+            fraction_read,
+            estimated_total_rows,
+            scale_factor,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn admitted(config: &ProfilerConfig, rows: usize) -> Vec<usize> {
+        let mut sampler = Sampler::new(config);
+        (0..rows).filter_map(|_| sampler.admit()).collect()
+    }
+
+    #[test]
+    fn test_sampling_modes() {
+        let systematic = ProfilerConfig { sampling: SamplingMode::Systematic { every: 3 }, ..ProfilerConfig::default() };
+        assert_eq!(admitted(&systematic, 10), vec![1, 4, 7, 10]);
+
+        let head = ProfilerConfig { sampling: SamplingMode::Head { rows: 5 }, max_rows: Some(3), ..ProfilerConfig::default() };
+        assert_eq!(admitted(&head, 10), vec![1, 2, 3]);
+
+        let bernoulli = ProfilerConfig { sampling: SamplingMode::Bernoulli { rate: 0.25, seed: Some(7) }, ..ProfilerConfig::default() };
+        let rows = admitted(&bernoulli, 10_000);
+        assert!((2_200..2_800).contains(&rows.len()));
+        assert_eq!(rows, admitted(&bernoulli, 10_000));
+    }
+
+    #[test]
+    fn test_summary_extrapolates_from_bytes() {
+        let config = ProfilerConfig { max_rows: Some(100), ..ProfilerConfig::default() };
+        let mut sampler = Sampler::new(&config);
+        while sampler.admit().is_some() {}
+
+        let summary = sampler.summary(100, Some(2_000), None, Some(10_000));
+        assert_eq!(summary.stop_reason, Some(StopReason::RowLimit));
+        assert_eq!(summary.fraction_read, Some(0.2));
+        assert_eq!(summary.estimated_total_rows, Some(500));
+        assert_eq!(summary.scale_factor, Some(5.0));
+        assert_eq!(sampler.summary(100, Some(2_000), Some(400), Some(10_000)).estimated_total_rows, Some(2_000));
+        assert_eq!(sampler.summary(100, Some(2_000), None, None).estimated_total_rows, None);
+    }
+}
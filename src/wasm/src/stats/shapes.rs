@@ -0,0 +1,293 @@
+//! Value shapes: each value reduced to a mask of character classes, so
+//! that "AB-1234" and "XY-9876" both read `AA-9{4}`.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use ts_rs::TS;
+
+/// Distinct example values kept per shape.
+const MAX_EXAMPLES: usize = 3;
+/// Masks longer than this are cut, as long values rarely share a shape.
+const MAX_MASK_LEN: usize = 64;
+/// Uppercase runs up to this length are spelled out (`AAA`), longer ones
+/// are counted (`A{12}`).
+const MAX_LITERAL_RUN: usize = 4;
+
+/// Share of values a shape needs to be reported as the dominant format.
+const DOMINANT_SHARE: f64 = 0.5;
+
+#[derive(Serialize, Debug, Clone, TS)]
+#[ts(export)]
+pub struct ShapeEntry {
+    pub shape: String,
+    pub count: u64,
+    pub percentage: f64,
+    pub examples: Vec<String>,
+}
+
+#[derive(Serialize, Debug, Clone, TS)]
+#[ts(export)]
+pub struct ShapeStats {
+    pub top_shapes: Vec<ShapeEntry>,
+    /// Distinct shapes seen, up to `max_categorical_keys`
+    pub distinct_shapes: u64,
+    /// Shape of at least half of the values, if any
+    pub dominant_format: Option<String>,
+    pub dominant_ratio: f64,
+    /// Values not matching the dominant format
+    pub nonconforming_count: u64,
+    /// Rows (1-based) not matching the dominant format, up to `max_anomaly_rows`.
+    /// Rows of shapes past the `max_categorical_keys` limit are not listed.
+    pub nonconforming_rows: Vec<usize>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+struct ShapeCount {
+    count: u64,
+    examples: Vec<String>,
+    rows: Vec<usize>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ShapeAccumulator {
+    shapes: HashMap<String, ShapeCount>,
+    total_count: u64,
+    max_shapes: usize,
+    top_n: usize,
+    max_rows: usize,
+    // Rows kept across all shapes, at most `2 * max_rows`. The dominant
+    // shape holds at most half, so the first `max_rows` nonconforming rows
+    // always fit.
+    stored_rows: usize,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum CharClass {
+    Upper,
+    Lower,
+    Digit,
+    // Letters without case, e.g. CJK
+    Letter,
+    Space,
+    Other(char),
+}
+
+impl CharClass {
+    fn of(c: char) -> Self {
+        if c.is_ascii_digit() {
+            CharClass::Digit
+        } else if c.is_uppercase() {
+            CharClass::Upper
+        } else if c.is_lowercase() {
+            CharClass::Lower
+        } else if c.is_alphabetic() {
+            CharClass::Letter
+        } else if c.is_whitespace() {
+            CharClass::Space
+        } else {
+            CharClass::Other(c)
+        }
+    }
+}
+
+/// Mask of a value: `A` uppercase, `a` lowercase, `L` other letters, `9`
+/// digits, a single space for any whitespace run, and punctuation kept
+/// as is. Digit runs carry their length, e.g. "123-45-6789" becomes
+/// `9{3}-9{2}-9{4}`. Lowercase and uncased letter runs are collapsed to
+/// `a+`/`L+` since word lengths vary, e.g. "John Smith" becomes `Aa+ Aa+`.
+pub fn shape_of(value: &str) -> String {
+    let mut mask = String::new();
+    let mut chars = value.chars().map(CharClass::of).peekable();
+    while let Some(class) = chars.next() {
+        let mut run = 1;
+        while chars.peek() == Some(&class) {
+            chars.next();
+            run += 1;
+        }
+        match class {
+            CharClass::Upper if run <= MAX_LITERAL_RUN => mask.extend(std::iter::repeat_n('A', run)),
+            CharClass::Digit if run == 1 => mask.push('9'),
+            CharClass::Upper | CharClass::Digit => {
+                let symbol = if class == CharClass::Upper { 'A' } else { '9' };
+                mask.push_str(&format!("{}{{{}}}", symbol, run));
+            }
+            CharClass::Lower | CharClass::Letter => {
+                mask.push(if class == CharClass::Lower { 'a' } else { 'L' });
+                if run > 1 {
+                    mask.push('+');
+                }
+            }
+            CharClass::Space => mask.push(' '),
+            CharClass::Other(c) => mask.extend(std::iter::repeat_n(c, run)),
+        }
+        if mask.len() >= MAX_MASK_LEN {
+            let mut end = MAX_MASK_LEN;
+            while !mask.is_char_boundary(end) {
+                end -= 1;
+            }
+            mask.truncate(end);
+            mask.push('…');
+            break;
+        }
+    }
+    mask
+}
+
+impl ShapeAccumulator {
+    pub fn new(max_shapes: usize, top_n: usize, max_rows: usize) -> Self {
+        Self {
+            shapes: HashMap::new(),
+            total_count: 0,
+            max_shapes,
+            top_n,
+            max_rows,
+            stored_rows: 0,
+        }
+    }
+
+    /// Records a trimmed, non-null value found at `row_index` (1-based).
+    pub fn update(&mut self, value: &str, row_index: usize) {
+        self.total_count += 1;
+        let shape = shape_of(value);
+        if !self.shapes.contains_key(&shape) {
+            if self.shapes.len() >= self.max_shapes {
+                return;
+            }
+            self.shapes.insert(shape.clone(), ShapeCount::default());
+        }
+        let entry = self.shapes.get_mut(&shape).unwrap();
+        entry.count += 1;
+        if entry.examples.len() < MAX_EXAMPLES && !entry.examples.iter().any(|e| e == value) {
+            entry.examples.push(value.to_string());
+        }
+        if entry.rows.len() < self.max_rows && self.stored_rows < 2 * self.max_rows {
+            entry.rows.push(row_index);
+            self.stored_rows += 1;
+        }
+    }
+
+    /// Adds the shapes of `other`, whose row indices are shifted by
+    /// `row_offset`. New shapes are kept while there is room, as in `update`,
+    /// and rows are added in row order so the same rows are kept.
+    pub fn merge(&mut self, other: &ShapeAccumulator, row_offset: usize) {
+        self.total_count += other.total_count;
+        let mut rows: Vec<(usize, &String)> = Vec::new();
+        for (shape, theirs) in &other.shapes {
+            if !self.shapes.contains_key(shape) {
+                if self.shapes.len() >= self.max_shapes {
+                    continue;
+                }
+                self.shapes.insert(shape.clone(), ShapeCount::default());
+            }
+            let entry = self.shapes.get_mut(shape).unwrap();
+            entry.count += theirs.count;
+            for example in &theirs.examples {
+                if entry.examples.len() < MAX_EXAMPLES && !entry.examples.contains(example) {
+                    entry.examples.push(example.clone());
+                }
+            }
+            rows.extend(theirs.rows.iter().map(|&row| (row + row_offset, shape)));
+        }
+        rows.sort_unstable();
+        for (row, shape) in rows {
+            if self.stored_rows >= 2 * self.max_rows {
+                break;
+            }
+            let entry = self.shapes.get_mut(shape).unwrap();
+            if entry.rows.len() < self.max_rows {
+                entry.rows.push(row);
+                self.stored_rows += 1;
+            }
+        }
+    }
+
+    pub fn finalize(&self) -> Option<ShapeStats> {
+        if self.total_count == 0 {
+            return None;
+        }
+        let mut ranked: Vec<(&String, &ShapeCount)> = self.shapes.iter().collect();
+        // Ties broken by shape so the report does not depend on hash order
+        ranked.sort_by(|a, b| b.1.count.cmp(&a.1.count).then_with(|| a.0.cmp(b.0)));
+
+        let (dominant_format, dominant_count) = match ranked.first() {
+            Some((shape, top)) if top.count as f64 / self.total_count as f64 >= DOMINANT_SHARE => {
+                (Some((*shape).clone()), top.count)
+            }
+            _ => (None, 0),
+        };
+        let mut nonconforming_rows = Vec::new();
+        if dominant_format.is_some() {
+            nonconforming_rows = ranked.iter().skip(1).flat_map(|(_, entry)| entry.rows.iter().copied()).collect();
+            nonconforming_rows.sort_unstable();
+            nonconforming_rows.truncate(self.max_rows);
+        }
+
+        Some(ShapeStats {
+            top_shapes: ranked.iter().take(self.top_n).map(|(shape, entry)| ShapeEntry {
+                shape: (*shape).clone(),
+                count: entry.count,
+                percentage: (entry.count as f64 / self.total_count as f64) * 100.0,
+                examples: entry.examples.clone(),
+            }).collect(),
+            distinct_shapes: self.shapes.len() as u64,
+            dominant_format,
+            dominant_ratio: dominant_count as f64 / self.total_count as f64,
+            nonconforming_count: if dominant_count > 0 { self.total_count - dominant_count } else { 0 },
+            nonconforming_rows,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shape_masks() {
+        assert_eq!(shape_of("ABC-1234"), "AAA-9{4}");
+        assert_eq!(shape_of("John Smith"), "Aa+ Aa+");
+        assert_eq!(shape_of("123-45-6789"), "9{3}-9{2}-9{4}");
+        assert_eq!(shape_of("4111111111111111"), "9{16}");
+        assert_eq!(shape_of("ABCDEFG 1"), "A{7} 9");
+        assert_eq!(shape_of("iPhone  15"), "aAa+ 9{2}");
+        assert_eq!(shape_of("東京"), "L+");
+        assert!(shape_of(&"x-".repeat(100)).ends_with('…'));
+    }
+
+    #[test]
+    fn test_dominant_format_and_nonconforming_rows() {
+        let values = ["AB-1234", "CD-5678", "EF-9012", "gh-3456", "IJ1234", "KL-7890"];
+        let mut first = ShapeAccumulator::new(100, 10, 100);
+        let mut second = ShapeAccumulator::new(100, 10, 100);
+        for (i, value) in values.iter().enumerate() {
+            if i < 3 { first.update(value, i + 1) } else { second.update(value, i - 2) }
+        }
+        first.merge(&second, 3);
+
+        let stats = first.finalize().unwrap();
+        assert_eq!(stats.dominant_format.as_deref(), Some("AA-9{4}"));
+        assert_eq!(stats.top_shapes[0].examples, vec!["AB-1234", "CD-5678", "EF-9012"]);
+        assert_eq!(stats.nonconforming_count, 2);
+        assert_eq!(stats.nonconforming_rows, vec![4, 5]);
+        assert_eq!(stats.distinct_shapes, 3);
+    }
+
+    #[test]
+    fn test_row_lists_share_one_budget() {
+        let mut first = ShapeAccumulator::new(100, 10, 3);
+        let mut second = ShapeAccumulator::new(100, 10, 3);
+        // Every fifth row has its own shape, the others share the dominant one
+        for row in 1..=40 {
+            let value = if row % 5 == 0 { "1".repeat(row / 5 + 1) } else { "AB-12".to_string() };
+            if row <= 20 { first.update(&value, row) } else { second.update(&value, row - 20) }
+        }
+        assert!(second.stored_rows <= 6);
+        first.merge(&second, 20);
+
+        assert!(first.stored_rows <= 6);
+        let stats = first.finalize().unwrap();
+        assert_eq!(stats.dominant_format.as_deref(), Some("AA-9{2}"));
+        assert_eq!(stats.nonconforming_count, 8);
+        assert_eq!(stats.nonconforming_rows, vec![5, 10, 15]);
+    }
+}
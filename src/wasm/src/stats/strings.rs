@@ -0,0 +1,296 @@
+//! Length, character and script statistics for string columns.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use ts_rs::TS;
+use crate::stats::histogram::HistogramBin;
+
+/// Bins in the length histogram when there are more distinct lengths.
+const LENGTH_BINS: usize = 20;
+
+/// Share of all characters (of trimmed values) in each class. A character
+/// can be in several classes, e.g. "é" is both alphabetic and non-ASCII.
+#[derive(Serialize, Debug, Clone, PartialEq, TS)]
+#[ts(export)]
+pub struct CharClassRatios {
+    pub alpha: f64,
+    pub digit: f64,
+    /// Punctuation and symbols
+    pub punctuation: f64,
+    pub whitespace: f64,
+    pub non_ascii: f64,
+}
+
+/// Values by letter case; values without cased letters are `uncased`.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, TS)]
+#[ts(export)]
+pub struct CaseProfile {
+    pub upper: u64,
+    pub lower: u64,
+    /// Every word capitalized, e.g. "New York"
+    pub title: u64,
+    pub mixed: u64,
+    pub uncased: u64,
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq, TS)]
+#[ts(export)]
+pub struct ScriptCount {
+    pub script: String,
+    /// Letters in this script
+    pub count: u64,
+}
+
+#[derive(Serialize, Debug, Clone, TS)]
+#[ts(export)]
+pub struct StringStats {
+    /// Lengths are in characters, after trimming
+    pub mean_length: f64,
+    pub median_length: f64,
+    pub length_histogram: Vec<HistogramBin>,
+    pub char_classes: CharClassRatios,
+    /// Values starting or ending with whitespace
+    pub leading_whitespace: u64,
+    pub trailing_whitespace: u64,
+    pub case_profile: CaseProfile,
+    /// C0 and C1 control characters other than tab, newline and carriage return
+    pub control_chars: u64,
+    /// Invisible formatting characters: zero-width spaces and joiners,
+    /// direction marks, soft hyphens and byte order marks
+    pub non_printable_chars: u64,
+    /// Scripts of the letters seen, most frequent first
+    pub scripts: Vec<ScriptCount>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct StringAccumulator {
+    // Values by length in characters
+    lengths: BTreeMap<usize, u64>,
+    chars: u64,
+    alpha: u64,
+    digit: u64,
+    punctuation: u64,
+    whitespace: u64,
+    non_ascii: u64,
+    leading_whitespace: u64,
+    trailing_whitespace: u64,
+    case_profile: CaseProfile,
+    control_chars: u64,
+    non_printable_chars: u64,
+    scripts: BTreeMap<String, u64>,
+}
+
+impl StringAccumulator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records a non-null value as read, before trimming.
+    pub fn update(&mut self, value: &str) {
+        let trimmed = value.trim();
+        if trimmed.len() != value.len() {
+            if value.starts_with(char::is_whitespace) { self.leading_whitespace += 1; }
+            if value.ends_with(char::is_whitespace) { self.trailing_whitespace += 1; }
+        }
+
+        let mut length = 0;
+        let (mut upper, mut lower, mut title) = (false, false, true);
+        let mut word_start = true;
+        for c in trimmed.chars() {
+            length += 1;
+            if c.is_alphabetic() {
+                self.alpha += 1;
+                if let Some(script) = script_of(c) {
+                    // Allocates the key only for a script's first letter
+                    match self.scripts.get_mut(script) {
+                        Some(count) => *count += 1,
+                        None => { self.scripts.insert(script.to_string(), 1); }
+                    }
+                }
+            } else if c.is_numeric() {
+                self.digit += 1;
+            } else if c.is_whitespace() {
+                self.whitespace += 1;
+            } else if c.is_control() {
+                if !matches!(c, '\t' | '\n' | '\r') {
+                    self.control_chars += 1;
+                }
+            } else if is_invisible(c) {
+                self.non_printable_chars += 1;
+            } else {
+                self.punctuation += 1;
+            }
+            if !c.is_ascii() {
+                self.non_ascii += 1;
+            }
+
+            if c.is_uppercase() {
+                upper = true;
+                title &= word_start;
+            } else if c.is_lowercase() {
+                lower = true;
+                title &= !word_start;
+            }
+            word_start = c.is_whitespace() || c == '-';
+        }
+        self.chars += length as u64;
+        *self.lengths.entry(length).or_insert(0) += 1;
+
+        let case = &mut self.case_profile;
+        match (upper, lower) {
+            (false, false) => case.uncased += 1,
+            (true, false) => case.upper += 1,
+            (false, true) => case.lower += 1,
+            (true, true) if title => case.title += 1,
+            (true, true) => case.mixed += 1,
+        }
+    }
+
+    pub fn merge(&mut self, other: &StringAccumulator) {
+        for (&length, &count) in &other.lengths {
+            *self.lengths.entry(length).or_insert(0) += count;
+        }
+        for (script, &count) in &other.scripts {
+            *self.scripts.entry(script.clone()).or_insert(0) += count;
+        }
+        self.chars += other.chars;
+        self.alpha += other.alpha;
+        self.digit += other.digit;
+        self.punctuation += other.punctuation;
+        self.whitespace += other.whitespace;
+        self.non_ascii += other.non_ascii;
+        self.leading_whitespace += other.leading_whitespace;
+        self.trailing_whitespace += other.trailing_whitespace;
+        self.control_chars += other.control_chars;
+        self.non_printable_chars += other.non_printable_chars;
+        let (case, theirs) = (&mut self.case_profile, &other.case_profile);
+        case.upper += theirs.upper;
+        case.lower += theirs.lower;
+        case.title += theirs.title;
+        case.mixed += theirs.mixed;
+        case.uncased += theirs.uncased;
+    }
+
+    pub fn finalize(&self) -> Option<StringStats> {
+        let values: u64 = self.lengths.values().sum();
+        if values == 0 {
+            return None;
+        }
+        let ratio = |count: u64| if self.chars == 0 { 0.0 } else { count as f64 / self.chars as f64 };
+
+        let mut scripts: Vec<ScriptCount> = self.scripts.iter()
+            .map(|(script, &count)| ScriptCount { script: script.clone(), count })
+            .collect();
+        scripts.sort_by_key(|s| std::cmp::Reverse(s.count));
+
+        Some(StringStats {
+            mean_length: self.chars as f64 / values as f64,
+            median_length: self.median_length(values),
+            length_histogram: self.length_histogram(),
+            char_classes: CharClassRatios {
+                alpha: ratio(self.alpha),
+                digit: ratio(self.digit),
+                punctuation: ratio(self.punctuation),
+                whitespace: ratio(self.whitespace),
+                non_ascii: ratio(self.non_ascii),
+            },
+            leading_whitespace: self.leading_whitespace,
+            trailing_whitespace: self.trailing_whitespace,
+            case_profile: self.case_profile.clone(),
+            control_chars: self.control_chars,
+            non_printable_chars: self.non_printable_chars,
+            scripts,
+        })
+    }
+
+    fn median_length(&self, values: u64) -> f64 {
+        // Lengths at 0-based ranks (n-1)/2 and n/2, averaged for even counts
+        let nth = |rank: u64| {
+            let mut seen = 0;
+            for (&length, &count) in &self.lengths {
+                seen += count;
+                if seen > rank {
+                    return length as f64;
+                }
+            }
+            0.0
+        };
+        (nth((values - 1) / 2) + nth(values / 2)) / 2.0
+    }
+
+    fn length_histogram(&self) -> Vec<HistogramBin> {
+        let (min, max) = match (self.lengths.keys().next(), self.lengths.keys().next_back()) {
+            (Some(&min), Some(&max)) => (min, max),
+            _ => return Vec::new(),
+        };
+        let width = (max - min) / LENGTH_BINS + 1;
+        let mut bins: Vec<HistogramBin> = Vec::new();
+        for (&length, &count) in &self.lengths {
+            let start = min + (length - min) / width * width;
+            match bins.last_mut() {
+                Some(bin) if bin.start == start as f64 => bin.count += count,
+                _ => bins.push(HistogramBin { start: start as f64, end: (start + width) as f64, count }),
+            }
+        }
+        bins
+    }
+}
+
+fn is_invisible(c: char) -> bool {
+    matches!(c, '\u{00AD}' | '\u{200B}'..='\u{200F}' | '\u{202A}'..='\u{202E}' | '\u{2060}'..='\u{2064}' | '\u{FEFF}')
+}
+
+/// Script of a letter, by Unicode block. Letters outside the listed blocks
+/// are not counted.
+fn script_of(c: char) -> Option<&'static str> {
+    let script = match c as u32 {
+        0x0041..=0x024F | 0x1E00..=0x1EFF => "Latin",
+        0x0370..=0x03FF | 0x1F00..=0x1FFF => "Greek",
+        0x0400..=0x052F => "Cyrillic",
+        0x0530..=0x058F => "Armenian",
+        0x0590..=0x05FF => "Hebrew",
+        0x0600..=0x06FF | 0x0750..=0x077F => "Arabic",
+        0x0900..=0x097F => "Devanagari",
+        0x0E00..=0x0E7F => "Thai",
+        0x10A0..=0x10FF => "Georgian",
+        0x3040..=0x30FF => "Kana",
+        0x1100..=0x11FF | 0x3130..=0x318F | 0xAC00..=0xD7AF => "Hangul",
+        0x3400..=0x4DBF | 0x4E00..=0x9FFF | 0xF900..=0xFAFF | 0x20000..=0x2FA1F => "CJK",
+        _ => return None,
+    };
+    Some(script)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stats_of(values: &[&str]) -> StringStats {
+        let mut acc = StringAccumulator::new();
+        for value in values {
+            acc.update(value);
+        }
+        acc.finalize().unwrap()
+    }
+
+    #[test]
+    fn test_lengths_and_case() {
+        let stats = stats_of(&["New York", " paris", "LONDON ", "mIxEd", "42"]);
+        assert_eq!(stats.median_length, 5.0);
+        assert!((stats.mean_length - 26.0 / 5.0).abs() < 1e-9);
+        assert_eq!((stats.leading_whitespace, stats.trailing_whitespace), (1, 1));
+        assert_eq!(stats.case_profile, CaseProfile { upper: 1, lower: 1, title: 1, mixed: 1, uncased: 1 });
+        assert_eq!(stats.length_histogram.iter().map(|b| b.count).sum::<u64>(), 5);
+        assert!((stats.char_classes.digit - 2.0 / 26.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_scripts_and_hidden_characters() {
+        let stats = stats_of(&["Москва", "東京", "Zürich\u{200B}", "bad\u{0007}"]);
+        let scripts: Vec<(&str, u64)> = stats.scripts.iter().map(|s| (s.script.as_str(), s.count)).collect();
+        assert_eq!(scripts, vec![("Latin", 9), ("Cyrillic", 6), ("CJK", 2)]);
+        assert_eq!(stats.non_printable_chars, 1);
+        assert_eq!(stats.control_chars, 1);
+        assert!(stats.char_classes.non_ascii > 0.0);
+    }
+}
@@ -0,0 +1,435 @@
+//! Statistics for date and timestamp columns.
+
+use chrono::{DateTime, Datelike, NaiveDate, NaiveDateTime, Timelike};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use ts_rs::TS;
+
+/// Above this many occupied buckets, buckets are widened to the next
+/// `BucketWidth`, which bounds the memory of an accumulator.
+const MAX_BUCKETS: usize = 50_000;
+/// Largest gaps reported per column.
+const MAX_GAPS: usize = 5;
+
+const HOUR: i64 = 3_600;
+const DAY: i64 = 86_400;
+
+/// The finest unit in which the values of a column vary.
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, TS)]
+#[serde(rename_all = "lowercase")]
+#[ts(export)]
+pub enum TemporalGranularity {
+    Yearly,
+    Monthly,
+    Daily,
+    Hourly,
+    Minutely,
+    Secondly,
+}
+
+impl TemporalGranularity {
+    /// The longest step between consecutive periods, in seconds.
+    fn step(self) -> i64 {
+        match self {
+            TemporalGranularity::Yearly => 366 * DAY,
+            TemporalGranularity::Monthly => 31 * DAY,
+            TemporalGranularity::Daily => DAY,
+            TemporalGranularity::Hourly => HOUR,
+            TemporalGranularity::Minutely => 60,
+            TemporalGranularity::Secondly => 1,
+        }
+    }
+}
+
+/// Width of the buckets values are counted in. Each width nests in the next,
+/// so buckets can be widened by re-keying them.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, PartialOrd)]
+enum BucketWidth {
+    Hour,
+    Day,
+    Month,
+    /// Runs of this many years, aligned to multiples of it (1, 10, 100, ...)
+    Years(i32),
+}
+
+impl BucketWidth {
+    fn wider(self) -> Self {
+        match self {
+            BucketWidth::Hour => BucketWidth::Day,
+            BucketWidth::Day => BucketWidth::Month,
+            BucketWidth::Month => BucketWidth::Years(1),
+            BucketWidth::Years(n) => BucketWidth::Years(n.saturating_mul(10)),
+        }
+    }
+
+    /// The longest bucket, in seconds.
+    fn seconds(self) -> i64 {
+        match self {
+            BucketWidth::Hour => HOUR,
+            BucketWidth::Day => DAY,
+            BucketWidth::Month => 31 * DAY,
+            BucketWidth::Years(n) => n as i64 * 366 * DAY,
+        }
+    }
+
+    /// Start of the bucket holding `value`.
+    fn start(self, value: NaiveDateTime) -> NaiveDateTime {
+        let midnight = chrono::NaiveTime::MIN;
+        match self {
+            BucketWidth::Hour => value.date().and_hms_opt(value.hour(), 0, 0).unwrap_or(value),
+            BucketWidth::Day => value.date().and_time(midnight),
+            BucketWidth::Month => NaiveDate::from_ymd_opt(value.year(), value.month(), 1)
+                .map_or(value, |d| d.and_time(midnight)),
+            // Clamped, as the aligned year of the earliest dates is out of range
+            BucketWidth::Years(n) => NaiveDate::from_ymd_opt(value.year().div_euclid(n) * n, 1, 1)
+                .or_else(|| NaiveDate::from_ymd_opt(value.year(), 1, 1))
+                .map_or(value, |d| d.and_time(midnight)),
+        }
+    }
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq, TS)]
+#[ts(export)]
+pub struct TemporalBin {
+    /// Start of the period, ISO 8601
+    pub start: String,
+    pub count: u64,
+}
+
+/// A stretch without values between two occupied periods.
+#[derive(Serialize, Debug, Clone, PartialEq, TS)]
+#[ts(export)]
+pub struct TemporalGap {
+    /// Last period with values before the gap, ISO 8601
+    pub from: String,
+    /// First period with values after the gap, ISO 8601
+    pub to: String,
+    pub seconds: i64,
+}
+
+#[derive(Serialize, Debug, Clone, TS)]
+#[ts(export)]
+pub struct TemporalStats {
+    /// Earliest and latest value, ISO 8601 (UTC for values with an offset)
+    pub min: String,
+    pub max: String,
+    pub span_seconds: i64,
+    pub granularity: TemporalGranularity,
+    /// Counts by weekday, Monday first
+    pub by_day_of_week: Vec<u64>,
+    /// Counts by hour of day, 0 to 23
+    pub by_hour: Vec<u64>,
+    /// Counts by month, January first
+    pub by_month: Vec<u64>,
+    /// "hour", "day", "month" or "year", chosen from the span
+    pub histogram_period: String,
+    pub histogram: Vec<TemporalBin>,
+    /// Largest gaps longer than one `granularity` step, largest first.
+    /// Measured between hour buckets, which are widened to days, months and
+    /// years when the values fill too many of them
+    pub largest_gaps: Vec<TemporalGap>,
+    /// Values after `ProfilerConfig::reference_time`; None when it is not set
+    pub future_count: Option<u64>,
+    /// Values before 1900, after 2100, or a placeholder such as 1900-01-01
+    /// or 1970-01-01T00:00:00
+    pub implausible_count: u64,
+}
+
+/// Accumulates `TemporalStats` from parsed values, in memory bounded by
+/// `MAX_BUCKETS`.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TemporalAccumulator {
+    min: Option<NaiveDateTime>,
+    max: Option<NaiveDateTime>,
+    // Values after it count as future values
+    reference_time: Option<NaiveDateTime>,
+    future: u64,
+    by_day_of_week: [u64; 7],
+    by_hour: [u64; 24],
+    by_month: [u64; 12],
+    // Value counts keyed by bucket start (epoch seconds)
+    buckets: BTreeMap<i64, u64>,
+    width: BucketWidth,
+    implausible: u64,
+    // Finest component in which any value differs from the period start
+    any_second: bool,
+    any_minute: bool,
+    any_hour: bool,
+    any_day: bool,
+    any_month: bool,
+}
+
+impl TemporalAccumulator {
+    /// `reference_time` is `ProfilerConfig::reference_time`.
+    pub fn new(reference_time: Option<NaiveDateTime>) -> Self {
+        Self {
+            min: None,
+            max: None,
+            reference_time,
+            future: 0,
+            by_day_of_week: [0; 7],
+            by_hour: [0; 24],
+            by_month: [0; 12],
+            buckets: BTreeMap::new(),
+            width: BucketWidth::Hour,
+            implausible: 0,
+            any_second: false,
+            any_minute: false,
+            any_hour: false,
+            any_day: false,
+            any_month: false,
+        }
+    }
+
+    pub fn update(&mut self, value: NaiveDateTime) {
+        if self.min.is_none_or(|min| value < min) { self.min = Some(value); }
+        if self.max.is_none_or(|max| value > max) { self.max = Some(value); }
+
+        self.by_day_of_week[value.weekday().num_days_from_monday() as usize] += 1;
+        self.by_hour[value.hour() as usize] += 1;
+        self.by_month[value.month0() as usize] += 1;
+
+        self.any_second |= value.second() != 0 || value.nanosecond() != 0;
+        self.any_minute |= value.minute() != 0;
+        self.any_hour |= value.hour() != 0;
+        self.any_day |= value.day() != 1;
+        self.any_month |= value.month() != 1;
+
+        if is_implausible(value) {
+            self.implausible += 1;
+        }
+        if self.reference_time.is_some_and(|now| value > now) {
+            self.future += 1;
+        }
+
+        *self.buckets.entry(seconds(self.width.start(value))).or_insert(0) += 1;
+        self.limit_buckets();
+    }
+
+    pub fn merge(&mut self, other: &TemporalAccumulator) {
+        for value in other.min.iter().chain(other.max.iter()) {
+            if self.min.is_none_or(|min| *value < min) { self.min = Some(*value); }
+            if self.max.is_none_or(|max| *value > max) { self.max = Some(*value); }
+        }
+        for (mine, theirs) in self.by_day_of_week.iter_mut().zip(&other.by_day_of_week) { *mine += theirs; }
+        for (mine, theirs) in self.by_hour.iter_mut().zip(&other.by_hour) { *mine += theirs; }
+        for (mine, theirs) in self.by_month.iter_mut().zip(&other.by_month) { *mine += theirs; }
+
+        self.any_second |= other.any_second;
+        self.any_minute |= other.any_minute;
+        self.any_hour |= other.any_hour;
+        self.any_day |= other.any_day;
+        self.any_month |= other.any_month;
+        self.implausible += other.implausible;
+        self.future += other.future;
+
+        while other.width > self.width {
+            self.widen_buckets();
+        }
+        for (&start, &count) in &other.buckets {
+            *self.buckets.entry(seconds(self.width.start(from_seconds(start)))).or_insert(0) += count;
+        }
+        self.limit_buckets();
+    }
+
+    /// Statistics, or `None` if no value was recorded.
+    pub fn finalize(&self) -> Option<TemporalStats> {
+        let (min, max) = (self.min?, self.max?);
+        let span_seconds = (max - min).num_seconds();
+        let granularity = self.granularity();
+
+        // The period follows the span, but is never finer than the buckets
+        let period = if span_seconds <= 2 * DAY {
+            BucketWidth::Hour
+        } else if span_seconds <= 92 * DAY {
+            BucketWidth::Day
+        } else if span_seconds <= 6 * 366 * DAY {
+            BucketWidth::Month
+        } else {
+            BucketWidth::Years(1)
+        };
+        let period = if period > self.width { period } else { self.width };
+        let histogram_period = match period {
+            BucketWidth::Hour => "hour",
+            BucketWidth::Day => "day",
+            BucketWidth::Month => "month",
+            BucketWidth::Years(_) => "year",
+        };
+        let mut bins: BTreeMap<NaiveDateTime, u64> = BTreeMap::new();
+        for (&start, &count) in &self.buckets {
+            *bins.entry(period.start(from_seconds(start))).or_insert(0) += count;
+        }
+        let histogram = bins.into_iter().map(|(start, count)| TemporalBin { start: iso(start), count }).collect();
+
+        let step = granularity.step().max(self.width.seconds());
+        let mut gaps: Vec<TemporalGap> = self.buckets.keys().zip(self.buckets.keys().skip(1))
+            .filter(|(from, to)| *to - *from > step)
+            .map(|(&from, &to)| TemporalGap { from: iso(from_seconds(from)), to: iso(from_seconds(to)), seconds: to - from })
+            .collect();
+        gaps.sort_by_key(|gap| std::cmp::Reverse(gap.seconds));
+        gaps.truncate(MAX_GAPS);
+
+        let future_count = self.reference_time.map(|_| self.future);
+
+        Some(TemporalStats {
+            min: iso(min),
+            max: iso(max),
+            span_seconds,
+            granularity,
+            by_day_of_week: self.by_day_of_week.to_vec(),
+            by_hour: self.by_hour.to_vec(),
+            by_month: self.by_month.to_vec(),
+            histogram_period: histogram_period.to_string(),
+            histogram,
+            largest_gaps: gaps,
+            future_count,
+            implausible_count: self.implausible,
+        })
+    }
+
+    fn granularity(&self) -> TemporalGranularity {
+        if self.any_second {
+            TemporalGranularity::Secondly
+        } else if self.any_minute {
+            TemporalGranularity::Minutely
+        } else if self.any_hour {
+            TemporalGranularity::Hourly
+        } else if self.any_day {
+            TemporalGranularity::Daily
+        } else if self.any_month {
+            TemporalGranularity::Monthly
+        } else {
+            TemporalGranularity::Yearly
+        }
+    }
+
+    fn limit_buckets(&mut self) {
+        while self.buckets.len() > MAX_BUCKETS {
+            self.widen_buckets();
+        }
+    }
+
+    fn widen_buckets(&mut self) {
+        let narrow = std::mem::take(&mut self.buckets);
+        self.width = self.width.wider();
+        for (start, count) in narrow {
+            *self.buckets.entry(seconds(self.width.start(from_seconds(start)))).or_insert(0) += count;
+        }
+    }
+}
+
+fn is_implausible(value: NaiveDateTime) -> bool {
+    let placeholder = (value.year(), value.month(), value.day()) == (1900, 1, 1)
+        || value.and_utc().timestamp() == 0;
+    placeholder || !(1900..=2100).contains(&value.year())
+}
+
+fn seconds(value: NaiveDateTime) -> i64 {
+    value.and_utc().timestamp()
+}
+
+fn from_seconds(seconds: i64) -> NaiveDateTime {
+    DateTime::from_timestamp(seconds, 0).map(|t| t.naive_utc()).unwrap_or_default()
+}
+
+fn iso(value: NaiveDateTime) -> String {
+    if value.nanosecond() == 0 {
+        value.format("%Y-%m-%dT%H:%M:%S").to_string()
+    } else {
+        value.format("%Y-%m-%dT%H:%M:%S%.f").to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(value: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M:%S").unwrap()
+    }
+
+    #[test]
+    fn test_daily_series_with_gap() {
+        let mut acc = TemporalAccumulator::new(Some(at("2026-03-05 12:00:00")));
+        for day in [1, 2, 3, 4, 9, 10] {
+            acc.update(at(&format!("2026-03-{:02} 00:00:00", day)));
+        }
+        acc.update(at("1900-01-01 00:00:00"));
+        let stats = acc.finalize().unwrap();
+
+        assert_eq!(stats.min, "1900-01-01T00:00:00");
+        assert_eq!(stats.max, "2026-03-10T00:00:00");
+        assert_eq!(stats.granularity, TemporalGranularity::Daily);
+        assert_eq!(stats.implausible_count, 1);
+        assert_eq!(stats.future_count, Some(2));
+        assert!(TemporalAccumulator::new(None).finalize().is_none());
+        assert_eq!(stats.histogram_period, "year");
+        // 2026-03-01 is a Sunday
+        assert_eq!(stats.by_day_of_week[6], 1);
+        assert_eq!(stats.by_month[2], 6);
+
+        assert_eq!(stats.largest_gaps[0].from, "1900-01-01T00:00:00");
+        assert_eq!(stats.largest_gaps[1], TemporalGap {
+            from: "2026-03-04T00:00:00".to_string(),
+            to: "2026-03-09T00:00:00".to_string(),
+            seconds: 5 * DAY,
+        });
+        assert_eq!(stats.largest_gaps.len(), 2);
+    }
+
+    #[test]
+    fn test_hourly_histogram_and_merge() {
+        let now = Some(at("2030-01-01 00:00:00"));
+        let mut first = TemporalAccumulator::new(now);
+        let mut second = TemporalAccumulator::new(now);
+        for hour in 0..6 {
+            first.update(at(&format!("2026-10-16 {:02}:00:00", hour)));
+            second.update(at(&format!("2026-10-16 {:02}:00:00", hour + 6)));
+        }
+        first.merge(&second);
+        let stats = first.finalize().unwrap();
+
+        assert_eq!(stats.granularity, TemporalGranularity::Hourly);
+        assert_eq!(stats.histogram_period, "hour");
+        assert_eq!(stats.histogram.len(), 12);
+        assert_eq!(stats.span_seconds, 11 * HOUR);
+        assert!(stats.largest_gaps.is_empty());
+        assert_eq!(stats.by_hour[11], 1);
+    }
+
+    #[test]
+    fn test_future_values_within_a_bucket() {
+        let mut acc = TemporalAccumulator::new(Some(at("2026-03-05 12:00:00")));
+        for value in ["2026-03-05 11:59:59", "2026-03-05 12:00:00", "2026-03-05 12:30:00"] {
+            acc.update(at(value));
+        }
+        assert_eq!(acc.finalize().unwrap().future_count, Some(1));
+
+        let mut unset = TemporalAccumulator::new(None);
+        unset.update(at("2999-01-01 00:00:00"));
+        assert_eq!(unset.finalize().unwrap().future_count, None);
+    }
+
+    #[test]
+    fn test_buckets_widen_to_stay_bounded() {
+        let start = at("2000-01-01 00:00:00");
+        let mut hourly = TemporalAccumulator::new(None);
+        for hour in 0..60_000 {
+            hourly.update(start + chrono::Duration::hours(hour));
+        }
+        assert_eq!(hourly.width, BucketWidth::Day);
+        assert_eq!(hourly.buckets.len(), 2_500);
+
+        let mut centuries = TemporalAccumulator::new(None);
+        for day in 0..(MAX_BUCKETS as i64 + 10) {
+            centuries.update(at("1800-01-01 00:00:00") + chrono::Duration::days(day));
+        }
+        centuries.merge(&hourly);
+        assert_eq!(centuries.width, BucketWidth::Month);
+        assert!(centuries.buckets.len() <= MAX_BUCKETS);
+
+        let stats = centuries.finalize().unwrap();
+        assert_eq!(stats.histogram_period, "year");
+        assert_eq!(stats.histogram.iter().map(|bin| bin.count).sum::<u64>(), 60_000 + MAX_BUCKETS as u64 + 10);
+    }
+}
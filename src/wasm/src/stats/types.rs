@@ -9,9 +9,52 @@ pub enum DataType {
     String,
     Boolean,
     Date,
+    /// Date and time of day, with or without a timezone
+    Timestamp,
+    /// Time of day without a date
+    Time,
     Null,
 }
 
+/// Semantic type declared by a source schema (e.g. Avro logical types), as
+/// opposed to the type inferred from the values themselves.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, TS)]
+#[serde(tag = "kind", rename_all = "kebab-case")]
+#[ts(export)]
+pub enum LogicalType {
+    Decimal { precision: usize, scale: usize },
+    Date,
+    TimeMillis,
+    TimeMicros,
+    TimestampMillis,
+    TimestampMicros,
+    LocalTimestampMillis,
+    LocalTimestampMicros,
+    Uuid,
+    Duration,
+}
+
+impl LogicalType {
+    /// The data type reported for a column declared with this logical type.
+    pub fn data_type(&self) -> DataType {
+        match self {
+            LogicalType::Decimal { .. } => DataType::Numeric,
+            LogicalType::Date => DataType::Date,
+            LogicalType::TimestampMillis
+            | LogicalType::TimestampMicros
+            | LogicalType::LocalTimestampMillis
+            | LogicalType::LocalTimestampMicros => DataType::Timestamp,
+            LogicalType::TimeMillis | LogicalType::TimeMicros => DataType::Time,
+            LogicalType::Uuid | LogicalType::Duration => DataType::String,
+        }
+    }
+
+    /// Whether values are calendar dates or instants, rendered as ISO 8601 strings.
+    pub fn is_date_like(&self) -> bool {
+        matches!(self.data_type(), DataType::Date | DataType::Timestamp)
+    }
+}
+
 #[derive(Serialize, Debug, Clone, TS)]
 #[ts(export)]
 pub struct BaseStats {